@@ -150,7 +150,7 @@ impl InputTty {
             //https://docs.rs/libc/0.2.112/libc/fn.select.html
             let sel = libc::select(
                 fd + 1,
-                ptr_read_set as *mut libc::fd_set,
+                ptr_read_set,
                 std::ptr::null_mut(),
                 std::ptr::null_mut(),
                 &mut tv as *mut libc::timeval,
@@ -1,10 +1,12 @@
 //! # RTWins full demo app
 
+// `qemu` feature is set with RUSTFLAGS
+#![allow(unexpected_cfgs)]
 #![cfg_attr(target_os = "none", no_main)]
 #![cfg_attr(target_os = "none", no_std)]
 
 use rtwins::wgt::{WId, WindowState};
-use rtwins::wnd_manager::WindowManager;
+use rtwins::wnd_manager::{Severity, WindowManager};
 use rtwins::{tetrary, wgt, TERM};
 
 // use core::prelude::rust_2021::*;
@@ -26,8 +28,6 @@ mod tui_colors;
 mod tui_commands;
 mod tui_main_def;
 mod tui_main_state;
-mod tui_msgbox_def;
-mod tui_msgbox_state;
 
 #[cfg(target_os = "linux")]
 mod input_libc_tty;
@@ -51,13 +51,13 @@ struct WndMngr {
     cmdque: Rc<RefCell<tui_commands::CommandsQueue>>,
     visible: Vec<WId>,
     main: tui_main_state::MainWndState,
-    msgbox: tui_msgbox_state::MsgBoxState,
+    dialog: rtwins::dialog::DialogState,
 }
 
 impl WndMngr {
     // rtwins::generate_ids!() - starts from 1, but Window ids are indexes and should start from 0
     pub const MAIN: rtwins::wgt::WId = 0;
-    pub const MSGBOX: rtwins::wgt::WId = 1;
+    pub const DIALOG: rtwins::wgt::WId = 1;
 
     fn new() -> Self {
         let cmdque = Rc::new(RefCell::new(tui_commands::CommandsQueue::default()));
@@ -71,15 +71,14 @@ impl WndMngr {
                 &tui_main_def::WND_MAIN_WGTS[..],
                 Rc::clone(&cmdque),
             ),
-            msgbox: tui_msgbox_state::MsgBoxState::new(
-                Self::MSGBOX,
-                &tui_msgbox_def::WND_MSGBOX_WGTS[..],
-                Rc::clone(&cmdque),
-            ),
+            dialog: rtwins::dialog::DialogState::default(),
         };
 
-        ret.msgbox
-            .center_on(ret.main.get_widgets().first().unwrap());
+        let main_area = rtwins::Rect {
+            coord: ret.main.get_window_coord(),
+            size: ret.main.get_window_size(),
+        };
+        ret.dialog.center_on(main_area);
         ret
     }
 }
@@ -106,7 +105,7 @@ impl<'a> Iterator for WndMngrIter<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         let result= match self.iter_idx {
             0 => Some(&self.wm.main as &dyn WindowState),
-            1 => Some(&self.wm.msgbox as &dyn WindowState),
+            1 => Some(&self.wm.dialog as &dyn WindowState),
             _ => None,
         };
 
@@ -121,7 +120,7 @@ impl WindowManager for WndMngr {
     fn get_ref(&self, wnd_id: WId) -> Option<&dyn WindowState> {
         match wnd_id {
             WndMngr::MAIN => Some(&self.main),
-            WndMngr::MSGBOX => Some(&self.msgbox),
+            WndMngr::DIALOG => Some(&self.dialog),
             _ => None,
         }
     }
@@ -129,7 +128,7 @@ impl WindowManager for WndMngr {
     fn get_mut(&mut self, wnd_id: WId) -> Option<&mut dyn WindowState> {
         match wnd_id {
             WndMngr::MAIN => Some(&mut self.main),
-            WndMngr::MSGBOX => Some(&mut self.msgbox),
+            WndMngr::DIALOG => Some(&mut self.dialog),
             _ => None,
         }
     }
@@ -215,8 +214,16 @@ fn tui() {
             coord.row as u16 + sz.height as u16 + 1
             // 1
         };
+        #[cfg(target_os = "linux")]
+        if let Some(size) = pal_std::DemoPal::get_screen_size() {
+            term_guard.screen_size = size;
+        }
         term_guard.write_str(rtwins::esc::TERM_RESET);
-        term_guard.mouse_mode(rtwins::MouseMode::M2);
+        term_guard.mouse_mode(rtwins::MouseMode::M3);
+        term_guard.bracketed_paste(true);
+        term_guard.focus_reporting(true);
+        term_guard.osc52_clipboard(true);
+        term_guard.keyboard_protocol(true);
     }
     else {
         panic!("Could not lock the TERM");
@@ -271,6 +278,19 @@ fn tui() {
     let mut ii = rtwins::input::InputInfo::default();
     let mut mouse_on = true;
 
+    #[cfg(target_os = "linux")]
+    {
+        use rtwins::input_decoder::TermProfile;
+
+        // select additional key sequences of the terminal emulator
+        match std::env::var("TERM").unwrap_or_default().as_str() {
+            t if t.starts_with("rxvt") => dec.set_term_profile(TermProfile::Rxvt),
+            t if t.starts_with("putty") => dec.set_term_profile(TermProfile::Putty),
+            "linux" => dec.set_term_profile(TermProfile::LinuxConsole),
+            _ => dec.set_term_profile(TermProfile::Xterm),
+        }
+    }
+
     #[allow(unused_labels)]
     'mainloop: loop {
         let (inp_seq, q) = inp.read_input();
@@ -280,10 +300,11 @@ fn tui() {
             rtwins::tr_warn!("Exit requested");
             break;
         }
-        else if !inp_seq.is_empty() {
+        else if !inp_seq.is_empty() || !ique.is_empty() {
             ique.extend(inp_seq.iter());
+            let now = TERM.try_lock().unwrap().pal.get_timestamp_ms();
 
-            while dec.decode_input_seq(&mut ique, &mut ii) > 0 {
+            while dec.decode_input_seq_at(&mut ique, &mut ii, now) > 0 {
                 // check for Ctrl+D
                 #[cfg(target_os = "none")]
                 if let InputEvent::Char(ref cb) = ii.evnt {
@@ -297,9 +318,7 @@ fn tui() {
                 use rtwins::input::Key;
 
                 // pass the input event to the top-window
-                let _key_handled = wmngr
-                    .get_top_mut()
-                    .map_or(false, |ws| wgt::process_input(ws, &ii));
+                let _key_handled = wmngr.process_input(&ii);
 
                 // input debug info
                 match ii.evnt {
@@ -309,11 +328,19 @@ fn tui() {
                     InputEvent::Key(ref _k) => {
                         rtwins::tr_debug!("key={}", ii.name);
                     }
+                    InputEvent::Paste(ref text) => {
+                        rtwins::tr_debug!("paste={} chars", text.chars().count());
+                    }
+                    InputEvent::Focus(focused) => {
+                        rtwins::tr_debug!("terminal focus {}", if focused { "IN" } else { "OUT" });
+                    }
                     InputEvent::Mouse(ref m) => {
                         let mut r = rtwins::Rect::cdeflt();
-                        let wgt_opt = wmngr
-                            .get_top_mut()
-                            .map_or(None, |ws| wgt::find_at(ws, m.col, m.row, &mut r));
+                        let wgt_opt = m.coord().and_then(|mc| {
+                            let wnd_id = wmngr.find_window_at(mc.col, mc.row)?;
+                            let ws = wmngr.get_mut(wnd_id)?;
+                            wgt::find_at(ws, mc.col, mc.row, &mut r)
+                        });
 
                         if let Some(w) = wgt_opt {
                             rtwins::tr_debug!(
@@ -350,7 +377,7 @@ fn tui() {
                         let mut term_guard = TERM.try_lock().unwrap();
                         term_guard.mouse_mode(tetrary!(
                             mouse_on,
-                            rtwins::MouseMode::M2,
+                            rtwins::MouseMode::M3,
                             rtwins::MouseMode::Off
                         ));
                         term_guard.flush_buff();
@@ -364,6 +391,26 @@ fn tui() {
                         let mut term_guard = TERM.try_lock().unwrap();
                         term_guard.trace_area_clear();
                     }
+                    else if *key == Key::F7 && wmngr.is_top(WndMngr::MAIN) {
+                        wmngr.dialog.prompt_number(
+                            "Number",
+                            "Enter the value in range -100..100",
+                            0,
+                            -100,
+                            100,
+                        );
+                        wmngr.show_modal(WndMngr::DIALOG);
+                    }
+                    else if *key == Key::F8 && wmngr.is_top(WndMngr::MAIN) {
+                        let items = ["Apple", "Banana", "Cherry", "Grape", "Lemon", "Orange"];
+                        wmngr.dialog.select_item(
+                            "Fruits",
+                            "Select your favourite fruit",
+                            items.iter().map(|it| it.to_string()).collect(),
+                            0,
+                        );
+                        wmngr.show_modal(WndMngr::DIALOG);
+                    }
                     else if ii.kmod.has_ctrl() && (*key == Key::PgUp || *key == Key::PgDown) {
                         if wmngr.is_top(WndMngr::MAIN) {
                             if let Some(main_ws) = wmngr.get_top_mut() {
@@ -407,15 +454,18 @@ fn tui() {
                                     title,
                                     message,
                                     buttons,
-                                    on_button,
+                                    on_result,
                                 } => {
                                     rtwins::tr_info!("Command: ShowPopup");
-                                    wmngr.msgbox.setup(title, message, buttons, on_button);
-                                    wmngr.show(WndMngr::MSGBOX);
+                                    wmngr
+                                        .dialog
+                                        .message(&title, &message, Severity::Info, buttons)
+                                        .on_result(on_result);
+                                    wmngr.show_modal(WndMngr::DIALOG);
                                 }
-                                Command::HidePopup => {
-                                    rtwins::tr_info!("Command: HidePopup");
-                                    wmngr.hide(WndMngr::MSGBOX);
+                                Command::ShowToast { text, severity } => {
+                                    rtwins::tr_info!("Command: ShowToast");
+                                    wmngr.notify(&text, severity, 3000);
                                 }
                             }
                         }
@@ -432,6 +482,9 @@ fn tui() {
             } // decode_input_seq
         }
 
+        // hide expired notifications
+        wmngr.toasts_tick();
+
         // flush the trace logs on every loop
         rtwins::tr_flush!(&mut TERM.try_lock().unwrap());
     }
@@ -440,6 +493,9 @@ fn tui() {
     {
         let mut term_guard = TERM.try_lock().unwrap();
         term_guard.mouse_mode(rtwins::MouseMode::Off);
+        term_guard.bracketed_paste(false);
+        term_guard.focus_reporting(false);
+        term_guard.keyboard_protocol(false);
         rtwins::tr_flush!(&mut term_guard);
 
         term_guard.pal.as_mut().sleep(1_000);
@@ -18,6 +18,23 @@ impl DemoPal {
             started_at: std::time::Instant::now(),
         }
     }
+
+    /// Returns the stdout terminal size, or None if not a terminal
+    pub fn get_screen_size() -> Option<rtwins::Size> {
+        unsafe {
+            let mut ws = std::mem::MaybeUninit::<libc::winsize>::uninit();
+
+            if 0 == libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, ws.as_mut_ptr()) {
+                let ws = ws.assume_init();
+                return Some(rtwins::Size::new(
+                    ws.ws_col.min(u8::MAX as u16) as u8,
+                    ws.ws_row.min(u8::MAX as u16) as u8,
+                ));
+            }
+        }
+
+        None
+    }
 }
 
 impl rtwins::pal::Pal for DemoPal {
@@ -1,9 +1,9 @@
 //! Demo - commands queue
 
-use rtwins::wgt::WId;
+use rtwins::dialog::{DialogButtons, DialogCallback};
+use rtwins::wnd_manager::Severity;
 
 extern crate alloc;
-use alloc::boxed::Box;
 use alloc::string::String;
 use alloc::vec::Vec;
 
@@ -13,10 +13,13 @@ pub enum Command {
     ShowPopup {
         title: String,
         message: String,
-        buttons: &'static str,
-        on_button: Box<dyn Fn(WId) + Send>,
+        buttons: DialogButtons,
+        on_result: DialogCallback,
+    },
+    ShowToast {
+        text: String,
+        severity: Severity,
     },
-    HidePopup,
 }
 
 /// Deferred commands
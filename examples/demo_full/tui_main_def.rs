@@ -144,6 +144,7 @@ const PAGE_PANELS_CHILDREN: &[Widget] = &[
                     title: "FwVer: 1.1",
                     fg_color: ColorFg::Blue,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -155,6 +156,7 @@ const PAGE_PANELS_CHILDREN: &[Widget] = &[
                     title: "",
                     fg_color: ColorFg::Black,
                     bg_color: ColorBg::White,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -166,6 +168,7 @@ const PAGE_PANELS_CHILDREN: &[Widget] = &[
                     title: "",
                     fg_color: ColorFg::White,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -204,6 +207,7 @@ const PAGE_PANELS_CHILDREN: &[Widget] = &[
                     title: "",
                     fg_color: ColorFg::White,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -215,6 +219,7 @@ const PAGE_PANELS_CHILDREN: &[Widget] = &[
                     title: "",
                     fg_color: ColorFg::White,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -318,6 +323,7 @@ const PAGE_LISTBOX_CHILDREN: &[Widget] = &[
                     title: "",
                     fg_color: ColorFg::YellowIntense,
                     bg_color: ColorBg::BlueIntense,
+                    markup: true,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -417,6 +423,7 @@ const PAGE_TEXTEDIT_CHILDREN: &[Widget] = &[
                     title: "Text edit:",
                     fg_color: ColorFg::WhiteIntense,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -428,6 +435,7 @@ const PAGE_TEXTEDIT_CHILDREN: &[Widget] = &[
                     fg_color: ColorFg::Black,
                     bg_color: ColorBgTheme::Edit1.into(),
                     psw_mask: false,
+                    input_mask: "",
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -439,6 +447,7 @@ const PAGE_TEXTEDIT_CHILDREN: &[Widget] = &[
                     title: "Num edit: UP/DOWN + Ctr/Shift:",
                     fg_color: ColorFg::WhiteIntense,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -450,6 +459,7 @@ const PAGE_TEXTEDIT_CHILDREN: &[Widget] = &[
                     fg_color: ColorFg::Black,
                     bg_color: ColorBgTheme::Edit2.into(),
                     psw_mask: false,
+                    input_mask: "",
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -461,6 +471,7 @@ const PAGE_TEXTEDIT_CHILDREN: &[Widget] = &[
                     title: "Password:",
                     fg_color: ColorFg::WhiteIntense,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -472,6 +483,7 @@ const PAGE_TEXTEDIT_CHILDREN: &[Widget] = &[
                     fg_color: ColorFg::Black,
                     bg_color: ColorBgTheme::EditPsw.into(),
                     psw_mask: true,
+                    input_mask: "",
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -497,6 +509,7 @@ const PAGE_TEXTEDIT_CHILDREN: &[Widget] = &[
                     title: "Check list:", // concat!(bold!(), "Check list:", normal!()),
                     fg_color: ColorFg::Blue,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -550,6 +563,8 @@ const PAGE_TXTBOX_CHILDREN: &[Widget] = &[
         prop: prop::TextBox {
             fg_color: ColorFg::White,
             bg_color: ColorBg::BlueIntense,
+            word_wrap: false,
+            markup: false,
         }.into(),
         ..Widget::cdeflt()
     },
@@ -560,6 +575,8 @@ const PAGE_TXTBOX_CHILDREN: &[Widget] = &[
         prop: prop::TextBox {
             fg_color: ColorFg::White,
             bg_color: ColorBg::BlueIntense,
+            word_wrap: true,
+            markup: false,
         }.into(),
         ..Widget::cdeflt()
     },
@@ -656,6 +673,7 @@ const PAGE_INACT_CHILDREN: &[Widget] = &[
                     title: "",
                     fg_color: ColorFg::White,
                     bg_color: ColorBg::BlueIntense,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -692,6 +710,7 @@ const PAGE_INACT_CHILDREN: &[Widget] = &[
                     title: "---",
                     fg_color: ColorFg::Inherit,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -709,6 +728,7 @@ const PAGE_MISC_CHILDREN: &[Widget] = &[
             title: "Custom Widget:",
             fg_color: ColorFg::Inherit,
             bg_color: ColorBg::Inherit,
+            markup: false,
         }.into(),
         ..Widget::cdeflt()
     },
@@ -837,11 +857,13 @@ const WINDOW_MAIN: Widget = Widget {
                     "F4 Mouse On • ",
                     "F5 Refresh • ",
                     "F6 Clr Logs • ",
+                    "F7/F8 Dialog • ",
                     "F9/F10 Page • ",
                     "\u{2581}\u{2582}\u{2583}\u{2584}\u{2585}\u{2586}\u{2587}\u{2588}"
                 ),
                 fg_color: ColorFg::White,
                 bg_color: ColorBgTheme::LabelFtr.into(),
+                markup: false,
             }.into(),
             ..Widget::cdeflt()
         },
@@ -4,6 +4,7 @@
 #![allow(dead_code)]
 
 use rtwins::common::*;
+use rtwins::dialog::{DialogButtons, DialogResult};
 use rtwins::esc;
 use rtwins::input::*;
 use rtwins::string_ext::StringExt;
@@ -15,7 +16,6 @@ use rtwins::*;
 
 use super::tui_commands::*;
 use super::tui_main_def::id;
-use crate::tui_msgbox_def::idmb;
 
 use core::cell::RefCell;
 
@@ -46,10 +46,11 @@ pub struct MainWndState {
     radiogrp1_idx: i16,
     //
     lbx_items: Vec<&'static str>,
+    // number of toasts shown so far
+    toasts_cnt: u16,
     // text box raw source string and source splitted into rows
     tbx_text: String,
-    tbx_wide_lines: utils::StringListRc,
-    tbx_narrow_lines: utils::StringListRc,
+    tbx_lines: utils::StringListRc,
     // app-wide commands queue
     cmds: Rc<RefCell<CommandsQueue>>,
 }
@@ -64,9 +65,9 @@ impl MainWndState {
             invalidated: Vec::with_capacity(4),
             radiogrp1_idx: 1,
             lbx_items: vec![],
+            toasts_cnt: 0,
             tbx_text: String::with_capacity(400),
-            tbx_wide_lines: Arc::new(RefCell::new(vec![])),
-            tbx_narrow_lines: Arc::new(RefCell::new(vec![])),
+            tbx_lines: utils::StringListRc::default(),
             cmds,
         };
 
@@ -144,7 +145,9 @@ impl MainWndState {
             id::TBX_NARROW,
             TxtbxState {
                 top_line: 0,
+                left_col: 0,
                 lines: Default::default(),
+                generation: 0,
             },
         );
         wnd_state
@@ -169,7 +172,7 @@ impl MainWndState {
 // -----------------------------------------------------------------------------------------------
 
 impl rtwins::wgt::WindowState for MainWndState {
-    /** events **/
+    /* events */
 
     fn on_button_down(&mut self, wgt: &Widget, ii: &InputInfo) {
         if wgt.id == id::BTN_YES {
@@ -218,12 +221,12 @@ impl rtwins::wgt::WindowState for MainWndState {
                                 tempor incididunt ut labore et dolore magna aliqua. \
                                 Ut enim ad minim veniam, quis nostrud exercitation ullamco \
                                 laboris nisi ut aliquip ex ea commodo consequat.".to_owned(),
-                            buttons: "ync",
-                            on_button: Box::new(move |btn_id| {
-                                let msg = match btn_id {
-                                    idmb::BTN_YES => "YES",
-                                    idmb::BTN_NO => "NO",
-                                    idmb::BTN_CANCEL => "CANCEL",
+                            buttons: DialogButtons::YesNoCancel,
+                            on_result: Box::new(move |result| {
+                                let msg = match result {
+                                    DialogResult::Yes => "YES",
+                                    DialogResult::No => "NO",
+                                    DialogResult::Cancel => "CANCEL",
                                     _ => ""
                                 };
                                 tr_info!("{}MsgBox callback: {msg}{}", esc::BG_DARK_CYAN, esc::BG_DEFAULT);
@@ -243,6 +246,25 @@ impl rtwins::wgt::WindowState for MainWndState {
         if wgt.id == id::BTN_YES {
             wgt::pagectrl_select_page(self, id::PG_CONTROL, id::PAGE_TEXTBOX);
         }
+
+        if wgt.id == id::BTN_TOASTER {
+            use rtwins::wnd_manager::Severity;
+
+            self.toasts_cnt += 1;
+            let severity = match self.toasts_cnt % 4 {
+                0 => Severity::Info,
+                1 => Severity::Success,
+                2 => Severity::Warning,
+                _ => Severity::Error,
+            };
+
+            if let Ok(ref mut cmds) = self.cmds.try_borrow_mut() {
+                cmds.push(Command::ShowToast {
+                    text: format!("Toast #{} {:?}", self.toasts_cnt, severity),
+                    severity,
+                });
+            }
+        }
     }
 
     fn on_button_key(&mut self, wgt: &Widget, ii: &InputInfo) -> bool {
@@ -271,6 +293,12 @@ impl rtwins::wgt::WindowState for MainWndState {
         false
     }
 
+    fn on_modal_result(&mut self, wnd_id: WId, result: &dyn core::any::Any) {
+        if let Some(result) = result.downcast_ref::<DialogResult>() {
+            rtwins::tr_info!("Modal wnd {} result: {:?}", wnd_id, result);
+        }
+    }
+
     fn on_text_edit_change(&mut self, wgt: &Widget, txt: &mut String) {
         rtwins::tr_debug!("TXT_EDIT_CHANGE: {}", txt);
         self.rs.txte.entry(wgt.id).or_default().txt = core::mem::take(txt);
@@ -363,6 +391,11 @@ impl rtwins::wgt::WindowState for MainWndState {
         rs.top_line = top_line;
     }
 
+    fn on_text_box_scroll_h(&mut self, wgt: &Widget, left_col: i16) {
+        let rs = self.rs.txtbx.entry(wgt.id).or_default();
+        rs.left_col = left_col;
+    }
+
     fn on_custom_widget_draw(&mut self, wgt: &Widget, term_cell: &RefCell<&mut Term>) {
         let coord = rtwins::wgt::get_screen_coord(self, wgt);
         let sz = &wgt.size;
@@ -378,7 +411,7 @@ impl rtwins::wgt::WindowState for MainWndState {
         if let InputEvent::Mouse(ref mouse) = ii.evnt {
             if let Some(mut term_guard) = TERM.try_lock() {
                 let term = &mut *term_guard;
-                term.move_to(mouse.col as u16, mouse.row as u16);
+                term.move_to(mouse.col, mouse.row);
                 let mark = mouse.evt.as_mark();
                 term.write_char(mark);
             }
@@ -394,7 +427,7 @@ impl rtwins::wgt::WindowState for MainWndState {
         false
     }
 
-    /** common state queries **/
+    /* common state queries */
 
     fn is_enabled(&self, wgt: &Widget) -> bool {
         if wgt.id == id::CHBX_C {
@@ -407,24 +440,31 @@ impl rtwins::wgt::WindowState for MainWndState {
 
     fn is_focused(&self, wgt: &Widget) -> bool {
         let rs_opt = self.rs.pgctrl.get(&id::PG_CONTROL);
-        rs_opt.map_or(false, |rs| self.focused_ids[rs.page as usize] == wgt.id)
+        rs_opt.is_some_and(|rs| self.focused_ids[rs.page as usize] == wgt.id)
     }
 
     fn is_visible(&self, wgt: &Widget) -> bool {
         if let wgt::Property::Page(_) = wgt.prop {
             let pgctrl = wgt::get_parent(wgt);
 
-            return self.rs.pgctrl.get(&pgctrl.id).map_or(true, |rs| {
-                wgt::page_page_idx(wgt).map_or(false, |pg_idx| pg_idx == rs.page)
-            });
+            return match self.rs.pgctrl.get(&pgctrl.id) {
+                Some(rs) => wgt::page_page_idx(wgt).is_some_and(|pg_idx| pg_idx == rs.page),
+                None => true,
+            };
         }
 
         if wgt.id == id::LAYER1 {
-            return self.rs.chbx.get(&id::CHBX_L1).map_or(true, |rs| rs.checked);
+            return match self.rs.chbx.get(&id::CHBX_L1) {
+                Some(rs) => rs.checked,
+                None => true,
+            };
         }
 
         if wgt.id == id::LAYER2 {
-            return self.rs.chbx.get(&id::CHBX_L2).map_or(true, |rs| rs.checked);
+            return match self.rs.chbx.get(&id::CHBX_L2) {
+                Some(rs) => rs.checked,
+                None => true,
+            };
         }
 
         true
@@ -452,7 +492,7 @@ impl rtwins::wgt::WindowState for MainWndState {
         Some(&mut self.rs)
     }
 
-    /** widget-specific queries; all mutable params are outputs **/
+    /* widget-specific queries; all mutable params are outputs */
 
     fn get_window_coord(&mut self) -> Coord {
         self.widgets.first().map_or(Coord::cdeflt(), |w| w.coord)
@@ -488,27 +528,12 @@ impl rtwins::wgt::WindowState for MainWndState {
             ));
         }
         else if wgt.id == id::LABEL_MULTI_FMT {
-            let _ = out.stream()
-                << "  ▫▫▫▫▫ "
-                << esc::INVERSE_ON
-                << "ListBox manual:"
-                << esc::INVERSE_OFF
-                << " ▫▫▫▫▫\n"
-                << "• "
-                << esc::UNDERLINE_ON
-                << "Up/Down"
-                << esc::UNDERLINE_OFF
-                << " -> change item\n"
-                << "• "
-                << esc::UNDERLINE_ON
-                << "PgUp/PgDown"
-                << esc::UNDERLINE_OFF
-                << " -> scroll page\n"
-                << "• "
-                << esc::UNDERLINE_ON
-                << "Enter"
-                << esc::UNDERLINE_OFF
-                << " -> select the item";
+            out.push_str(
+                "  ▫▫▫▫▫ [r]ListBox manual:[/r] ▫▫▫▫▫\n\
+                 • [u]Up/Down[/u] -> change item\n\
+                 • [u]PgUp/PgDown[/u] -> scroll page\n\
+                 • [u]Enter[/u] -> select the [b][fg=white_intense]item[/fg][/b]",
+            );
         }
         else if wgt.id == id::LBL_WORDWRAP {
             let mut tmp = String::with_capacity(100);
@@ -606,18 +631,14 @@ impl rtwins::wgt::WindowState for MainWndState {
         let rs = self.rs.txtbx.entry(wgt.id).or_default();
         out.top_line = rs.top_line;
 
-        if wgt.id == id::TBX_WIDE {
-            if self.tbx_wide_lines.borrow().len() == 0 {
-                self.tbx_wide_lines = utils::word_wrap(wgt.size.width as usize - 2, &self.tbx_text);
-            }
-            out.lines = Arc::clone(&self.tbx_wide_lines);
-        }
-        else if wgt.id == id::TBX_NARROW {
-            if self.tbx_narrow_lines.borrow().len() == 0 {
-                self.tbx_narrow_lines =
-                    utils::word_wrap(wgt.size.width as usize - 2, &self.tbx_text);
+        out.left_col = rs.left_col;
+
+        if wgt.id == id::TBX_WIDE || wgt.id == id::TBX_NARROW {
+            if self.tbx_lines.borrow().is_empty() {
+                // split into lines only; the narrow one wraps them
+                self.tbx_lines = utils::word_wrap(usize::MAX, &self.tbx_text);
             }
-            out.lines = Arc::clone(&self.tbx_narrow_lines);
+            out.lines = Arc::clone(&self.tbx_lines);
         }
     }
 
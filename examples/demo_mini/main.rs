@@ -1,5 +1,7 @@
 //! # RTWins minimal demo app
 
+// `qemu` feature is set with RUSTFLAGS
+#![allow(unexpected_cfgs)]
 #![cfg_attr(target_os = "none", no_main)]
 #![cfg_attr(target_os = "none", no_std)]
 
@@ -79,6 +81,7 @@ const WINDOW_MAIN: Widget = Widget {
                 ),
                 fg_color: ColorFg::White,
                 bg_color: ColorBg::GreenIntense,
+                markup: false,
             }.into(),
             ..Widget::cdeflt()
         },
@@ -248,8 +251,15 @@ fn tui() {
             let sz = ws_main.get_window_size();
             coord.row as u16 + sz.height as u16 + 1
         };
+        #[cfg(target_os = "linux")]
+        if let Some(size) = pal_std::DemoPal::get_screen_size() {
+            term_guard.screen_size = size;
+        }
         term_guard.write_str(esc::TERM_RESET);
         term_guard.mouse_mode(rtwins::MouseMode::M2);
+        term_guard.bracketed_paste(true);
+        term_guard.focus_reporting(true);
+        term_guard.keyboard_protocol(true);
         term_guard.draw_wnd(&mut ws_main);
     }
     else {
@@ -288,10 +298,11 @@ fn tui() {
             rtwins::tr_warn!("Exit requested");
             break;
         }
-        else if !inp_seq.is_empty() {
+        else if !inp_seq.is_empty() || !ique.is_empty() {
             ique.extend(inp_seq.iter());
+            let now = TERM.try_lock().unwrap().pal.get_timestamp_ms();
 
-            while dec.decode_input_seq(&mut ique, &mut ii) > 0 {
+            while dec.decode_input_seq_at(&mut ique, &mut ii, now) > 0 {
                 // check for Ctrl+D
                 #[cfg(target_os = "none")]
                 if let InputEvent::Char(ref cb) = ii.evnt {
@@ -328,6 +339,9 @@ fn tui() {
     {
         let mut term_guard = TERM.try_lock().unwrap();
         term_guard.mouse_mode(rtwins::MouseMode::Off);
+        term_guard.bracketed_paste(false);
+        term_guard.focus_reporting(false);
+        term_guard.keyboard_protocol(false);
         rtwins::tr_flush!(&mut term_guard);
 
         term_guard.pal.as_mut().sleep(1_000);
@@ -5,7 +5,7 @@ use crate::Term;
 // ---------------------------------------------------------------------------------------------- //
 
 /// Widget coordinates on screen or on parent widget
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
 pub struct Coord {
     pub col: u8,
     pub row: u8,
@@ -58,7 +58,7 @@ impl core::ops::SubAssign for Coord {
 }
 
 /// Widget size
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
 pub struct Size {
     pub width: u8,
     pub height: u8,
@@ -93,7 +93,7 @@ impl core::ops::Sub for Size {
 }
 
 /// Rectangle area
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Rect {
     pub coord: Coord,
     pub size: Size,
@@ -127,17 +127,19 @@ impl Rect {
     /// Checks if given point at `col:row` is within this rectangle
     pub fn is_point_within(&self, col: u8, row: u8) -> bool {
         col >= self.coord.col
-            && col < self.coord.col + self.size.width
+            && (col as u16) < self.coord.col as u16 + self.size.width as u16
             && row >= self.coord.row
-            && row < self.coord.row + self.size.height
+            && (row as u16) < self.coord.row as u16 + self.size.height as u16
     }
 
     /// Check if `r` fits within this rectangle
     pub fn is_rect_within(&self, r: &Rect) -> bool {
         r.coord.col >= self.coord.col
-            && r.coord.col + r.size.width <= self.coord.col + self.size.width
+            && r.coord.col as u16 + r.size.width as u16
+                <= self.coord.col as u16 + self.size.width as u16
             && r.coord.row >= self.coord.row
-            && r.coord.row + r.size.height <= self.coord.row + self.size.height
+            && r.coord.row as u16 + r.size.height as u16
+                <= self.coord.row as u16 + self.size.height as u16
     }
 }
 
@@ -253,4 +255,8 @@ pub enum MouseMode {
     M1,
     /// buttons and wheel
     M2,
+    /// buttons, wheel and motion with button pressed (drag)
+    M3,
+    /// buttons, wheel and any motion
+    M4,
 }
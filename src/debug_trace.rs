@@ -110,7 +110,7 @@ impl Trace {
         let time_str = self.trace_timestr.as_ref()();
 
         if self.print_location {
-            let filename = filepath.split('/').last().unwrap_or_default();
+            let filename = filepath.split('/').next_back().unwrap_or_default();
             let longmsg = format!("{}:{}: {}", filename, line, msg);
             msg = longmsg;
         }
@@ -0,0 +1,659 @@
+//! # RTWins built-in dialogs
+//!
+//! Message box, confirmation, text/number prompt and list selection,
+//! auto-sized to the message and centered on the parent area
+
+#![allow(unused_variables)]
+
+use crate::colors::{ColorBg, ColorFg};
+use crate::common::*;
+use crate::input::*;
+use crate::keymap::{self, Action};
+use crate::string_ext::StrExt;
+use crate::tetrary;
+use crate::utils;
+use crate::wgt::{self, prop, rstate, transform, ButtonStyle, Link, WId, Widget, WIDGET_ID_NONE};
+use crate::wnd_manager::Severity;
+use crate::TERM;
+
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Dialog widget identifiers
+pub mod id {
+    use crate::wgt::{WId, WIDGET_ID_NONE};
+
+    #[rustfmt::skip]
+    crate::generate_ids!(
+        WND_DIALOG
+            LBL_ICON
+            LBL_MSG
+            EDT_INPUT
+            LBX_ITEMS
+            BTN_YES
+            BTN_NO
+            BTN_OK
+            BTN_CANCEL
+    );
+}
+
+/// Dialog flavour
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DialogKind {
+    /// Message with icon and buttons
+    Message,
+    /// Yes/No question
+    Confirm,
+    /// Single line text input
+    TextPrompt,
+    /// Integer input, limited to the given range
+    NumberPrompt { min: i64, max: i64 },
+    /// Select one of the items
+    ListSelect,
+}
+
+/// Set of buttons visible in the dialog
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DialogButtons {
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+}
+
+/// Value returned by the closed dialog;
+/// passed to the `WindowState::on_modal_result()` of the opener window, as `&dyn Any`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DialogResult {
+    Yes,
+    No,
+    Ok,
+    /// Cancel button or Esc key
+    Cancel,
+    /// Accepted text of the `TextPrompt`
+    Text(String),
+    /// Accepted value of the `NumberPrompt`
+    Number(i64),
+    /// Accepted item index of the `ListSelect`
+    Item(i16),
+}
+
+/// Dialog result handler
+pub type DialogCallback = Box<dyn FnMut(&DialogResult)>;
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Defines the dialog widgets tree of width `$W`, message height `$MSG_H` and input area height `$IN_H`
+#[rustfmt::skip]
+macro_rules! dialog_tree {
+    ($W: expr, $MSG_H: expr, $IN_H: expr) => {
+        Widget {
+            id: id::WND_DIALOG,
+            link: Link::cdeflt(),
+            coord: Coord::cdeflt(),
+            size: Size { width: $W, height: $MSG_H + $IN_H + 6 },
+            prop: prop::Window {
+                title: "",
+                fg_color: ColorFg::Blue,
+                bg_color: ColorBg::White,
+                is_popup: true,
+            }.into(),
+            children: &[
+                Widget {
+                    id: id::LBL_ICON,
+                    coord: Coord { col: 2, row: 2 },
+                    size: Size { width: 3, height: 1 },
+                    prop: prop::Label {
+                        title: "",
+                        fg_color: ColorFg::Inherit,
+                        bg_color: ColorBg::Inherit,
+                        markup: false,
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+                Widget {
+                    id: id::LBL_MSG,
+                    coord: Coord { col: 6, row: 2 },
+                    size: Size { width: $W - 8, height: $MSG_H },
+                    prop: prop::Label {
+                        title: "",
+                        fg_color: ColorFg::Black,
+                        bg_color: ColorBg::Inherit,
+                        markup: false,
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+                Widget {
+                    id: id::EDT_INPUT,
+                    coord: Coord { col: 6, row: $MSG_H + 3 },
+                    size: Size { width: $W - 8, height: 1 },
+                    prop: prop::TextEdit {
+                        fg_color: ColorFg::White,
+                        bg_color: ColorBg::Blue,
+                        psw_mask: false,
+                        input_mask: "",
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+                Widget {
+                    id: id::LBX_ITEMS,
+                    coord: Coord { col: 6, row: $MSG_H + 3 },
+                    size: Size { width: $W - 8, height: tetrary!($IN_H > 1, $IN_H - 1, 1) },
+                    prop: prop::ListBox {
+                        fg_color: ColorFg::Black,
+                        bg_color: ColorBg::Inherit,
+                        no_frame: false,
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+                Widget {
+                    id: id::BTN_YES,
+                    coord: Coord { col: $W / 2 - 11, row: $MSG_H + $IN_H + 3 },
+                    prop: prop::Button {
+                        text: "YES",
+                        fg_color: ColorFg::WhiteIntense,
+                        bg_color: ColorBg::Green,
+                        style: ButtonStyle::Solid,
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+                Widget {
+                    id: id::BTN_NO,
+                    coord: Coord { col: $W / 2 - 3, row: $MSG_H + $IN_H + 3 },
+                    prop: prop::Button {
+                        text: "NO",
+                        fg_color: ColorFg::WhiteIntense,
+                        bg_color: ColorBg::Red,
+                        style: ButtonStyle::Solid,
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+                Widget {
+                    id: id::BTN_OK,
+                    coord: Coord { col: $W / 2 - 3, row: $MSG_H + $IN_H + 3 },
+                    prop: prop::Button {
+                        text: "OK",
+                        fg_color: ColorFg::WhiteIntense,
+                        bg_color: ColorBg::Green,
+                        style: ButtonStyle::Solid,
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+                Widget {
+                    id: id::BTN_CANCEL,
+                    coord: Coord { col: $W / 2 + 4, row: $MSG_H + $IN_H + 3 },
+                    prop: prop::Button {
+                        text: "CANCEL",
+                        fg_color: ColorFg::White,
+                        bg_color: ColorBg::BlackIntense,
+                        style: ButtonStyle::Solid,
+                    }.into(),
+                    ..Widget::cdeflt()
+                },
+            ],
+        }
+    };
+}
+
+/// Defines flattened dialog widgets array `$NAME`, based on the `$TREE` definition
+macro_rules! dialog_preset {
+    ($NAME: ident, $TREE: ident, $W: expr, $MSG_H: expr, $IN_H: expr) => {
+        const $TREE: Widget = dialog_tree!($W, $MSG_H, $IN_H);
+        static $NAME: [Widget; transform::tree_wgt_count(&$TREE)] =
+            transform::tree_to_array(&$TREE);
+    };
+}
+
+// message only
+dialog_preset!(DLG_MSG_S, TREE_MSG_S, 36, 3, 0);
+dialog_preset!(DLG_MSG_M, TREE_MSG_M, 52, 5, 0);
+dialog_preset!(DLG_MSG_L, TREE_MSG_L, 70, 8, 0);
+// text or number input
+dialog_preset!(DLG_EDT_S, TREE_EDT_S, 36, 2, 2);
+dialog_preset!(DLG_EDT_M, TREE_EDT_M, 52, 4, 2);
+dialog_preset!(DLG_EDT_L, TREE_EDT_L, 70, 6, 2);
+// list of items
+dialog_preset!(DLG_LBX_S, TREE_LBX_S, 36, 2, 7);
+dialog_preset!(DLG_LBX_M, TREE_LBX_M, 52, 3, 9);
+dialog_preset!(DLG_LBX_L, TREE_LBX_L, 70, 4, 11);
+
+/// Available layouts for each kind of dialog, from the smallest one
+fn get_presets(kind: DialogKind) -> [&'static [Widget]; 3] {
+    match kind {
+        DialogKind::Message | DialogKind::Confirm => [&DLG_MSG_S, &DLG_MSG_M, &DLG_MSG_L],
+        DialogKind::TextPrompt | DialogKind::NumberPrompt { .. } => {
+            [&DLG_EDT_S, &DLG_EDT_M, &DLG_EDT_L]
+        }
+        DialogKind::ListSelect => [&DLG_LBX_S, &DLG_LBX_M, &DLG_LBX_L],
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Reusable dialog window state.
+///
+/// Setup it with one of the `message()`, `confirm()`, `prompt_text()`, `prompt_number()`
+/// or `select_item()`, then show it using the `WindowManager`.
+/// The result is passed to the callback and can be polled with `take_result()`;
+/// when shown with `WindowManager::show_modal()`, it is also delivered to the opener window
+pub struct DialogState {
+    /// all window widgets, starting with the window widget itself; depends on the dialog size
+    widgets: &'static [Widget],
+    /// widgets runtime state
+    pub rs: wgt::RuntimeStates,
+    /// currently focused widget
+    focused_id: WId,
+    /// list of widgets to redraw
+    invalidated: Vec<WId>,
+    /// dialog coordinates, centered over the parent area
+    coord: Coord,
+    /// area the dialog is centered on
+    parent_area: Rect,
+    kind: DialogKind,
+    severity: Severity,
+    buttons: DialogButtons,
+    title: String,
+    /// message, wrapped to the label width
+    message: String,
+    /// text or number prompt value
+    input: String,
+    /// list selection items
+    items: Vec<String>,
+    on_result: Option<DialogCallback>,
+    result: Option<DialogResult>,
+}
+
+impl Default for DialogState {
+    fn default() -> Self {
+        DialogState {
+            widgets: &DLG_MSG_S,
+            rs: wgt::RuntimeStates::default(),
+            focused_id: WIDGET_ID_NONE,
+            invalidated: Vec::with_capacity(4),
+            coord: Coord::cdeflt(),
+            parent_area: Rect::cdeflt(),
+            kind: DialogKind::Message,
+            severity: Severity::Info,
+            buttons: DialogButtons::Ok,
+            title: String::new(),
+            message: String::new(),
+            input: String::new(),
+            items: Vec::new(),
+            on_result: None,
+            result: None,
+        }
+    }
+}
+
+impl DialogState {
+    /// Sets the area the dialog will be centered on, eg. the main window
+    pub fn center_on(&mut self, parent_area: Rect) {
+        self.parent_area = parent_area;
+        self.update_coord();
+    }
+
+    /// Setup message box with icon depending on the `severity`
+    pub fn message(
+        &mut self,
+        title: &str,
+        message: &str,
+        severity: Severity,
+        buttons: DialogButtons,
+    ) -> &mut Self {
+        self.setup(DialogKind::Message, title, message);
+        self.severity = severity;
+        self.buttons = buttons;
+        self.focused_id = tetrary!(
+            matches!(buttons, DialogButtons::Ok | DialogButtons::OkCancel),
+            id::BTN_OK,
+            id::BTN_YES
+        );
+        self
+    }
+
+    /// Setup Yes/No question
+    pub fn confirm(&mut self, title: &str, message: &str) -> &mut Self {
+        self.setup(DialogKind::Confirm, title, message);
+        self.buttons = DialogButtons::YesNo;
+        self.focused_id = id::BTN_NO;
+        self
+    }
+
+    /// Setup text input
+    pub fn prompt_text(&mut self, title: &str, message: &str, initial: &str) -> &mut Self {
+        self.setup(DialogKind::TextPrompt, title, message);
+        self.input.push_str(initial);
+        self.focused_id = id::EDT_INPUT;
+        self
+    }
+
+    /// Setup integer input limited to `min..=max`; reversed limits are swapped
+    pub fn prompt_number(
+        &mut self,
+        title: &str,
+        message: &str,
+        initial: i64,
+        min: i64,
+        max: i64,
+    ) -> &mut Self {
+        let (min, max) = (min.min(max), min.max(max));
+        self.setup(DialogKind::NumberPrompt { min, max }, title, message);
+        self.input = initial.clamp(min, max).to_string();
+        self.focused_id = id::EDT_INPUT;
+        self
+    }
+
+    /// Setup selection of one of the `items`
+    pub fn select_item(
+        &mut self,
+        title: &str,
+        message: &str,
+        items: Vec<String>,
+        initial_idx: i16,
+    ) -> &mut Self {
+        self.setup(DialogKind::ListSelect, title, message);
+        let lbs = self.rs.lbx.entry(id::LBX_ITEMS).or_default();
+        lbs.item_idx = initial_idx;
+        lbs.sel_idx = initial_idx;
+        self.items = items;
+        self.focused_id = id::LBX_ITEMS;
+        self
+    }
+
+    /// Sets the handler called when the dialog is closed
+    pub fn on_result(&mut self, callback: DialogCallback) -> &mut Self {
+        self.on_result = Some(callback);
+        self
+    }
+
+    /// Returns the dialog kind
+    pub fn get_kind(&self) -> DialogKind {
+        self.kind
+    }
+
+    /// Returns true if dialog was closed with a result, that was not taken yet
+    pub fn is_closed(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Returns the result of closed dialog, only once
+    pub fn take_result(&mut self) -> Option<DialogResult> {
+        self.result.take()
+    }
+
+    /// Closes the dialog with given result: calls the handler and stores the result for polling
+    pub fn close(&mut self, result: DialogResult) {
+        if let Some(ref mut cb) = self.on_result {
+            cb(&result);
+        }
+        self.result = Some(result);
+    }
+
+    // -----------------
+
+    fn setup(&mut self, kind: DialogKind, title: &str, message: &str) {
+        self.kind = kind;
+        self.severity = tetrary!(
+            kind == DialogKind::Confirm,
+            Severity::Warning,
+            Severity::Info
+        );
+        self.buttons = DialogButtons::OkCancel;
+        self.title = title.to_string();
+        self.input.clear();
+        self.items.clear();
+        self.rs = wgt::RuntimeStates::default();
+        self.invalidated.clear();
+        self.on_result = None;
+        self.result = None;
+
+        // select the smallest layout that fits the message
+        let presets = get_presets(kind);
+        let title_w = title.displayed_width() + 6;
+
+        for (idx, preset) in presets.iter().enumerate() {
+            let lbl_size = wgt::find_by_id(preset, id::LBL_MSG).map_or(Size::cdeflt(), |w| w.size);
+            let lines = utils::word_wrap(lbl_size.width as usize, message).take();
+            let fits =
+                lines.len() <= lbl_size.height as usize && title_w <= preset[0].size.width as usize;
+
+            if fits || idx == presets.len() - 1 {
+                self.widgets = preset;
+                self.message = lines.join("\n");
+                break;
+            }
+        }
+
+        self.update_coord();
+    }
+
+    fn update_coord(&mut self) {
+        let size = self.widgets[0].size;
+        let area = self.parent_area;
+        self.coord.col = area.coord.col + area.size.width.saturating_sub(size.width) / 2;
+        self.coord.row = area.coord.row + area.size.height.saturating_sub(size.height) / 2;
+    }
+
+    /// Accept the current input; invalid number is rejected, leaving the dialog open
+    fn accept(&mut self) {
+        let result = match self.kind {
+            DialogKind::Message | DialogKind::Confirm => DialogResult::Ok,
+            DialogKind::TextPrompt => DialogResult::Text(self.input.clone()),
+            DialogKind::NumberPrompt { min, max } => match self.input.trim().parse::<i64>() {
+                Ok(val) if (min..=max).contains(&val) => DialogResult::Number(val),
+                _ => {
+                    wgt::ring_bell();
+                    return;
+                }
+            },
+            DialogKind::ListSelect => {
+                let lbs = self.rs.lbx.entry(id::LBX_ITEMS).or_default();
+                DialogResult::Item(lbs.sel_idx)
+            }
+        };
+
+        self.close(result);
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+impl wgt::WindowState for DialogState {
+    /* events */
+
+    fn on_button_click(&mut self, wgt: &Widget, ii: &InputInfo) {
+        match wgt.id {
+            id::BTN_YES => self.close(DialogResult::Yes),
+            id::BTN_NO => self.close(DialogResult::No),
+            id::BTN_OK => self.accept(),
+            id::BTN_CANCEL => self.close(DialogResult::Cancel),
+            _ => {}
+        }
+    }
+
+    fn on_text_edit_change(&mut self, wgt: &Widget, txt: &mut String) {
+        // Enter in the editor accepts the input
+        self.input = core::mem::take(txt);
+        self.accept();
+    }
+
+    fn on_text_edit_input_evt(
+        &mut self,
+        wgt: &Widget,
+        ii: &InputInfo,
+        txt: &mut String,
+        cursor_pos: &mut i16,
+    ) -> bool {
+        if let DialogKind::NumberPrompt { min, max } = self.kind {
+            return utils::num_edit_input_evt(ii, txt, cursor_pos, min, max, false);
+        }
+
+        false
+    }
+
+    fn on_list_box_select(&mut self, wgt: &Widget, new_sel_idx: i16) {
+        self.rs.lbx.entry(wgt.id).or_default().sel_idx = new_sel_idx;
+    }
+
+    fn on_list_box_change(&mut self, wgt: &Widget, new_idx: i16) {
+        self.rs.lbx.entry(wgt.id).or_default().item_idx = new_idx;
+        self.accept();
+    }
+
+    fn on_window_unhandled_input_evt(&mut self, wgt: &Widget, ii: &InputInfo) -> bool {
+        if keymap::action_of(ii) == Some(Action::Cancel) {
+            self.close(DialogResult::Cancel);
+            return true;
+        }
+
+        false
+    }
+
+    /* common state queries */
+
+    fn is_enabled(&self, wgt: &Widget) -> bool {
+        self.rs.get_enabled_or_default(wgt.id)
+    }
+
+    fn is_focused(&self, wgt: &Widget) -> bool {
+        self.focused_id == wgt.id
+    }
+
+    fn is_visible(&self, wgt: &Widget) -> bool {
+        let ok_btn = !matches!(self.kind, DialogKind::Message | DialogKind::Confirm)
+            || matches!(self.buttons, DialogButtons::Ok | DialogButtons::OkCancel);
+
+        match wgt.id {
+            id::EDT_INPUT => {
+                matches!(
+                    self.kind,
+                    DialogKind::TextPrompt | DialogKind::NumberPrompt { .. }
+                )
+            }
+            id::LBX_ITEMS => self.kind == DialogKind::ListSelect,
+            id::BTN_YES | id::BTN_NO => !ok_btn,
+            id::BTN_OK => ok_btn,
+            id::BTN_CANCEL => {
+                !matches!(self.kind, DialogKind::Message | DialogKind::Confirm)
+                    || matches!(
+                        self.buttons,
+                        DialogButtons::OkCancel | DialogButtons::YesNoCancel
+                    )
+            }
+            _ => true,
+        }
+    }
+
+    fn is_movable(&self) -> bool {
+        true
+    }
+
+    fn get_focused_id(&mut self) -> WId {
+        self.focused_id
+    }
+
+    fn get_widgets(&self) -> &'static [Widget] {
+        self.widgets
+    }
+
+    fn get_rstate(&mut self) -> Option<&mut wgt::RuntimeStates> {
+        Some(&mut self.rs)
+    }
+
+    fn take_modal_result(&mut self) -> Option<wgt::ModalResult> {
+        self.take_result()
+            .map(|result| Box::new(result) as wgt::ModalResult)
+    }
+
+    /* widget-specific queries; all mutable params are outputs */
+
+    fn get_window_coord(&mut self) -> Coord {
+        self.coord
+    }
+
+    fn get_window_size(&mut self) -> Size {
+        self.widgets[0].size
+    }
+
+    fn get_window_title(&mut self, wgt: &Widget, out: &mut String) {
+        out.push_str(&self.title);
+    }
+
+    fn get_label_text(&mut self, wgt: &Widget, out: &mut String) {
+        if wgt.id == id::LBL_MSG {
+            out.push_str(&self.message);
+        }
+        else if wgt.id == id::LBL_ICON {
+            let (cl_bg, icon) = match self.severity {
+                _ if self.kind == DialogKind::Confirm => (ColorBg::Blue, '?'),
+                Severity::Info => (ColorBg::Blue, 'i'),
+                Severity::Success => (ColorBg::Green, '✓'),
+                Severity::Warning => (ColorBg::Yellow, '!'),
+                Severity::Error => (ColorBg::Red, '✗'),
+            };
+
+            out.push_str(&format!(
+                "{}{} {} ",
+                cl_bg.encode(),
+                ColorFg::WhiteIntense.encode(),
+                icon
+            ));
+        }
+    }
+
+    fn get_text_edit_text(&mut self, wgt: &Widget, out: &mut String, edit_mode: bool) {
+        out.push_str(&self.input);
+    }
+
+    fn get_list_box_state(&mut self, wgt: &Widget, out: &mut rstate::LbxState) {
+        let lbs = self.rs.lbx.entry(wgt.id).or_default();
+        out.item_idx = lbs.item_idx;
+        out.sel_idx = lbs.sel_idx;
+        out.items_cnt = self.items.len() as i16;
+    }
+
+    fn get_list_box_item(&mut self, wgt: &Widget, item_idx: i16, out: &mut String) {
+        if let Some(item) = self.items.get(item_idx as usize) {
+            out.push_str(item);
+        }
+    }
+
+    /* requests */
+
+    fn set_focused_id(&mut self, wid: WId) {
+        self.focused_id = wid;
+    }
+
+    fn set_window_coord(&mut self, coord: Coord) {
+        self.coord = coord;
+    }
+
+    fn instant_redraw(&mut self, wid: WId) {
+        if let Some(mut term_guard) = TERM.try_lock() {
+            term_guard.draw(self, &[wid]);
+            term_guard.flush_buff();
+        }
+    }
+
+    fn invalidate_many(&mut self, wids: &[WId]) {
+        for wid in wids.iter() {
+            if !self.invalidated.contains(wid) {
+                self.invalidated.push(*wid);
+            }
+        }
+    }
+
+    fn clear_invalidated(&mut self) {
+        self.invalidated.clear();
+    }
+
+    fn get_invalidated(&mut self, out: &mut Vec<WId>) {
+        core::mem::swap(&mut self.invalidated, out);
+    }
+}
@@ -3,8 +3,11 @@
 //! [More on Wikipedia](https://en.m.wikipedia.org/wiki/ANSI_escape_code)
 //!
 
+extern crate alloc;
+use alloc::string::String;
+
 // -----------------------------------------------------------------------------------------------
-/// # Escape sequence generation
+// # Escape sequence generation
 
 /// ESC prefix
 #[macro_export]
@@ -93,7 +96,7 @@ macro_rules! bg_color {
 }
 
 // -----------------------------------------------------------------------------------------------
-/// # Text Display Modifier Escape Sequences
+// # Text Display Modifier Escape Sequences
 
 #[macro_export]
 macro_rules! bold {
@@ -244,9 +247,9 @@ pub const FONT_8: &str = csi!("18m");
 pub const FONT_9: &str = csi!("19m");
 
 // -----------------------------------------------------------------------------------------------
-/// # Text Color Control Sequences
-/// 4/8/24-bit ANSI colors
-/// https://en.wikipedia.org/wiki/ANSI_escape_code
+// # Text Color Control Sequences
+// 4/8/24-bit ANSI colors
+// https://en.wikipedia.org/wiki/ANSI_escape_code
 
 pub const FG_BLACK: &str = csi!("30m");
 pub const FG_BLACK_INTENSE: &str = csi!("90m");
@@ -288,8 +291,8 @@ pub const BG_DEFAULT: &str = csi!("49m");
 pub const COLORS_DEFAULT: &str = csi!("0m");
 
 // -----------------------------------------------------------------------------------------------
-/// # WEB colors
-/// https://en.wikipedia.org/wiki/Web_colors
+// # WEB colors
+// https://en.wikipedia.org/wiki/Web_colors
 
 /// Pink colors
 pub const FG_PINK: &str = fg_rgb!(255, 192, 203);
@@ -558,7 +561,7 @@ pub const FG_BLACK_RGB: &str = fg_rgb!(0, 0, 0);
 pub const BG_BLACK_RGB: &str = bg_rgb!(0, 0, 0);
 
 // -----------------------------------------------------------------------------------------------
-/// # Cursor Positioning Control Sequences
+// # Cursor Positioning Control Sequences
 
 pub const CURSOR_POS_SAVE: &str = csi!("s");
 pub const CURSOR_POS_RESTORE: &str = csi!("u");
@@ -636,7 +639,7 @@ pub const CURSOR_FORWARD_FMT: &str = cursor_forward!();
 pub const CURSOR_BACKWARD_FMT: &str = cursor_backward!();
 
 // -----------------------------------------------------------------------------------------------
-/// # Line control
+// # Line control
 
 /// Erases the current line, returning the cursor to the far left
 pub const LINE_ERASE_ALL: &str = csi!("2K");
@@ -669,7 +672,7 @@ pub const LINE_INSERT_FMT: &str = line_insert!();
 pub const LINE_DELETE_FMT: &str = line_delete!();
 
 // -----------------------------------------------------------------------------------------------
-/// # Character control
+// # Character control
 
 /// Repeat last character `n` times - not supported on every platform
 #[macro_export]
@@ -721,7 +724,7 @@ pub const CHAR_DELETE_FMT: &str = char_delete!();
 pub const CHAR_INSERT_FMT: &str = char_insert!();
 
 // -----------------------------------------------------------------------------------------------
-/// # Screen Control Sequences
+// # Screen Control Sequences
 
 /// Erases the entire display, returning the cursor to the top left.
 pub const SCREEN_ERASE_ALL: &str = csi!("2J");
@@ -762,8 +765,8 @@ pub const SCREEN_SCROLL_UP_FMT: &str = screen_scroll_up!();
 pub const SCREEN_SCROLL_DOWN_FMT: &str = screen_scroll_down!();
 
 // -----------------------------------------------------------------------------------------------
-/// # Mouse Control Sequences
-/// https://www.systutorials.com/docs/linux/man/4-console_codes/#lbAF
+// # Mouse Control Sequences
+// https://www.systutorials.com/docs/linux/man/4-console_codes/#lbAF
 
 /// Mode1: only click
 pub const MOUSE_REPORTING_M1_ON: &str = csi!("?9h");
@@ -773,8 +776,41 @@ pub const MOUSE_REPORTING_M1_OFF: &str = csi!("?9l");
 pub const MOUSE_REPORTING_M2_ON: &str = csi!("?1000h");
 pub const MOUSE_REPORTING_M2_OFF: &str = csi!("?1000l");
 
+/// Mode3: Mode2 + motion while button pressed
+pub const MOUSE_REPORTING_M3_ON: &str = csi!("?1002h");
+pub const MOUSE_REPORTING_M3_OFF: &str = csi!("?1002l");
+
+/// Mode4: Mode2 + any motion
+pub const MOUSE_REPORTING_M4_ON: &str = csi!("?1003h");
+pub const MOUSE_REPORTING_M4_OFF: &str = csi!("?1003l");
+
+/// SGR extended coordinates, for screens wider than 223 columns
+pub const MOUSE_REPORTING_SGR_ON: &str = csi!("?1006h");
+pub const MOUSE_REPORTING_SGR_OFF: &str = csi!("?1006l");
+
+/// Bracketed paste: pasted text is surrounded with `PASTE_BEGIN` and `PASTE_END`
+pub const BRACKETED_PASTE_ON: &str = csi!("?2004h");
+pub const BRACKETED_PASTE_OFF: &str = csi!("?2004l");
+pub const PASTE_BEGIN: &str = csi!("200~");
+pub const PASTE_END: &str = csi!("201~");
+
+/// Focus reporting: terminal reports `FOCUS_IN` and `FOCUS_OUT` when its window gains/loses focus
+pub const FOCUS_REPORTING_ON: &str = csi!("?1004h");
+pub const FOCUS_REPORTING_OFF: &str = csi!("?1004l");
+pub const FOCUS_IN: &str = csi!("I");
+pub const FOCUS_OUT: &str = csi!("O");
+
+/// Clipboard access: `OSC 52 ; c ; <base64 text> BEL` sets the terminal host system clipboard;
+/// see `osc52_copy()`
+pub const CLIPBOARD_SET: &str = osc!("52;c;");
+
+/// Kitty keyboard protocol: push/pop the `disambiguate escape codes` enhancement;
+/// keys with modifiers are then reported as `CSI code;mods u`
+pub const KEYBOARD_ENHANCE_ON: &str = csi!(">1u");
+pub const KEYBOARD_ENHANCE_OFF: &str = csi!("<u");
+
 // -----------------------------------------------------------------------------------------------
-/// # Miscellaneous Control Sequences
+// # Miscellaneous Control Sequences
 
 /// Terminal reset - clear the screen and scroll buffer
 pub const TERM_RESET: &str = esc!("c");
@@ -799,9 +835,6 @@ pub const SEQ_MAX_LENGTH: usize = 8;
 
 // -----------------------------------------------------------------------------------------------
 
-/// Clickable URL with title
-/// `\u001B]8;;https://github.com\u0007Click\u001B]8;;\u0007`
-
 // pub fn link(url: &str, lbl: &str) -> String {
 //     let lnk = osc!("8;;").to_string() + url + "\u{0007}" + lbl + osc!("8;;\u{0007}");
 //     let mut out = String::from("\x1B]");
@@ -809,6 +842,17 @@ pub const SEQ_MAX_LENGTH: usize = 8;
 //     out
 // }
 
+/// Sequence copying the text to the terminal host system clipboard (OSC 52);
+/// not all terminals support it, some require it to be enabled in the settings
+pub fn osc52_copy(text: &str) -> String {
+    let mut out = String::from(CLIPBOARD_SET);
+    out.push_str(&crate::utils::base64_encode(text.as_bytes()));
+    out.push_str(BELL);
+    out
+}
+
+/// Clickable URL with title
+/// `\u001B]8;;https://github.com\u0007Click\u001B]8;;\u0007`
 #[macro_export]
 macro_rules! url_link {
     ($URL:literal, $LABEL:literal ) => {
@@ -2,6 +2,11 @@
 
 #![allow(dead_code)]
 
+use crate::common::Coord;
+
+extern crate alloc;
+use alloc::string::String;
+
 // ---------------------------------------------------------------------------------------------- //
 
 /// ANSI control codes
@@ -87,6 +92,14 @@ impl Default for Key {
     }
 }
 
+/// Mouse button held during the motion
+#[derive(PartialEq, PartialOrd, Debug, Copy, Clone)]
+pub enum MouseButton {
+    Left,
+    Mid,
+    Right,
+}
+
 /// Mouse button click events
 #[derive(PartialEq, PartialOrd, Debug, Copy, Clone)]
 pub enum MouseEvent {
@@ -99,6 +112,10 @@ pub enum MouseEvent {
     ButtonReleased,
     WheelUp,
     WheelDown,
+    /// motion with the button pressed; requires `MouseMode::M3` or `M4`
+    Drag(MouseButton),
+    /// motion without any button pressed; requires `MouseMode::M4`
+    Move,
 }
 
 impl MouseEvent {
@@ -113,6 +130,8 @@ impl MouseEvent {
             Self::ButtonReleased => '^',
             Self::WheelUp => 'U',
             Self::WheelDown => 'D',
+            Self::Drag(_) => '~',
+            Self::Move => '.',
         }
     }
 }
@@ -129,15 +148,28 @@ impl Default for MouseEvent {
 pub struct MouseInfo {
     // button or wheel event
     pub evt: MouseEvent,
+    // button that caused the event; unknown for the release in the legacy protocol
+    pub btn: Option<MouseButton>,
     // 1:1 based terminal coordinates of the event
-    pub col: u8,
-    pub row: u8,
+    pub col: u16,
+    pub row: u16,
+}
+
+impl MouseInfo {
+    /// Returns the event position, or None if it is beyond the widgets coordinates range
+    pub fn coord(&self) -> Option<Coord> {
+        Some(Coord::new(
+            u8::try_from(self.col).ok()?,
+            u8::try_from(self.row).ok()?,
+        ))
+    }
 }
 
 impl Default for MouseInfo {
     fn default() -> Self {
         Self {
             evt: MouseEvent::None,
+            btn: None,
             col: 0,
             row: 0,
         }
@@ -220,6 +252,14 @@ impl CharBuff {
     }
 }
 
+impl From<char> for CharBuff {
+    fn from(c: char) -> Self {
+        let mut cb = CharBuff::default();
+        cb.utf8sl = c.encode_utf8(&mut cb.utf8seq).len() as u8;
+        cb
+    }
+}
+
 /// Decoded input type
 #[derive(Debug, Clone)]
 pub enum InputEvent {
@@ -231,6 +271,10 @@ pub enum InputEvent {
     Key(Key),
     /// Mouse event
     Mouse(MouseInfo),
+    /// Text pasted by the user, with bracketed paste mode enabled
+    Paste(String),
+    /// Terminal window gained (true) or lost (false) focus, with focus reporting enabled
+    Focus(bool),
 }
 
 #[allow(clippy::derivable_impls)]
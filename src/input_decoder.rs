@@ -3,6 +3,7 @@
 #![allow(dead_code)]
 
 use crate::input::*;
+use crate::tetrary;
 use crate::utils;
 
 use core::cmp::Ordering;
@@ -269,6 +270,79 @@ const ESC_KEYS_MAP_UNSORTED : [SeqMap; 155] = [
 
 const ESC_KEYS_MAP_SORTED: [SeqMap; ESC_KEYS_MAP_UNSORTED.len()] = sort_seq(&ESC_KEYS_MAP_UNSORTED);
 
+/// Terminal emulator profile; selects sequences not present in the built-in map
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TermProfile {
+    Xterm,
+    Vt220,
+    Rxvt,
+    Putty,
+    LinuxConsole,
+}
+
+#[rustfmt::skip]
+const XTERM_KEYS_MAP : [SeqMap; 6] = [
+    seq_def!("OA",       "Up",           Key::Up,        KEY_MOD_SPECIAL),   // application cursor
+    seq_def!("OB",       "Down",         Key::Down,      KEY_MOD_SPECIAL),
+    seq_def!("OC",       "Right",        Key::Right,     KEY_MOD_SPECIAL),
+    seq_def!("OD",       "Left",         Key::Left,      KEY_MOD_SPECIAL),
+    seq_def!("OH",       "Home",         Key::Home,      KEY_MOD_SPECIAL),
+    seq_def!("OF",       "End",          Key::End,       KEY_MOD_SPECIAL),
+];
+
+#[rustfmt::skip]
+const RXVT_KEYS_MAP : [SeqMap; 20] = [
+    seq_def!("[a",       "S-Up",         Key::Up,        KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[b",       "S-Down",       Key::Down,      KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[c",       "S-Right",      Key::Right,     KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[d",       "S-Left",       Key::Left,      KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("Oa",       "C-Up",         Key::Up,        KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("Ob",       "C-Down",       Key::Down,      KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("Oc",       "C-Right",      Key::Right,     KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("Od",       "C-Left",       Key::Left,      KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("[2$",      "S-Ins",        Key::Insert,    KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[3$",      "S-Del",        Key::Delete,    KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[5$",      "S-PgUp",       Key::PgUp,      KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[6$",      "S-PgDown",     Key::PgDown,    KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[7$",      "S-Home",       Key::Home,      KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[8$",      "S-End",        Key::End,       KEY_MOD_SPECIAL | KEY_MOD_SHIFT),
+    seq_def!("[2^",      "C-Ins",        Key::Insert,    KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("[3^",      "C-Del",        Key::Delete,    KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("[5^",      "C-PgUp",       Key::PgUp,      KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("[6^",      "C-PgDown",     Key::PgDown,    KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("[7^",      "C-Home",       Key::Home,      KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("[8^",      "C-End",        Key::End,       KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+];
+
+#[rustfmt::skip]
+const PUTTY_KEYS_MAP : [SeqMap; 4] = [
+    seq_def!("OA",       "C-Up",         Key::Up,        KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("OB",       "C-Down",       Key::Down,      KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("OC",       "C-Right",      Key::Right,     KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+    seq_def!("OD",       "C-Left",       Key::Left,      KEY_MOD_SPECIAL | KEY_MOD_CTRL),
+];
+
+#[rustfmt::skip]
+const LINUX_KEYS_MAP : [SeqMap; 5] = [
+    seq_def!("[[A",      "F1",           Key::F1,        KEY_MOD_SPECIAL),   // also PuTTY
+    seq_def!("[[B",      "F2",           Key::F2,        KEY_MOD_SPECIAL),
+    seq_def!("[[C",      "F3",           Key::F3,        KEY_MOD_SPECIAL),
+    seq_def!("[[D",      "F4",           Key::F4,        KEY_MOD_SPECIAL),
+    seq_def!("[[E",      "F5",           Key::F5,        KEY_MOD_SPECIAL),
+];
+
+/// Key sequence registered at runtime
+struct UserSeqMap {
+    // ESC sequence, without the leading ESC
+    seq: String,
+    // keyboard key name mapped to sequence
+    name: &'static str,
+    // keyboard special key code
+    key: Key,
+    // key modifiers, like KEY_MOD_CTRL
+    kmod: u8,
+}
+
 macro_rules! ctrl_def {
     ($C:literal, $N:literal, $K:literal, $M:expr) => {
         LetterMap {
@@ -441,7 +515,8 @@ fn seq_binary_search(sequence: &[u8], map: &'static [SeqMap]) -> Option<&'static
         let (startswith, cmp) = seq_cmp(sequence, map[mid as usize].seq.as_bytes());
 
         if startswith {
-            return Some(&map[mid as usize]);
+            // sequence shorter than the map entry is not complete yet
+            return tetrary!(cmp.is_lt(), None, Some(&map[mid as usize]));
         }
         else if cmp.is_gt() {
             lo = mid + 1;
@@ -460,13 +535,301 @@ fn seq_binary_search(sequence: &[u8], map: &'static [SeqMap]) -> Option<&'static
     None
 }
 
+/// Maximum parametrized CSI sequence length (including null),
+/// like the SGR mouse report `ESC[<b;col;rowM` or the kitty key `ESC[57414;5:3u`
+const CSI_SEQ_MAX_LENGTH: usize = 24;
+
+/// Decodes mouse button code, common for the legacy and the SGR protocol
+fn decode_mouse_btn(code: u8, released: bool, inp_info: &mut InputInfo) -> MouseInfo {
+    let mut mi = MouseInfo::default();
+    let btn = match code & 0x03 {
+        0x00 => Some(MouseButton::Left),
+        0x01 => Some(MouseButton::Mid),
+        0x02 => Some(MouseButton::Right),
+        _ => None,
+    };
+
+    // wheel and extra buttons are not reported as the `MouseButton`
+    mi.btn = tetrary!(code & 0xC0 == 0, btn, None);
+
+    if released {
+        // SGR protocol reports which button was released
+        mi.evt = MouseEvent::ButtonReleased;
+    }
+    else {
+        mi.evt = match code & 0xE3 {
+            0x00 => MouseEvent::ButtonLeft,
+            0x01 => MouseEvent::ButtonMid,
+            0x02 => MouseEvent::ButtonRight,
+            0x03 => MouseEvent::ButtonReleased,
+            0x80 => MouseEvent::ButtonGoBack,
+            0x81 => MouseEvent::ButtonGoForward,
+            0x40 => MouseEvent::WheelUp,
+            0x41 => MouseEvent::WheelDown,
+            // motion flag set
+            0x20 => MouseEvent::Drag(MouseButton::Left),
+            0x21 => MouseEvent::Drag(MouseButton::Mid),
+            0x22 => MouseEvent::Drag(MouseButton::Right),
+            0x23 => MouseEvent::Move,
+            _ => MouseEvent::None,
+        };
+    }
+
+    if code & 0x04 != 0 {
+        inp_info.kmod.set_shift();
+    }
+    if code & 0x08 != 0 {
+        inp_info.kmod.set_alt();
+    }
+    if code & 0x10 != 0 {
+        inp_info.kmod.set_ctrl();
+    }
+
+    mi
+}
+
+/// Parses SGR mouse report parameters `btn;col;row` terminated with `M` (press) or `m` (release);
+/// returns the parameters, the release flag and the number of bytes parsed
+fn parse_sgr_mouse(seq: &[u8]) -> Option<([u16; 3], bool, usize)> {
+    let mut params = [0u16; 3];
+    let mut idx = 0;
+
+    for (i, b) in seq.iter().enumerate() {
+        match *b {
+            b'0'..=b'9' => {
+                params[idx] = params[idx]
+                    .saturating_mul(10)
+                    .saturating_add((*b - b'0') as u16);
+            }
+            b';' if idx < 2 => idx += 1,
+            b'M' | b'm' if idx == 2 => return Some((params, *b == b'm', i + 1)),
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Parameters of the CSI sequence `p1[:s1];p2[:s2];p3 final`;
+/// only the first sub-parameter of each parameter is kept
+#[derive(Copy, Clone, Default)]
+struct CsiParams {
+    params: [u32; 3],
+    subparams: [u32; 3],
+    // final byte, like `u` or `~`
+    fin: u8,
+    // number of bytes parsed, including the final byte
+    len: usize,
+}
+
+/// Parses CSI sequence parameters following the `ESC [`;
+/// returns None if the sequence is incomplete or is not a plain numeric one
+fn parse_csi_params(seq: &[u8]) -> Option<CsiParams> {
+    let mut csi = CsiParams::default();
+    let mut idx = 0;
+    let mut sub = 0;
+
+    for (i, b) in seq.iter().enumerate() {
+        match *b {
+            b'0'..=b'9' => {
+                let digit = (*b - b'0') as u32;
+                if sub == 0 {
+                    csi.params[idx] = csi.params[idx].saturating_mul(10).saturating_add(digit);
+                }
+                else if sub == 1 {
+                    csi.subparams[idx] =
+                        csi.subparams[idx].saturating_mul(10).saturating_add(digit);
+                }
+            }
+            b':' => sub += 1,
+            b';' if idx < 2 => {
+                idx += 1;
+                sub = 0;
+            }
+            0x40..=0x7E => {
+                csi.fin = *b;
+                csi.len = i + 1;
+                return Some(csi);
+            }
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// Decodes the key reported with the kitty keyboard protocol: `CSI code;mods:event u`,
+/// or the legacy key reported with any modifiers: `CSI 1;mods X`, `CSI n;mods ~`;
+/// returns false if the key is not recognized
+fn decode_csi_key(csi: &CsiParams, inp_info: &mut InputInfo) -> bool {
+    let code = csi.params[0];
+    let mut key = Key::None;
+    let mut chr = None;
+
+    match csi.fin {
+        b'u' => match code {
+            8 | 127 => key = Key::Backspace,
+            9 => key = Key::Tab,
+            13 | 57414 => key = Key::Enter,
+            27 => key = Key::Esc,
+            57362 => key = Key::Pause,
+            // keypad
+            57399..=57408 => chr = char::from_digit(code - 57399, 10),
+            57409 => chr = Some('.'),
+            57410 => chr = Some('/'),
+            57411 => chr = Some('*'),
+            57412 => chr = Some('-'),
+            57413 => chr = Some('+'),
+            57415 => chr = Some('='),
+            57417 => key = Key::Left,
+            57418 => key = Key::Right,
+            57419 => key = Key::Up,
+            57420 => key = Key::Down,
+            57421 => key = Key::PgUp,
+            57422 => key = Key::PgDown,
+            57423 => key = Key::Home,
+            57424 => key = Key::End,
+            57425 => key = Key::Insert,
+            57426 => key = Key::Delete,
+            // other functional keys, like sole modifiers, media keys or F13+, are ignored
+            57344..=63743 => {}
+            0..=31 => return false,
+            _ => chr = char::from_u32(code),
+        },
+        b'~' => {
+            key = match code {
+                1 | 7 => Key::Home,
+                2 => Key::Insert,
+                3 => Key::Delete,
+                4 | 8 => Key::End,
+                5 => Key::PgUp,
+                6 => Key::PgDown,
+                11 => Key::F1,
+                12 => Key::F2,
+                13 => Key::F3,
+                14 => Key::F4,
+                15 => Key::F5,
+                17 => Key::F6,
+                18 => Key::F7,
+                19 => Key::F8,
+                20 => Key::F9,
+                21 => Key::F10,
+                23 => Key::F11,
+                24 => Key::F12,
+                _ => return false,
+            }
+        }
+        b'A' | b'B' | b'C' | b'D' | b'F' | b'H' | b'P' | b'Q' | b'R' | b'S' if code <= 1 => {
+            key = match csi.fin {
+                b'A' => Key::Up,
+                b'B' => Key::Down,
+                b'C' => Key::Right,
+                b'D' => Key::Left,
+                b'F' => Key::End,
+                b'H' => Key::Home,
+                b'P' => Key::F1,
+                b'Q' => Key::F2,
+                b'R' => Key::F3,
+                _ => Key::F4,
+            }
+        }
+        _ => return false,
+    }
+
+    // modifiers are sent as 1 + bit mask
+    let mods = csi.params[1].saturating_sub(1);
+    if mods & 0x01 != 0 {
+        inp_info.kmod.set_shift();
+    }
+    if mods & 0x02 != 0 {
+        inp_info.kmod.set_alt();
+    }
+    if mods & 0x04 != 0 {
+        inp_info.kmod.set_ctrl();
+    }
+
+    // key release event is consumed, but not reported
+    if csi.subparams[1] == 3 {
+        inp_info.kmod.mask = KEY_MOD_NONE;
+        inp_info.name = "KeyRelease";
+        return true;
+    }
+
+    if key != Key::None {
+        inp_info.kmod.mask |= KEY_MOD_SPECIAL;
+        inp_info.evnt = InputEvent::Key(key);
+        // use the name of the legacy sequence, if there is one
+        let mask = inp_info.kmod.mask;
+        inp_info.name = ESC_KEYS_MAP_UNSORTED
+            .iter()
+            .find(|km| km.key == key && km.kmod == mask)
+            .map(|km| km.name)
+            .or_else(|| {
+                SPECIAL_KEYS_MAP_UNSORTED
+                    .iter()
+                    .find(|km| km.key == key && km.kmod == mask)
+                    .map(|km| km.name)
+            })
+            .or_else(|| {
+                ESC_KEYS_MAP_UNSORTED
+                    .iter()
+                    .find(|km| km.key == key)
+                    .map(|km| km.name)
+            })
+            .unwrap_or("<Key>");
+    }
+    else if let Some(mut c) = chr {
+        if inp_info.kmod.has_ctrl() {
+            // Ctrl+letter is reported as an uppercase letter, like the legacy Ctrl+[A..Z]
+            c = c.to_ascii_uppercase();
+        }
+
+        inp_info.evnt = InputEvent::Char(CharBuff::from(c));
+        let mask = inp_info.kmod.mask;
+        inp_info.name = CTRL_KEYS_MAP_SORTED
+            .iter()
+            .find(|km| km.key as char == c && km.kmod == mask)
+            .map(|km| km.name)
+            .unwrap_or("<Char>");
+    }
+    else {
+        inp_info.kmod.mask = KEY_MOD_NONE;
+        inp_info.name = "<Key>";
+    }
+
+    true
+}
+
 // -----------------------------------------------------------------------------
 
+/// Default time after which the sole ESC is reported as the Esc key, in milliseconds
+pub const ESC_TIMEOUT_MS: u16 = 50;
+
+/// Size of the buffer used by `Decoder::feed()`
+pub const FEED_BUFF_SIZE: usize = 32;
+
+/// Maximum length of the text reported by single `InputEvent::Paste`;
+/// longer pasted text is reported in parts
+pub const PASTE_MAX_LENGTH: usize = 4096;
+
 /// ESC sequence into Key description decoder
 pub struct Decoder {
     decode_fail_ctr: u8,
     prev_cr: u8,
     prev_esc_ignored: bool,
+    // inter-byte timeout, used by `decode_input_seq_at()`
+    esc_timeout_ms: u16,
+    // time of the last input queue growth
+    rx_timestamp: u32,
+    // input queue length after the last decoding
+    rx_len: usize,
+    // sequences registered at runtime, checked before the built-in map
+    user_keys: Vec<UserSeqMap>,
+    // bytes waiting for decoding, for `feed()`
+    feed_buff: [u8; FEED_BUFF_SIZE],
+    feed_len: usize,
+    // the bracketed paste text is being reported in parts
+    paste_pending: bool,
 }
 
 impl Decoder {
@@ -476,20 +839,178 @@ impl Decoder {
         self.decode_fail_ctr = 0;
         self.prev_cr = 0;
         self.prev_esc_ignored = false;
+        self.rx_timestamp = 0;
+        self.rx_len = 0;
+        self.feed_len = 0;
+        self.paste_pending = false;
+    }
+
+    /// Registers additional key sequence, with or without the leading ESC, like `"[[A"`;
+    /// up to 6 characters long (excluding ESC); it has priority over the built-in sequences;
+    /// `KEY_MOD_SPECIAL` is always added to `kmod`
+    pub fn add_key_seq(&mut self, seq: &str, name: &'static str, key: Key, kmod: u8) {
+        let seq = seq
+            .strip_prefix(AnsiCodes::ESC as u8 as char)
+            .unwrap_or(seq);
+        if seq.is_empty() || seq.len() > crate::esc::SEQ_MAX_LENGTH - 2 {
+            return;
+        }
+
+        self.user_keys.retain(|km| km.seq != seq);
+        self.user_keys.push(UserSeqMap {
+            seq: seq.into(),
+            name,
+            key,
+            kmod: kmod | KEY_MOD_SPECIAL,
+        });
+    }
+
+    /// Replaces all the registered sequences with the ones of the given terminal profile
+    pub fn set_term_profile(&mut self, profile: TermProfile) {
+        self.clear_key_seqs();
+
+        let maps: &[&[SeqMap]] = match profile {
+            TermProfile::Xterm | TermProfile::Vt220 => &[&XTERM_KEYS_MAP],
+            TermProfile::Rxvt => &[&RXVT_KEYS_MAP],
+            TermProfile::Putty => &[&PUTTY_KEYS_MAP, &LINUX_KEYS_MAP],
+            TermProfile::LinuxConsole => &[&LINUX_KEYS_MAP],
+        };
+
+        for km in maps.iter().flat_map(|map| map.iter()) {
+            self.add_key_seq(km.seq, km.name, km.key, km.kmod);
+        }
+    }
+
+    /// Removes all the sequences registered at runtime
+    pub fn clear_key_seqs(&mut self) {
+        self.user_keys.clear();
+    }
+
+    /// Sets the time after which the incomplete ESC sequence is no longer awaited,
+    /// and the sole ESC is reported as the Esc key; used by `decode_input_seq_at()`
+    pub fn set_esc_timeout(&mut self, timeout_ms: u16) {
+        self.esc_timeout_ms = timeout_ms;
     }
 
     /// Decodes input ESC sequence fetching bytes from queue;
     /// fills the output with decoded key/mouse event,
-    /// Returns number of bytes consumed from the queue; 0 if no valid data found
+    /// Returns number of bytes consumed from the queue, 0 if no valid data found;
+    /// for the `InputEvent::Paste` the value is capped at 255, while the entire text is consumed
     pub fn decode_input_seq(&mut self, input: &mut InputQue, inp_info: &mut InputInfo) -> u8 {
+        let (seq_len, consumed) = self.decode(input, inp_info, None, PASTE_MAX_LENGTH);
+        input.drain(..consumed);
+        seq_len
+    }
+
+    /// Decodes input ESC sequence like `decode_input_seq()`, but the sole ESC is reported as
+    /// the Esc key as soon as no more bytes were received for the `set_esc_timeout()` time,
+    /// and the incomplete sequence is kept until then;
+    /// `now_ms` is the current timestamp, like `Pal::get_timestamp_ms()`.
+    /// Shall be called periodically, also when there is no new input
+    pub fn decode_input_seq_at(
+        &mut self,
+        input: &mut InputQue,
+        inp_info: &mut InputInfo,
+        now_ms: u32,
+    ) -> u8 {
+        if input.len() != self.rx_len {
+            self.rx_timestamp = now_ms;
+        }
+
+        let idle_ms = now_ms.wrapping_sub(self.rx_timestamp);
+        let (seq_len, consumed) = self.decode(input, inp_info, Some(idle_ms), PASTE_MAX_LENGTH);
+        input.drain(..consumed);
+        self.rx_len = input.len();
+        seq_len
+    }
+
+    /// Push-style decoding, without the input queue:
+    /// appends the `byte` to the internal buffer and returns the event decoded, if any.
+    /// Sole ESC is reported by the next call of `poll()`;
+    /// the bracketed paste text is reported in parts that fit into the buffer;
+    /// heap is allocated only for the `InputEvent::Paste` text
+    pub fn feed(&mut self, byte: u8) -> Option<InputInfo> {
+        self.feed_push(byte);
+        self.poll()
+    }
+
+    /// Push-style decoding like `feed()`, with the sole ESC recognized by the inter-byte timeout,
+    /// like `decode_input_seq_at()`
+    pub fn feed_at(&mut self, byte: u8, now_ms: u32) -> Option<InputInfo> {
+        self.feed_push(byte);
+        self.rx_timestamp = now_ms;
+        self.poll_at(now_ms)
+    }
+
+    /// Decodes the next event from the bytes already given to `feed()`
+    pub fn poll(&mut self) -> Option<InputInfo> {
+        self.feed_decode(None)
+    }
+
+    /// Decodes the next event from the bytes already given to `feed_at()`;
+    /// shall be called periodically to report the sole ESC after the timeout
+    pub fn poll_at(&mut self, now_ms: u32) -> Option<InputInfo> {
+        let idle_ms = now_ms.wrapping_sub(self.rx_timestamp);
+        self.feed_decode(Some(idle_ms))
+    }
+
+    /// Returns iterator over the events decoded from `bytes`, using `feed()`
+    pub fn feed_slice<'a>(&'a mut self, bytes: &'a [u8]) -> FeedIter<'a> {
+        FeedIter {
+            dec: self,
+            bytes: bytes.iter(),
+            pending: false,
+        }
+    }
+
+    fn feed_push(&mut self, byte: u8) {
+        if self.feed_len == FEED_BUFF_SIZE {
+            // buffer full - drop the oldest byte;
+            // not expected, as the decoded sequences and paste parts are shorter
+            self.feed_buff.copy_within(1.., 0);
+            self.feed_len -= 1;
+        }
+
+        self.feed_buff[self.feed_len] = byte;
+        self.feed_len += 1;
+    }
+
+    fn feed_decode(&mut self, idle_ms: Option<u32>) -> Option<InputInfo> {
+        let mut inp_info = InputInfo::default();
+        let buff = self.feed_buff;
+        let (seq_len, consumed) = self.decode(
+            &buff[..self.feed_len],
+            &mut inp_info,
+            idle_ms,
+            FEED_BUFF_SIZE - crate::esc::PASTE_BEGIN.len(),
+        );
+        self.feed_buff.copy_within(consumed..self.feed_len, 0);
+        self.feed_len -= consumed;
+        tetrary!(seq_len > 0, Some(inp_info), None)
+    }
+
+    /// Decoder implementation; `idle_ms` is the time since the last input byte was received,
+    /// if known; `paste_max_len` limits the length of the single `InputEvent::Paste` text
+    fn decode(
+        &mut self,
+        data: &[u8],
+        inp_info: &mut InputInfo,
+        idle_ms: Option<u32>,
+        paste_max_len: usize,
+    ) -> (u8, usize) {
         inp_info.reset();
 
-        if input.is_empty() {
-            return 0;
+        // more bytes of the incomplete sequence may still come
+        let waiting = match idle_ms {
+            Some(idle) => idle < self.esc_timeout_ms as u32,
+            None => true,
+        };
+
+        if data.is_empty() {
+            return (0, 0);
         }
 
-        let read_seq_from_queue = |inp: &InputQue, out: &mut [u8]| -> usize {
-            // out.copy_from_slice() impossible as Deque is noncontiguous
+        let read_seq_from_queue = |inp: &[u8], out: &mut [u8]| -> usize {
             let count = (out.len() - 1).min(inp.len());
             let mut it = inp.iter();
             #[allow(clippy::needless_range_loop)]
@@ -503,7 +1024,20 @@ impl Decoder {
 
         let mut seq = [0u8; crate::esc::SEQ_MAX_LENGTH];
 
-        while !input.is_empty() {
+        // number of bytes skipped before the decoded sequence
+        let mut dropped = 0;
+
+        if self.paste_pending {
+            // next part of the long pasted text
+            match self.decode_paste(data, inp_info, waiting, paste_max_len) {
+                // only the end marker left; decode what follows
+                (0, consumed) if consumed > 0 => dropped = consumed,
+                res => return res,
+            }
+        }
+
+        while dropped < data.len() {
+            let input = &data[dropped..];
             let seq_sz = read_seq_from_queue(input, &mut seq);
             self.prev_cr >>= 1; // set = 2 and then shift is faster than: if(prevCR) prevCR--;
 
@@ -511,46 +1045,117 @@ impl Decoder {
             //    check for two following ESC characters to avoid lock
             if seq_sz > 1 && seq[0] == AnsiCodes::ESC as u8 && seq[1] != AnsiCodes::ESC as u8 {
                 if seq_sz < 3 {
-                    // sequence too short
-                    return 0;
+                    if waiting {
+                        // sequence too short
+                        return (0, dropped);
+                    }
+
+                    // nothing more came in time - report the sole Esc key
+                    inp_info.evnt = InputEvent::Key(Key::Esc);
+                    inp_info.kmod.mask = KEY_MOD_SPECIAL;
+                    inp_info.name = "Esc";
+                    return (1, dropped + 1);
                 }
 
                 self.prev_esc_ignored = false;
 
                 // check mouse code
                 if seq_sz >= 6 && seq[1] == b'[' && seq[2] == b'M' {
-                    let mouse_btn = seq[3] - b' ';
-                    let mut mi = MouseInfo::default();
-                    match mouse_btn & 0xE3 {
-                        0x00 => mi.evt = MouseEvent::ButtonLeft,
-                        0x01 => mi.evt = MouseEvent::ButtonMid,
-                        0x02 => mi.evt = MouseEvent::ButtonRight,
-                        0x03 => mi.evt = MouseEvent::ButtonReleased,
-                        0x80 => mi.evt = MouseEvent::ButtonGoBack,
-                        0x81 => mi.evt = MouseEvent::ButtonGoForward,
-                        0x40 => mi.evt = MouseEvent::WheelUp,
-                        0x41 => mi.evt = MouseEvent::WheelDown,
-                        _ => mi.evt = MouseEvent::None,
-                    }
+                    let mut mi = decode_mouse_btn(seq[3].wrapping_sub(b' '), false, inp_info);
+                    // protect against possible substract with overflow
+                    mi.col = seq[4].saturating_sub(b' ') as u16;
+                    mi.row = seq[5].saturating_sub(b' ') as u16;
 
-                    if mouse_btn & 0x04 != 0 {
-                        inp_info.kmod.set_shift();
-                    }
-                    if mouse_btn & 0x08 != 0 {
-                        inp_info.kmod.set_alt();
+                    inp_info.evnt = InputEvent::Mouse(mi);
+                    inp_info.name = "MouseEvent";
+                    return (6, dropped + 6);
+                }
+
+                // check SGR mouse code; it is longer than the regular ESC sequence
+                if seq[1] == b'[' && seq[2] == b'<' {
+                    let mut seq = [0u8; CSI_SEQ_MAX_LENGTH];
+                    let seq_sz = read_seq_from_queue(input, &mut seq);
+
+                    if let Some((params, released, len)) = parse_sgr_mouse(&seq[3..seq_sz]) {
+                        let mut mi = decode_mouse_btn(params[0] as u8, released, inp_info);
+                        mi.col = params[1];
+                        mi.row = params[2];
+
+                        inp_info.evnt = InputEvent::Mouse(mi);
+                        inp_info.name = "MouseEvent";
+                        return (3 + len as u8, dropped + 3 + len);
                     }
-                    if mouse_btn & 0x10 != 0 {
-                        inp_info.kmod.set_ctrl();
+
+                    if waiting
+                        && seq[3..seq_sz]
+                            .iter()
+                            .all(|b| b.is_ascii_digit() || *b == b';')
+                    {
+                        // sequence not complete yet
+                        return (0, dropped);
                     }
+                }
 
-                    // protect against possible substract with overflow
-                    mi.col = seq[4].saturating_sub(b' ');
-                    mi.row = seq[5].saturating_sub(b' ');
+                // check focus report
+                if seq[1] == b'[' && (seq[2] == b'I' || seq[2] == b'O') {
+                    inp_info.evnt = InputEvent::Focus(seq[2] == b'I');
+                    inp_info.name = tetrary!(seq[2] == b'I', "FocusIn", "FocusOut");
+                    return (3, dropped + 3);
+                }
 
-                    inp_info.evnt = InputEvent::Mouse(mi);
-                    inp_info.name = "MouseEvent";
-                    input.drain(..6);
-                    return 6;
+                // check bracketed paste; wait until the entire text is received
+                if seq[..seq_sz].starts_with(crate::esc::PASTE_BEGIN.as_bytes()) {
+                    let begin_len = crate::esc::PASTE_BEGIN.len();
+                    let text = &input[begin_len..];
+
+                    return match self.decode_paste(text, inp_info, waiting, paste_max_len) {
+                        // nothing more came in time - drop the sole marker
+                        (0, 0) if !waiting => (0, dropped + begin_len),
+                        (0, 0) => (0, dropped),
+                        (_, consumed) => {
+                            let consumed = begin_len + consumed;
+                            (consumed.min(u8::MAX as usize) as u8, dropped + consumed)
+                        }
+                    };
+                }
+
+                // check sequences registered at runtime
+                if let Some(km) = self
+                    .user_keys
+                    .iter()
+                    .find(|km| seq[1..seq_sz].starts_with(km.seq.as_bytes()))
+                {
+                    inp_info.evnt = InputEvent::Key(km.key);
+                    inp_info.kmod.mask = km.kmod;
+                    inp_info.name = km.name;
+                    // +1 for ESC
+                    return (1 + km.seq.len() as u8, dropped + 1 + km.seq.len());
+                }
+
+                if waiting
+                    && self
+                        .user_keys
+                        .iter()
+                        .any(|km| km.seq.as_bytes().starts_with(&seq[1..seq_sz]))
+                {
+                    // sequence not complete yet
+                    return (0, dropped);
+                }
+
+                // check CSI sequence with the key code and modifiers, like the kitty keyboard protocol;
+                // it may be longer than the regular ESC sequence
+                let mut csi_seq = [0u8; CSI_SEQ_MAX_LENGTH];
+                let csi_seq_sz = read_seq_from_queue(input, &mut csi_seq);
+                let csi = tetrary!(
+                    seq[1] == b'[',
+                    parse_csi_params(&csi_seq[2..csi_seq_sz]),
+                    None
+                );
+
+                if let Some(csi) = csi.filter(|csi| csi.fin == b'u') {
+                    if decode_csi_key(&csi, inp_info) {
+                        return (2 + csi.len as u8, dropped + 2 + csi.len);
+                    }
                 }
 
                 // binary search: find key map in max 7 steps
@@ -558,8 +1163,26 @@ impl Decoder {
                     inp_info.evnt = InputEvent::Key(km.key);
                     inp_info.kmod.mask = km.kmod;
                     inp_info.name = km.name;
-                    input.drain(..1 + km.seq.len()); // +1 for ESC
-                    return 1 + km.seq.len() as u8;
+                    // +1 for ESC
+                    return (1 + km.seq.len() as u8, dropped + 1 + km.seq.len());
+                }
+
+                // key with modifiers combination not present in the map
+                if let Some(csi) = csi {
+                    if decode_csi_key(&csi, inp_info) {
+                        return (2 + csi.len as u8, dropped + 2 + csi.len);
+                    }
+                }
+
+                if waiting
+                    && seq[1] == b'['
+                    && csi_seq_sz < CSI_SEQ_MAX_LENGTH - 1
+                    && csi_seq[2..csi_seq_sz]
+                        .iter()
+                        .all(|b| b.is_ascii_digit() || *b == b';' || *b == b':')
+                {
+                    // sequence not complete yet
+                    return (0, dropped);
                 }
 
                 // ESC sequence invalid or unknown?
@@ -572,7 +1195,7 @@ impl Decoder {
                         if seq[i] == AnsiCodes::ESC as u8 {
                             esc_found = true;
                             // found next ESC, current seq is unknown
-                            input.drain(..i);
+                            dropped += i;
                             //dbg!("found at ", i);
                             break;
                         }
@@ -583,22 +1206,40 @@ impl Decoder {
                     }
                 }
 
+                if idle_ms.is_some() {
+                    if waiting {
+                        // the rest of sequence may still come
+                        return (0, dropped);
+                    }
+
+                    // nothing more came in time - invalid sequence
+                    self.decode_fail_ctr = 0;
+                    return (0, data.len());
+                }
+
                 self.decode_fail_ctr += 1;
                 if self.decode_fail_ctr == 3 {
                     // invalid sequence; drop entire buffer
                     self.decode_fail_ctr = 0;
-                    input.clear();
+                    dropped = data.len();
                 }
 
-                return 0;
+                return (0, dropped);
             }
             else {
                 // single character
-                if seq[0] == AnsiCodes::ESC as u8 && seq_sz == 1 && !self.prev_esc_ignored {
-                    // avoid situations where ESC not followed by another code
-                    // is decoded as freestanding Esc key
-                    self.prev_esc_ignored = true;
-                    return 0;
+                if seq[0] == AnsiCodes::ESC as u8 && seq_sz == 1 {
+                    match idle_ms {
+                        // wait for the ESC sequence data
+                        Some(_) if waiting => return (0, dropped),
+                        None if !self.prev_esc_ignored => {
+                            // avoid situations where ESC not followed by another code
+                            // is decoded as freestanding Esc key
+                            self.prev_esc_ignored = true;
+                            return (0, dropped);
+                        }
+                        _ => {}
+                    }
                 }
 
                 self.prev_esc_ignored = false;
@@ -615,7 +1256,7 @@ impl Decoder {
                             self.prev_cr = 2;
                         }
                         else if seq[0] == AnsiCodes::LF as u8 && self.prev_cr > 0 {
-                            input.drain(..1);
+                            dropped += 1;
                             self.prev_cr = 0;
                             skip = true;
                             break;
@@ -624,8 +1265,7 @@ impl Decoder {
                         inp_info.evnt = InputEvent::Key(km.key);
                         inp_info.kmod.mask = km.kmod;
                         inp_info.name = km.name;
-                        input.drain(..1);
-                        return 1;
+                        return (1, dropped + 1);
                     }
                 }
 
@@ -641,8 +1281,7 @@ impl Decoder {
                     inp_info.evnt = InputEvent::Char(cb);
                     inp_info.kmod.mask = km.kmod;
                     inp_info.name = km.name;
-                    input.drain(..1);
-                    return 1;
+                    return (1, dropped + 1);
                 }
 
                 // 4. regular ASCII character or UTF-8 sequence
@@ -668,29 +1307,74 @@ impl Decoder {
                         inp_info.evnt = InputEvent::Char(cb);
                         inp_info.name = "<Char>";
 
-                        input.drain(..utf8seqlen);
-                        return utf8seqlen as u8;
+                        return (utf8seqlen as u8, dropped + utf8seqlen);
                     }
                     else {
                         // drop the incomplete starting sequence
-                        input.drain(..valid_bytes);
+                        dropped += valid_bytes;
                     }
                 }
                 else {
                     // invalid/incomplete sequence?
                     if utf8seqlen > 0 {
                         // try next time with more data
-                        return 0;
+                        return (0, dropped);
                     }
                     else {
                         // invalid byte, not utf8_char_boundary
-                        input.drain(..1);
+                        dropped += 1;
                     }
                 }
             }
         }
 
-        0
+        (0, dropped)
+    }
+
+    /// Decodes the bracketed paste text, up to the end marker;
+    /// the text longer than `max_len` is reported in parts,
+    /// and the text not terminated in time is reported as it is
+    fn decode_paste(
+        &mut self,
+        text: &[u8],
+        inp_info: &mut InputInfo,
+        waiting: bool,
+        max_len: usize,
+    ) -> (u8, usize) {
+        let end_marker = crate::esc::PASTE_END.as_bytes();
+        let continued = self.paste_pending;
+
+        let (text_len, consumed) =
+            match text.windows(end_marker.len()).position(|w| w == end_marker) {
+                Some(text_len) => {
+                    self.paste_pending = false;
+                    (text_len, text_len + end_marker.len())
+                }
+                None if !waiting && !text.is_empty() => {
+                    // nothing more came in time - the end marker is lost
+                    self.paste_pending = false;
+                    (text.len(), text.len())
+                }
+                None if text.len() >= max_len => {
+                    // keep the possible beginning of the end marker and the incomplete UTF-8 char
+                    let mut part_len = (text.len() + 1 - end_marker.len()).min(max_len);
+                    while part_len > 1 && utils::utf8_char_width(text[part_len]) == 0 {
+                        part_len -= 1;
+                    }
+                    self.paste_pending = true;
+                    (part_len, part_len)
+                }
+                None => return (0, 0),
+            };
+
+        if continued && text_len == 0 {
+            // only the end marker left
+            return (0, consumed);
+        }
+
+        inp_info.evnt = InputEvent::Paste(String::from_utf8_lossy(&text[..text_len]).into());
+        inp_info.name = "Paste";
+        (consumed.min(u8::MAX as usize) as u8, consumed)
     }
 }
 
@@ -701,10 +1385,47 @@ impl Default for Decoder {
             decode_fail_ctr: 0,
             prev_cr: 0,
             prev_esc_ignored: false,
+            esc_timeout_ms: ESC_TIMEOUT_MS,
+            rx_timestamp: 0,
+            rx_len: 0,
+            user_keys: Vec::new(),
+            feed_buff: [0; FEED_BUFF_SIZE],
+            feed_len: 0,
+            paste_pending: false,
         }
     }
 }
 
+/// Iterator over events decoded from the byte slice, returned by `Decoder::feed_slice()`
+pub struct FeedIter<'a> {
+    dec: &'a mut Decoder,
+    bytes: core::slice::Iter<'a, u8>,
+    // last event was decoded - the buffer may contain more of them
+    pending: bool,
+}
+
+impl Iterator for FeedIter<'_> {
+    type Item = InputInfo;
+
+    fn next(&mut self) -> Option<InputInfo> {
+        if self.pending {
+            if let Some(inp_info) = self.dec.poll() {
+                return Some(inp_info);
+            }
+            self.pending = false;
+        }
+
+        for b in self.bytes.by_ref() {
+            if let Some(inp_info) = self.dec.feed(*b) {
+                self.pending = true;
+                return Some(inp_info);
+            }
+        }
+
+        None
+    }
+}
+
 /// Prepares displayable form of input bytes sequence
 pub fn inp_seq_debug(inp_seq: &[u8]) -> String {
     let mut out = String::with_capacity(10);
@@ -809,6 +1530,12 @@ mod tests {
             assert!(opt.is_none());
         }
 
+        {
+            // input incomplete
+            let opt = seq_binary_search(b"[1;5", &ESC_KEYS_MAP_SORTED);
+            assert!(opt.is_none());
+        }
+
         {
             // input followed by "BC"
             let opt = seq_binary_search(b"[ABC", &ESC_KEYS_MAP_SORTED);
@@ -0,0 +1,325 @@
+//! # RTWins input macros
+//!
+//! Recording of the decoded input events with their relative timing, and the playback.
+//! The macro is stored as terminal input sequences; its text form contains one event per line:
+//! `<delay_ms> <sequence>`, with control characters escaped, like `120 \e[1;5A`
+
+use crate::esc;
+use crate::input::*;
+use crate::input_decoder::{Decoder, InputQue};
+use crate::tetrary;
+use crate::wgt::{self, WindowState};
+
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Single macro step: input sequence and the delay since the previous step
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MacroStep {
+    pub delay_ms: u32,
+    pub seq: String,
+}
+
+/// Recorded input macro
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputMacro {
+    pub steps: Vec<MacroStep>,
+}
+
+impl InputMacro {
+    /// Serializes the macro to the text form
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+
+        for step in self.steps.iter() {
+            let _ = write!(out, "{} ", step.delay_ms);
+            escape_seq(&step.seq, &mut out);
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Parses the macro text form; empty lines and lines starting with `#` are skipped;
+    /// returns None if any line is malformed
+    pub fn from_text(text: &str) -> Option<Self> {
+        let mut mcr = InputMacro::default();
+
+        for line in text.lines() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (delay, seq) = line.split_once(' ')?;
+            mcr.steps.push(MacroStep {
+                delay_ms: delay.parse().ok()?,
+                seq: unescape_seq(seq)?,
+            });
+        }
+
+        Some(mcr)
+    }
+
+    /// Returns the macro events with their delays
+    pub fn events(&self) -> Vec<(u32, InputInfo)> {
+        let mut dec = Decoder::default();
+        let mut inp = InputQue::new();
+        let mut out = Vec::with_capacity(self.steps.len());
+
+        for step in self.steps.iter() {
+            let mut delay_ms = step.delay_ms;
+            inp.clear();
+            inp.extend(step.seq.as_bytes());
+
+            loop {
+                let mut ii = InputInfo::default();
+                if dec.decode_input_seq(&mut inp, &mut ii) == 0 {
+                    break;
+                }
+
+                if !matches!(ii.evnt, InputEvent::None) {
+                    out.push((delay_ms, ii));
+                    delay_ms = 0;
+                }
+            }
+        }
+
+        out
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Records the input events into macro
+#[derive(Default)]
+pub struct MacroRecorder {
+    mcr: InputMacro,
+    last_ts: Option<u32>,
+    recording: bool,
+}
+
+impl MacroRecorder {
+    /// Starts a new recording
+    pub fn start(&mut self) {
+        self.mcr.steps.clear();
+        self.last_ts = None;
+        self.recording = true;
+    }
+
+    /// Stops the recording and returns the recorded macro
+    pub fn stop(&mut self) -> InputMacro {
+        self.recording = false;
+        core::mem::take(&mut self.mcr)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Records the event, if recording is active;
+    /// `now_ms` is the current timestamp, like `Pal::get_timestamp_ms()`
+    pub fn record(&mut self, ii: &InputInfo, now_ms: u32) {
+        if !self.recording {
+            return;
+        }
+
+        let mut seq = String::new();
+        encode_event(ii, &mut seq);
+        if seq.is_empty() {
+            return;
+        }
+
+        let delay_ms = self.last_ts.map_or(0, |ts| now_ms.wrapping_sub(ts));
+        self.last_ts = Some(now_ms);
+        self.mcr.steps.push(MacroStep { delay_ms, seq });
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Plays the macro back, keeping the recorded timing
+pub struct MacroPlayer {
+    events: Vec<(u32, InputInfo)>,
+    next: usize,
+    last_ts: Option<u32>,
+}
+
+impl MacroPlayer {
+    pub fn new(mcr: &InputMacro) -> Self {
+        Self {
+            events: mcr.events(),
+            next: 0,
+            last_ts: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    /// Returns the next event, if its delay elapsed;
+    /// the delays are counted from the first call
+    pub fn poll(&mut self, now_ms: u32) -> Option<InputInfo> {
+        let (delay_ms, ii) = self.events.get(self.next)?;
+        let last_ts = *self.last_ts.get_or_insert(now_ms);
+
+        if now_ms.wrapping_sub(last_ts) < *delay_ms {
+            return None;
+        }
+
+        // count from the planned time, not to accumulate delays
+        self.last_ts = Some(last_ts.wrapping_add(*delay_ms));
+        self.next += 1;
+        Some(ii.clone())
+    }
+
+    /// Passes all the due events to the window; returns the number of events played
+    pub fn play(&mut self, ws: &mut dyn WindowState, now_ms: u32) -> usize {
+        let mut played = 0;
+
+        while let Some(ii) = self.poll(now_ms) {
+            wgt::process_input(ws, &ii);
+            played += 1;
+        }
+
+        played
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Control Sequence Introducer
+const CSI: &str = "\x1B[";
+
+/// Encodes the event as the terminal input sequence understood by the `Decoder`
+fn encode_event(ii: &InputInfo, out: &mut String) {
+    // modifiers parameter, like in the xterm/kitty sequences
+    let mods = 1
+        + tetrary!(ii.kmod.has_shift(), 1, 0)
+        + tetrary!(ii.kmod.has_alt(), 2, 0)
+        + tetrary!(ii.kmod.has_ctrl(), 4, 0);
+
+    match ii.evnt {
+        InputEvent::None => {}
+        InputEvent::Char(ref cb) => {
+            if !ii.kmod.has_ctrl() && !ii.kmod.has_alt() {
+                out.push_str(cb.as_str());
+            }
+            else {
+                // Ctrl+letter is decoded as an uppercase letter
+                let code = cb.as_char().to_ascii_lowercase() as u32;
+                let _ = write!(out, "{}{};{}u", CSI, code, mods);
+            }
+        }
+        InputEvent::Key(key) => {
+            let (code, fin) = match key {
+                Key::None => return,
+                Key::Up => (1, 'A'),
+                Key::Down => (1, 'B'),
+                Key::Right => (1, 'C'),
+                Key::Left => (1, 'D'),
+                Key::End => (1, 'F'),
+                Key::Home => (1, 'H'),
+                Key::F1 => (1, 'P'),
+                Key::F2 => (1, 'Q'),
+                Key::F3 => (1, 'R'),
+                Key::F4 => (1, 'S'),
+                Key::Insert => (2, '~'),
+                Key::Delete => (3, '~'),
+                Key::PgUp => (5, '~'),
+                Key::PgDown => (6, '~'),
+                Key::F5 => (15, '~'),
+                Key::F6 => (17, '~'),
+                Key::F7 => (18, '~'),
+                Key::F8 => (19, '~'),
+                Key::F9 => (20, '~'),
+                Key::F10 => (21, '~'),
+                Key::F11 => (23, '~'),
+                Key::F12 => (24, '~'),
+                Key::Tab => (9, 'u'),
+                Key::Enter => (13, 'u'),
+                Key::Esc => (27, 'u'),
+                Key::Backspace => (127, 'u'),
+                Key::Pause => (57362, 'u'),
+            };
+
+            let _ = write!(out, "{}{};{}{}", CSI, code, mods, fin);
+        }
+        InputEvent::Mouse(ref mi) => {
+            let btn = mi.btn.map_or(3, |b| b as u16);
+            let (code, fin) = match mi.evt {
+                MouseEvent::None => return,
+                MouseEvent::ButtonLeft => (0, 'M'),
+                MouseEvent::ButtonMid => (1, 'M'),
+                MouseEvent::ButtonRight => (2, 'M'),
+                MouseEvent::ButtonReleased => (btn, tetrary!(btn == 3, 'M', 'm')),
+                MouseEvent::WheelUp => (64, 'M'),
+                MouseEvent::WheelDown => (65, 'M'),
+                MouseEvent::ButtonGoBack => (128, 'M'),
+                MouseEvent::ButtonGoForward => (129, 'M'),
+                MouseEvent::Drag(b) => (32 + b as u16, 'M'),
+                MouseEvent::Move => (35, 'M'),
+            };
+            let code = code + (mods - 1) * 4;
+            let _ = write!(out, "{}<{};{};{}{}", CSI, code, mi.col, mi.row, fin);
+        }
+        InputEvent::Paste(ref text) => {
+            out.push_str(esc::PASTE_BEGIN);
+            out.push_str(text);
+            out.push_str(esc::PASTE_END);
+        }
+        InputEvent::Focus(focused) => {
+            out.push_str(tetrary!(focused, esc::FOCUS_IN, esc::FOCUS_OUT));
+        }
+    }
+}
+
+/// Escapes control characters of the sequence
+fn escape_seq(seq: &str, out: &mut String) {
+    for c in seq.chars() {
+        match c {
+            '\x1B' => out.push_str("\\e"),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\x{:02X}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+}
+
+/// Reverts the `escape_seq()`
+fn unescape_seq(text: &str) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut it = text.chars();
+
+    while let Some(c) = it.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match it.next()? {
+            'e' => out.push('\x1B'),
+            '\\' => out.push('\\'),
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            'x' => {
+                let hex: String = it.by_ref().take(2).collect();
+                let code = u32::from_str_radix(&hex, 16).ok()?;
+                out.push(char::from_u32(code)?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(out)
+}
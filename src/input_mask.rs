@@ -0,0 +1,175 @@
+//! # RTWins TextEdit input masks
+//!
+//! Mask like `"99:99:99"` or `"HH-HH-HH-HH-HH-HH"` defines the fixed-length text,
+//! where the placeholders accept only matching characters and the literals are skipped by the cursor.
+//! Placeholders: `9` digit, `H` hex digit, `A` letter, `N` letter or digit, `?` any character;
+//! `\` makes the following placeholder character a literal
+
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Displayed in place of the empty placeholder
+pub const MASK_EMPTY_CHAR: char = '_';
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum MaskItem {
+    Literal(char),
+    Placeholder(char),
+}
+
+/// Parsed input mask
+#[derive(Clone, Debug)]
+pub struct InputMask {
+    items: Vec<MaskItem>,
+}
+
+impl InputMask {
+    pub fn new(mask: &str) -> Self {
+        let mut items = Vec::with_capacity(mask.len());
+        let mut it = mask.chars();
+
+        while let Some(c) = it.next() {
+            match c {
+                '9' | 'H' | 'A' | 'N' | '?' => items.push(MaskItem::Placeholder(c)),
+                '\\' => {
+                    if let Some(c) = it.next() {
+                        items.push(MaskItem::Literal(c));
+                    }
+                }
+                c => items.push(MaskItem::Literal(c)),
+            }
+        }
+
+        Self { items }
+    }
+
+    /// Number of characters of the masked text
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Formats the text to the mask: fills the placeholders with the text characters,
+    /// skipping the ones equal to the literals or not accepted by the placeholders
+    pub fn apply(&self, txt: &str) -> String {
+        let mut out = String::with_capacity(self.items.len() * 2);
+        let mut it = txt.chars().peekable();
+
+        for item in self.items.iter() {
+            match *item {
+                MaskItem::Literal(lit) => {
+                    it.next_if_eq(&lit);
+                    out.push(lit);
+                }
+                MaskItem::Placeholder(ph) => {
+                    let mut c = MASK_EMPTY_CHAR;
+                    while let Some(&tc) = it.peek() {
+                        if tc != MASK_EMPTY_CHAR && !accepts(ph, tc) && self.is_literal(tc) {
+                            // placeholder left empty, like the hour in "9:30" for "99:99"
+                            break;
+                        }
+
+                        it.next();
+                        if tc == MASK_EMPTY_CHAR || accepts(ph, tc) {
+                            c = tc;
+                            break;
+                        }
+                    }
+                    out.push(c);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Checks if all the placeholders are filled
+    pub fn is_complete(&self, txt: &str) -> bool {
+        txt.chars().count() == self.items.len()
+            && self
+                .items
+                .iter()
+                .zip(txt.chars())
+                .all(|(item, c)| match *item {
+                    MaskItem::Literal(lit) => c == lit,
+                    MaskItem::Placeholder(ph) => accepts(ph, c),
+                })
+    }
+
+    /// Returns the first empty placeholder position of the masked text, or the mask length
+    pub fn first_empty(&self, txt: &str) -> usize {
+        self.items
+            .iter()
+            .zip(txt.chars())
+            .position(|(item, c)| matches!(item, MaskItem::Placeholder(_)) && c == MASK_EMPTY_CHAR)
+            .unwrap_or(self.items.len())
+    }
+
+    /// Returns the first placeholder position at or after `pos`, or the mask length
+    pub fn next_pos(&self, pos: usize) -> usize {
+        (pos..self.items.len())
+            .find(|&i| matches!(self.items[i], MaskItem::Placeholder(_)))
+            .unwrap_or(self.items.len())
+    }
+
+    /// Returns the last placeholder position before `pos`
+    pub fn prev_pos(&self, pos: usize) -> Option<usize> {
+        (0..pos.min(self.items.len()))
+            .rev()
+            .find(|&i| matches!(self.items[i], MaskItem::Placeholder(_)))
+    }
+
+    /// Puts the character at placeholder position `pos` of the masked text;
+    /// returns the next cursor position, or None if the character is not accepted
+    pub fn put_char(&self, txt: &mut String, pos: usize, c: char) -> Option<usize> {
+        let pos = self.next_pos(pos);
+
+        match self.items.get(pos) {
+            Some(MaskItem::Placeholder(ph)) if accepts(*ph, c) => {
+                set_char_at(txt, pos, c);
+                Some(self.next_pos(pos + 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// Clears the placeholders in the `from..to` range of the masked text
+    pub fn clear(&self, txt: &mut String, from: usize, to: usize) {
+        for pos in from..to.min(self.items.len()) {
+            if let MaskItem::Placeholder(_) = self.items[pos] {
+                set_char_at(txt, pos, MASK_EMPTY_CHAR);
+            }
+        }
+    }
+
+    /// Checks if the character is one of the mask literals
+    pub fn is_literal(&self, c: char) -> bool {
+        self.items.contains(&MaskItem::Literal(c))
+    }
+}
+
+/// Checks if the placeholder accepts the character
+fn accepts(placeholder: char, c: char) -> bool {
+    match placeholder {
+        '9' => c.is_ascii_digit(),
+        'H' => c.is_ascii_hexdigit(),
+        'A' => c.is_alphabetic(),
+        'N' => c.is_alphanumeric(),
+        _ => !c.is_control(),
+    }
+}
+
+fn set_char_at(txt: &mut String, pos: usize, c: char) {
+    let mut chars: Vec<char> = txt.chars().collect();
+
+    if let Some(tc) = chars.get_mut(pos) {
+        *tc = c;
+        *txt = chars.into_iter().collect();
+    }
+}
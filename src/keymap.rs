@@ -0,0 +1,223 @@
+//! # RTWins key bindings
+//!
+//! Maps keys with modifiers to abstract actions used by the widgets keyboard processing;
+//! the default map may be changed at runtime, eg. for vi-style `j`/`k` or keypads without Tab key
+
+use crate::input::*;
+
+use atomic_once_cell::AtomicLazy;
+use try_lock::TryLock;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Widget navigation and editing action
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    /// Focus next widget
+    FocusNext,
+    /// Focus previous widget
+    FocusPrev,
+    /// Focus the parent widget
+    FocusParent,
+    /// Focus the nearest widget on the left
+    FocusLeft,
+    /// Focus the nearest widget on the right
+    FocusRight,
+    /// Focus the nearest widget above
+    FocusUp,
+    /// Focus the nearest widget below
+    FocusDown,
+    /// Click the button, confirm the selection, start/finish text editing
+    Activate,
+    /// Toggle the check box, select the radio, drop down the combo box
+    Toggle,
+    /// Cancel text editing, close the drop-down list; otherwise acts like `FocusParent`
+    Cancel,
+    /// Move the selection up; if not handled by the widget, acts like `FocusUp`
+    LineUp,
+    /// Move the selection down; if not handled by the widget, acts like `FocusDown`
+    LineDown,
+    PageUp,
+    PageDown,
+    /// Previous page of the page control
+    PrevPage,
+    /// Next page of the page control
+    NextPage,
+    /// Move the text cursor left; if not handled by the widget, acts like `FocusLeft`
+    CursorLeft,
+    /// Move the text cursor right; if not handled by the widget, acts like `FocusRight`
+    CursorRight,
+    CursorHome,
+    CursorEnd,
+    /// Delete the character before the cursor
+    DeleteBack,
+    /// Delete the character at the cursor
+    DeleteForward,
+    /// Delete all the characters before the cursor
+    DeleteToStart,
+    /// Delete all the characters from the cursor
+    DeleteToEnd,
+    /// Extend the selection one character left
+    SelectLeft,
+    /// Extend the selection one character right
+    SelectRight,
+    /// Extend the selection one line up
+    SelectUp,
+    /// Extend the selection one line down
+    SelectDown,
+    /// Extend the selection to the beginning
+    SelectHome,
+    /// Extend the selection to the end
+    SelectEnd,
+    /// Select the entire text
+    SelectAll,
+    /// Copy the selected text, or the entire text, to the clipboard
+    Copy,
+    /// Move the selected text, or the entire text, to the clipboard
+    Cut,
+    /// Insert the clipboard text at the cursor
+    Paste,
+    /// Revert the last text change
+    Undo,
+    /// Restore the reverted text change
+    Redo,
+    /// Open the search prompt
+    Find,
+    /// Move to the next search match
+    FindNext,
+    /// Move to the previous search match
+    FindPrev,
+}
+
+/// Key the action is bound to
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum KeyCode {
+    Key(Key),
+    Char(char),
+}
+
+/// Single key binding
+#[derive(Clone, Copy, Debug)]
+struct KeyBinding {
+    code: KeyCode,
+    // key modifiers, like KEY_MOD_CTRL, without KEY_MOD_SPECIAL
+    kmod: u8,
+    action: Action,
+}
+
+/// Key to action map
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<KeyBinding>,
+}
+
+impl Keymap {
+    /// Creates an empty map, without any binding; `Keymap::default()` has the default bindings
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Binds the key with modifiers (like `KEY_MOD_CTRL`) to the action,
+    /// replacing the previous binding of that key
+    pub fn bind(&mut self, code: KeyCode, kmod: u8, action: Action) {
+        self.unbind(code, kmod);
+        self.bindings.push(KeyBinding {
+            code,
+            kmod: kmod & !KEY_MOD_SPECIAL,
+            action,
+        });
+    }
+
+    /// Removes the key binding
+    pub fn unbind(&mut self, code: KeyCode, kmod: u8) {
+        let kmod = kmod & !KEY_MOD_SPECIAL;
+        self.bindings
+            .retain(|kb| kb.code != code || kb.kmod != kmod);
+    }
+
+    /// Removes all bindings of the action
+    pub fn unbind_action(&mut self, action: Action) {
+        self.bindings.retain(|kb| kb.action != action);
+    }
+
+    /// Returns the action bound to the key event
+    pub fn action(&self, ii: &InputInfo) -> Option<Action> {
+        let code = match ii.evnt {
+            InputEvent::Key(key) => KeyCode::Key(key),
+            InputEvent::Char(ref cb) => KeyCode::Char(cb.as_char()),
+            _ => return None,
+        };
+        let kmod = ii.kmod.mask & !KEY_MOD_SPECIAL;
+
+        self.bindings
+            .iter()
+            .find(|kb| kb.code == code && kb.kmod == kmod)
+            .map(|kb| kb.action)
+    }
+}
+
+impl Default for Keymap {
+    /// Creates the map with default bindings
+    fn default() -> Self {
+        use Action::*;
+        let mut km = Self::empty();
+
+        km.bind(KeyCode::Key(Key::Tab), KEY_MOD_NONE, FocusNext);
+        km.bind(KeyCode::Key(Key::Tab), KEY_MOD_SHIFT, FocusPrev);
+        km.bind(KeyCode::Key(Key::Esc), KEY_MOD_NONE, Cancel);
+        km.bind(KeyCode::Key(Key::Enter), KEY_MOD_NONE, Activate);
+        km.bind(KeyCode::Char(' '), KEY_MOD_NONE, Toggle);
+        km.bind(KeyCode::Key(Key::Up), KEY_MOD_NONE, LineUp);
+        km.bind(KeyCode::Key(Key::Down), KEY_MOD_NONE, LineDown);
+        km.bind(KeyCode::Key(Key::PgUp), KEY_MOD_NONE, PageUp);
+        km.bind(KeyCode::Key(Key::PgDown), KEY_MOD_NONE, PageDown);
+        km.bind(KeyCode::Key(Key::PgUp), KEY_MOD_CTRL, PrevPage);
+        km.bind(KeyCode::Key(Key::PgDown), KEY_MOD_CTRL, NextPage);
+        km.bind(KeyCode::Key(Key::F11), KEY_MOD_NONE, PrevPage);
+        km.bind(KeyCode::Key(Key::F12), KEY_MOD_NONE, NextPage);
+        km.bind(KeyCode::Key(Key::Left), KEY_MOD_NONE, CursorLeft);
+        km.bind(KeyCode::Key(Key::Right), KEY_MOD_NONE, CursorRight);
+        km.bind(KeyCode::Key(Key::Home), KEY_MOD_NONE, CursorHome);
+        km.bind(KeyCode::Key(Key::End), KEY_MOD_NONE, CursorEnd);
+        km.bind(KeyCode::Key(Key::Backspace), KEY_MOD_NONE, DeleteBack);
+        km.bind(KeyCode::Key(Key::Backspace), KEY_MOD_CTRL, DeleteToStart);
+        km.bind(KeyCode::Key(Key::Delete), KEY_MOD_NONE, DeleteForward);
+        km.bind(KeyCode::Key(Key::Delete), KEY_MOD_CTRL, DeleteToEnd);
+        km.bind(KeyCode::Key(Key::Left), KEY_MOD_SHIFT, SelectLeft);
+        km.bind(KeyCode::Key(Key::Right), KEY_MOD_SHIFT, SelectRight);
+        km.bind(KeyCode::Key(Key::Up), KEY_MOD_SHIFT, SelectUp);
+        km.bind(KeyCode::Key(Key::Down), KEY_MOD_SHIFT, SelectDown);
+        km.bind(KeyCode::Key(Key::Home), KEY_MOD_SHIFT, SelectHome);
+        km.bind(KeyCode::Key(Key::End), KEY_MOD_SHIFT, SelectEnd);
+        km.bind(KeyCode::Char('A'), KEY_MOD_CTRL, SelectAll);
+        km.bind(KeyCode::Char('C'), KEY_MOD_CTRL, Copy);
+        km.bind(KeyCode::Char('X'), KEY_MOD_CTRL, Cut);
+        km.bind(KeyCode::Char('V'), KEY_MOD_CTRL, Paste);
+        km.bind(KeyCode::Key(Key::Insert), KEY_MOD_CTRL, Copy);
+        km.bind(KeyCode::Key(Key::Delete), KEY_MOD_SHIFT, Cut);
+        km.bind(KeyCode::Key(Key::Insert), KEY_MOD_SHIFT, Paste);
+        km.bind(KeyCode::Char('Z'), KEY_MOD_CTRL, Undo);
+        km.bind(KeyCode::Char('Y'), KEY_MOD_CTRL, Redo);
+        km.bind(KeyCode::Char('F'), KEY_MOD_CTRL, Find);
+        km.bind(KeyCode::Char('/'), KEY_MOD_NONE, Find);
+        km.bind(KeyCode::Key(Key::F3), KEY_MOD_NONE, FindNext);
+        km.bind(KeyCode::Char('n'), KEY_MOD_NONE, FindNext);
+        km.bind(KeyCode::Key(Key::F3), KEY_MOD_SHIFT, FindPrev);
+        km.bind(KeyCode::Char('N'), KEY_MOD_NONE, FindPrev);
+        km
+    }
+}
+
+/// Key bindings used by the widgets
+pub static KEYMAP: AtomicLazy<TryLock<Keymap>> =
+    AtomicLazy::new(|| TryLock::new(Keymap::default()));
+
+/// Returns the action bound to the key event in the global `KEYMAP`
+pub fn action_of(ii: &InputInfo) -> Option<Action> {
+    KEYMAP.try_lock().and_then(|km| km.action(ii))
+}
@@ -15,12 +15,18 @@ pub const VER: &str = env!("CARGO_PKG_VERSION");
 // public modules
 pub mod colors;
 pub mod common;
+pub mod dialog;
 pub mod esc;
 pub mod input;
 pub mod input_decoder;
+pub mod input_macro;
+pub mod input_mask;
+pub mod keymap;
+pub mod markup;
 pub mod pal;
 pub mod string_ext;
 pub mod utils;
+pub mod validator;
 pub mod wnd_manager;
 
 // private modules
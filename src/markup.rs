@@ -0,0 +1,298 @@
+//! # RTWins rich text markup
+//!
+//! Markup for the Label and TextBox texts, safe to edit without knowing the ESC sequences:
+//! `[b]bold[/b]`, `[i]italics[/i]`, `[u]underline[/u]`, `[s]strikethrough[/s]`, `[r]inverse[/r]`,
+//! `[fg=red]color[/fg]`, `[bg=blue_intense]background[/bg]`.
+//! `[/]` closes the last opened tag, closing tag restores the style from before its opening tag;
+//! `[[` is the literal `[`; unknown and unmatched tags are left as the text.
+//! Colors: `default`, `black`, `red`, `green`, `yellow`, `blue`, `magenta`, `cyan`, `white`,
+//! optionally with the `_intense` suffix
+
+use crate::colors::{ColorBg, ColorFg};
+use crate::esc;
+use crate::string_ext::StrExt;
+use crate::utils;
+
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Text style set by the markup tags; colors not set are the widget colors
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Style {
+    pub bold: bool,
+    pub italics: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub inverse: bool,
+    pub fg: Option<ColorFg>,
+    pub bg: Option<ColorBg>,
+}
+
+/// Text fragment with its style
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Span<'a> {
+    pub text: &'a str,
+    pub style: Style,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tag {
+    Bold,
+    Italics,
+    Underline,
+    Strikethrough,
+    Inverse,
+    Fg,
+    Bg,
+}
+
+#[rustfmt::skip]
+const COLORS: [(&str, ColorFg, ColorBg); 17] = [
+    ("default",         ColorFg::Default,           ColorBg::Default),
+    ("black",           ColorFg::Black,             ColorBg::Black),
+    ("black_intense",   ColorFg::BlackIntense,      ColorBg::BlackIntense),
+    ("red",             ColorFg::Red,               ColorBg::Red),
+    ("red_intense",     ColorFg::RedIntense,        ColorBg::RedIntense),
+    ("green",           ColorFg::Green,             ColorBg::Green),
+    ("green_intense",   ColorFg::GreenIntense,      ColorBg::GreenIntense),
+    ("yellow",          ColorFg::Yellow,            ColorBg::Yellow),
+    ("yellow_intense",  ColorFg::YellowIntense,     ColorBg::YellowIntense),
+    ("blue",            ColorFg::Blue,              ColorBg::Blue),
+    ("blue_intense",    ColorFg::BlueIntense,       ColorBg::BlueIntense),
+    ("magenta",         ColorFg::Magenta,           ColorBg::Magenta),
+    ("magenta_intense", ColorFg::MagentaIntense,    ColorBg::MagentaIntense),
+    ("cyan",            ColorFg::Cyan,              ColorBg::Cyan),
+    ("cyan_intense",    ColorFg::CyanIntense,       ColorBg::CyanIntense),
+    ("white",           ColorFg::White,             ColorBg::White),
+    ("white_intense",   ColorFg::WhiteIntense,      ColorBg::WhiteIntense),
+];
+
+fn find_color(name: &str) -> Option<(ColorFg, ColorBg)> {
+    COLORS
+        .iter()
+        .find(|(cl_name, _, _)| *cl_name == name)
+        .map(|(_, fg, bg)| (*fg, *bg))
+}
+
+fn find_tag(name: &str) -> Option<Tag> {
+    match name {
+        "b" => Some(Tag::Bold),
+        "i" => Some(Tag::Italics),
+        "u" => Some(Tag::Underline),
+        "s" => Some(Tag::Strikethrough),
+        "r" => Some(Tag::Inverse),
+        "fg" => Some(Tag::Fg),
+        "bg" => Some(Tag::Bg),
+        _ => None,
+    }
+}
+
+/// Returns the opening tag and the style it sets
+fn open_tag(tag: &str, mut style: Style) -> Option<(Tag, Style)> {
+    let (name, arg) = match tag.split_once('=') {
+        Some((name, arg)) => (name, Some(arg)),
+        None => (tag, None),
+    };
+
+    let tag = find_tag(name)?;
+
+    match (tag, arg) {
+        (Tag::Bold, None) => style.bold = true,
+        (Tag::Italics, None) => style.italics = true,
+        (Tag::Underline, None) => style.underline = true,
+        (Tag::Strikethrough, None) => style.strikethrough = true,
+        (Tag::Inverse, None) => style.inverse = true,
+        (Tag::Fg, Some(cl)) => style.fg = Some(find_color(cl)?.0),
+        (Tag::Bg, Some(cl)) => style.bg = Some(find_color(cl)?.1),
+        _ => return None,
+    }
+
+    Some((tag, style))
+}
+
+fn push_span<'a>(spans: &mut Vec<Span<'a>>, text: &'a str, style: Style) {
+    if !text.is_empty() {
+        spans.push(Span { text, style });
+    }
+}
+
+/// Splits the markup text into the styled spans
+pub fn parse(src: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    // opened tags with the style from before them
+    let mut opened: Vec<(Tag, Style)> = Vec::new();
+    let mut style = Style::default();
+    let mut text_begin = 0;
+    let mut pos = 0;
+
+    while let Some(offs) = src[pos..].find('[') {
+        let tag_begin = pos + offs;
+
+        if src[tag_begin..].starts_with("[[") {
+            push_span(&mut spans, &src[text_begin..tag_begin + 1], style);
+            pos = tag_begin + 2;
+            text_begin = pos;
+            continue;
+        }
+
+        let tag_end = match src[tag_begin..].find(']') {
+            Some(offs) => tag_begin + offs,
+            None => break,
+        };
+        let tag = &src[tag_begin + 1..tag_end];
+
+        let new_style = if let Some(name) = tag.strip_prefix('/') {
+            let idx = if name.is_empty() {
+                opened.len().checked_sub(1)
+            }
+            else {
+                find_tag(name).and_then(|tag| opened.iter().rposition(|(t, _)| *t == tag))
+            };
+
+            idx.map(|idx| {
+                let prev_style = opened[idx].1;
+                opened.truncate(idx);
+                prev_style
+            })
+        }
+        else {
+            open_tag(tag, style).map(|(tag, new_style)| {
+                opened.push((tag, style));
+                new_style
+            })
+        };
+
+        if let Some(new_style) = new_style {
+            push_span(&mut spans, &src[text_begin..tag_begin], style);
+            style = new_style;
+            pos = tag_end + 1;
+            text_begin = pos;
+        }
+        else {
+            // not a tag, keep as the text
+            pos = tag_begin + 1;
+        }
+    }
+
+    push_span(&mut spans, &src[text_begin..], style);
+    spans
+}
+
+/// Appends the ESC sequences changing the style `from` to `to`
+fn push_style_change(out: &mut String, from: &Style, to: &Style, fg: ColorFg, bg: ColorBg) {
+    let attrs = [
+        (from.bold, to.bold, esc::BOLD, esc::NORMAL),
+        (from.italics, to.italics, esc::ITALICS_ON, esc::ITALICS_OFF),
+        (
+            from.underline,
+            to.underline,
+            esc::UNDERLINE_ON,
+            esc::UNDERLINE_OFF,
+        ),
+        (
+            from.strikethrough,
+            to.strikethrough,
+            esc::STRIKETHROUGH_ON,
+            esc::STRIKETHROUGH_OFF,
+        ),
+        (from.inverse, to.inverse, esc::INVERSE_ON, esc::INVERSE_OFF),
+    ];
+
+    for (was_on, is_on, seq_on, seq_off) in attrs {
+        if was_on != is_on {
+            out.push_str(if is_on { seq_on } else { seq_off });
+        }
+    }
+
+    if from.fg != to.fg {
+        out.push_str(to.fg.unwrap_or(fg).encode());
+    }
+
+    if from.bg != to.bg {
+        out.push_str(to.bg.unwrap_or(bg).encode());
+    }
+}
+
+/// Appends the spans text with the ESC sequences; ends with `fg`/`bg` colors and no attributes
+fn render(out: &mut String, spans: &[Span], fg: ColorFg, bg: ColorBg) {
+    let mut style = Style::default();
+
+    for span in spans {
+        push_style_change(out, &style, &span.style, fg, bg);
+        out.push_str(span.text);
+        style = span.style;
+    }
+
+    push_style_change(out, &style, &Style::default(), fg, bg);
+}
+
+/// Converts the markup text into text with ESC sequences;
+/// `fg` and `bg` are the widget colors, restored when the color tag is closed.
+/// Bold is turned off with `esc::NORMAL`, skipped by `Term::write_str()` in the faint mode
+pub fn to_esc(src: &str, fg: ColorFg, bg: ColorBg) -> String {
+    let mut out = String::with_capacity(src.len());
+    render(&mut out, &parse(src), fg, bg);
+    out
+}
+
+/// Returns the markup text without the tags
+pub fn strip(src: &str) -> String {
+    parse(src).iter().map(|span| span.text).collect()
+}
+
+/// Markup text width in the terminal
+pub fn displayed_width(src: &str) -> usize {
+    strip(src).as_str().displayed_width()
+}
+
+/// Splits the markup text into lines, like `utils::word_wrap()`, and converts them into
+/// text with ESC sequences; every line starts and ends with the widget colors and no attributes,
+/// thus can be drawn separately
+pub fn word_wrap(max_disp_w: usize, src: &str, fg: ColorFg, bg: ColorBg) -> utils::StringListRc {
+    let spans = parse(src);
+    let plain: String = spans.iter().map(|span| span.text).collect();
+    let wrapped = utils::word_wrap(max_disp_w, &plain);
+    let out = utils::StringListRc::default();
+
+    {
+        let mut out_lines = out.borrow_mut();
+        let mut line_begin = 0;
+
+        for line in wrapped.borrow().iter() {
+            let line_end = line_begin + line.len();
+            let mut line_spans = Vec::new();
+            let mut span_begin = 0;
+
+            // spans trimmed to the line
+            for span in spans.iter() {
+                let span_end = span_begin + span.text.len();
+                let from = span_begin.max(line_begin);
+                let to = span_end.min(line_end);
+
+                if from < to {
+                    line_spans.push(Span {
+                        text: &span.text[from - span_begin..to - span_begin],
+                        style: span.style,
+                    });
+                }
+
+                span_begin = span_end;
+            }
+
+            let mut out_line = String::with_capacity(line.len());
+            render(&mut out_line, &line_spans, fg, bg);
+            out_lines.push(out_line);
+
+            // new line character is not a part of the line
+            line_begin = line_end;
+            if plain[line_end..].starts_with('\n') {
+                line_begin += 1;
+            }
+        }
+    }
+
+    out
+}
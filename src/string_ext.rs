@@ -22,7 +22,7 @@ pub trait StringExt {
     /// Append and return mutable reference to itself
     fn append(&mut self, s: &str) -> &mut Self;
     /// Returns stream operator wrapper
-    fn stream(&mut self) -> StrStreamOp;
+    fn stream(&mut self) -> StrStreamOp<'_>;
     /// Erase characters (not byte, like `remove()`) range; Never panics
     fn erase_char_range(&mut self, ch_idx: usize, len: usize);
     /// Remove trailing text, starting at given char (not byte) index
@@ -94,7 +94,7 @@ impl StringExt for String {
         self
     }
 
-    fn stream(&mut self) -> StrStreamOp {
+    fn stream(&mut self) -> StrStreamOp<'_> {
         StrStreamOp::new(self)
     }
 
@@ -151,6 +151,10 @@ pub trait StrExt {
     fn esc_seq_len(&self) -> usize;
     /// Calculate UTF-8 terminal text width, ignoring ESC sequences inside it
     fn displayed_width(&self) -> usize;
+    /// Returns the text without ESC sequences, like colors and font attributes
+    fn strip_esc(&self) -> String;
+    /// Returns the text without the first `cols` displayed columns, keeping the ESC sequences
+    fn skip_displayed_cols(&self, cols: usize) -> String;
 }
 
 impl StrExt for str {
@@ -223,6 +227,53 @@ impl StrExt for str {
             0
         }
     }
+
+    fn strip_esc(&self) -> String {
+        let mut out = String::with_capacity(self.len());
+        let mut it = self.char_indices();
+
+        while let Some((byte_idx, c)) = it.next() {
+            let esc_len = self[byte_idx..].esc_seq_len();
+
+            if esc_len > 0 {
+                // sequence is ASCII only
+                it.nth(esc_len - 2);
+            }
+            else {
+                out.push(c);
+            }
+        }
+
+        out
+    }
+
+    fn skip_displayed_cols(&self, cols: usize) -> String {
+        let mut out = String::with_capacity(self.len());
+        let mut skipped = 0usize;
+        let mut it = self.char_indices();
+
+        while let Some((byte_idx, c)) = it.next() {
+            let esc_len = self[byte_idx..].esc_seq_len();
+
+            if esc_len > 0 {
+                out.push_str(&self[byte_idx..byte_idx + esc_len]);
+                it.nth(esc_len - 2);
+            }
+            else if skipped < cols {
+                skipped += UnicodeWidthChar::width(c).unwrap_or(0);
+
+                if skipped > cols {
+                    // half of the wide character remains visible
+                    out.push(' ');
+                }
+            }
+            else {
+                out.push(c);
+            }
+        }
+
+        out
+    }
 }
 
 /// C++ like stream operator for `String` type.
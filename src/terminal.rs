@@ -29,9 +29,12 @@ pub static TERM: AtomicLazy<TryLock<Term>> = AtomicLazy::new(|| TryLock::new(Ter
 pub struct Term {
     pub pal: PalBox,
     pub trace_row: u16,
+    /// Screen size, used to place the notifications; shall be set by the application
+    pub screen_size: Size,
     current_cl_fg: ColorFg,
     current_cl_bg: ColorBg,
     attr_faint: i8,
+    osc52_clipboard: bool,
     _log_raw_font_memento: FontMementoManual,
     invalidated: Vec<WId>,
     pub(crate) stack_cl_fg: Vec<ColorFg>,
@@ -46,9 +49,11 @@ impl Default for Term {
         Term {
             pal: Box::<crate::pal::PalStub>::default(),
             trace_row: 0,
+            screen_size: Size::new(80, 24),
             current_cl_fg: ColorFg::Default,
             current_cl_bg: ColorBg::Default,
             attr_faint: 0,
+            osc52_clipboard: false,
             _log_raw_font_memento: FontMementoManual::new(),
             invalidated: Vec::with_capacity(4),
             stack_cl_fg: vec![],
@@ -344,10 +349,8 @@ impl Term {
         self.stack_attr.push(attr);
 
         match attr {
-            FontAttrib::Bold => {
-                if self.attr_faint == 0 {
-                    self.write_str(esc::BOLD);
-                }
+            FontAttrib::Bold if self.attr_faint == 0 => {
+                self.write_str(esc::BOLD);
             }
             FontAttrib::Faint => {
                 self.attr_faint += 1;
@@ -381,19 +384,15 @@ impl Term {
             let attr = self.stack_attr.pop().unwrap();
 
             match attr {
-                FontAttrib::Bold => {
+                FontAttrib::Bold if self.attr_faint == 0 => {
+                    self.write_str(esc::NORMAL);
+                }
+                FontAttrib::Faint if self.attr_faint > 0 => {
+                    self.attr_faint -= 1;
                     if self.attr_faint == 0 {
                         self.write_str(esc::NORMAL);
                     }
                 }
-                FontAttrib::Faint => {
-                    if self.attr_faint > 0 {
-                        self.attr_faint -= 1;
-                        if self.attr_faint == 0 {
-                            self.write_str(esc::NORMAL);
-                        }
-                    }
-                }
                 FontAttrib::Italics => {
                     self.write_str(esc::ITALICS_OFF);
                 }
@@ -451,6 +450,10 @@ impl Term {
             MouseMode::Off => {
                 self.write_str(esc::MOUSE_REPORTING_M1_OFF);
                 self.write_str(esc::MOUSE_REPORTING_M2_OFF);
+                self.write_str(esc::MOUSE_REPORTING_M3_OFF);
+                self.write_str(esc::MOUSE_REPORTING_M4_OFF);
+                self.write_str(esc::MOUSE_REPORTING_SGR_OFF);
+                return;
             }
             MouseMode::M1 => {
                 self.write_str(esc::MOUSE_REPORTING_M1_ON);
@@ -458,6 +461,60 @@ impl Term {
             MouseMode::M2 => {
                 self.write_str(esc::MOUSE_REPORTING_M2_ON);
             }
+            MouseMode::M3 => {
+                self.write_str(esc::MOUSE_REPORTING_M3_ON);
+            }
+            MouseMode::M4 => {
+                self.write_str(esc::MOUSE_REPORTING_M4_ON);
+            }
+        }
+
+        // the decoder understands both the legacy and the SGR reports
+        self.write_str(esc::MOUSE_REPORTING_SGR_ON);
+    }
+
+    /// Bracketed paste; when enabled, pasted text is decoded as single `InputEvent::Paste`
+    pub fn bracketed_paste(&mut self, enable: bool) {
+        if enable {
+            self.write_str(esc::BRACKETED_PASTE_ON);
+        }
+        else {
+            self.write_str(esc::BRACKETED_PASTE_OFF);
+        }
+    }
+
+    /// Focus reporting; when enabled, terminal focus change is decoded as `InputEvent::Focus`
+    pub fn focus_reporting(&mut self, enable: bool) {
+        if enable {
+            self.write_str(esc::FOCUS_REPORTING_ON);
+        }
+        else {
+            self.write_str(esc::FOCUS_REPORTING_OFF);
+        }
+    }
+
+    /// Kitty (`CSI u`) keyboard protocol; when enabled, terminals supporting it
+    /// report unambiguous key codes with all modifiers, like Ctrl+Enter or Ctrl+1
+    pub fn keyboard_protocol(&mut self, enable: bool) {
+        if enable {
+            self.write_str(esc::KEYBOARD_ENHANCE_ON);
+        }
+        else {
+            self.write_str(esc::KEYBOARD_ENHANCE_OFF);
+        }
+    }
+
+    /// OSC 52 clipboard; when enabled, text copied by the widgets is also sent
+    /// to the terminal host system clipboard
+    pub fn osc52_clipboard(&mut self, enable: bool) {
+        self.osc52_clipboard = enable;
+    }
+
+    /// Sends the text to the terminal host system clipboard, if the OSC 52 clipboard is enabled
+    pub fn clipboard_copy(&mut self, text: &str) {
+        if self.osc52_clipboard {
+            self.write_str(&esc::osc52_copy(text));
+            self.flush_buff();
         }
     }
 
@@ -469,8 +526,8 @@ impl Term {
     }
 
     /// Draw widgets marked as invalidated; flushes the buffer.
-    /// Clears the invalidated widgets list
-    pub fn draw_invalidated(&mut self, ws: &mut dyn WindowState) {
+    /// Clears the invalidated widgets list. Returns true if anything was drawn
+    pub fn draw_invalidated(&mut self, ws: &mut dyn WindowState) -> bool {
         self.invalidated.clear();
         // this functions performs internal swap of the passed vector with its own vector
         ws.get_invalidated(&mut self.invalidated);
@@ -481,7 +538,10 @@ impl Term {
             wgt::draw_widgets(self, ws, &invalidated[..]);
             // restore the vector
             self.invalidated = invalidated;
+            return true;
         }
+
+        false
     }
 
     /// Draw entire window; flushes the buffer
@@ -489,4 +549,14 @@ impl Term {
         wgt::draw_widgets(self, ws, &[WIDGET_ID_ALL]);
         ws.clear_invalidated();
     }
+
+    /// Clear the screen area, eg. uncovered by moved window; flushes the buffer
+    pub fn clear_area(&mut self, coord: Coord, size: Size) {
+        wgt::clear_area(self, coord, size);
+    }
+
+    /// Draw notifications, stacked down from the `anchor` (top-right corner); flushes the buffer
+    pub fn draw_toasts(&mut self, anchor: Coord, toasts: &[crate::wnd_manager::Toast]) {
+        wgt::draw_toasts(self, anchor, toasts);
+    }
 }
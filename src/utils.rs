@@ -4,6 +4,7 @@ use crate::input::*;
 use crate::string_ext::*;
 
 use core::fmt::Write;
+use unicode_width::UnicodeWidthChar;
 
 extern crate alloc;
 use alloc::string::String;
@@ -42,61 +43,71 @@ pub type StringListRc = alloc::sync::Arc<core::cell::RefCell<Vec<String>>>;
 
 /// Splits given string into lines so that each line is not wider than `max_disp_w`.
 ///
-/// Display width is calculated using Unicode data to determine if character is single or double width.
+/// Display width is calculated using Unicode data to determine if character is single or double width;
+/// words wider than `max_disp_w` are broken between the characters.
 /// Returns Vector of String's (not slices)
 pub fn word_wrap(max_disp_w: usize, src: &str) -> StringListRc {
-    let it = src
-        .split_inclusive(char::is_whitespace)
-        .scan((0usize, 0usize, 0usize), |state, word| {
+    let out = StringListRc::default();
+
+    {
+        let mut lines = out.borrow_mut();
+        let mut line_begin = 0usize;
+        let mut line_end = 0usize;
+        let mut line_w = 0usize;
+
+        for word in src.split_inclusive(char::is_whitespace) {
             let word_w = word.displayed_width();
 
-            if state.0 + word_w > max_disp_w {
-                // ready to output the line, as the current word would made it too wide
-                let ends_with_nl = state.2 > 0 && src.as_bytes()[state.2-1] == b'\n';
-                let outslice = &src[state.1..state.2 - ends_with_nl as usize];
-                state.0 = word_w; // line_w = word_w
-                state.1 = state.2; // begin = end
-                state.2 += word.len(); // end += word.len
-                Some(outslice.to_string())
-            }
-            else if word.as_bytes().ends_with(&[b'\n']) {
-                // perhaps shorter than possible, but ends with a new line
-                state.2 += word.len(); // end += word.len
-                let outslice = &src[state.1..state.2 - 1];
-                state.0 = 0;
-                state.1 = state.2; // begin = end
-                Some(outslice.to_string())
+            if line_w + word_w > max_disp_w && line_end > line_begin {
+                // output the line, as the current word would made it too wide
+                lines.push(src[line_begin..line_end].to_string());
+                line_begin = line_end;
+                line_w = 0;
             }
-            else {
-                if state.2 > state.1 && src.as_bytes()[state.2-1] == b'\n' {
-                    // existing sequence ends with the new line
-                    let outslice = &src[state.1..state.2 - 1];
-                    state.0 = word_w; // line_w = word_w
-                    state.1 = state.2; // begin = end
-                    state.2 += word.len(); // end += word.len
-                    // FIXME: if `word` is the last item in a string, it will be lost
-                    Some(outslice.to_string())
-                }
-                else {
-                    // single word not ending with \n
-                    state.0 += word_w; // line_w += word_w
-                    state.2 += word.len(); // end += word.len
 
-                    if state.2 == src.len() {
-                        // `word` is the last item in a string -> output it
-                        Some(word.to_string())
+            if word_w > max_disp_w {
+                // word wider than the line - break it between the characters
+                let mut it = word.char_indices();
+
+                while let Some((byte_idx, c)) = it.next() {
+                    let esc_len = word[byte_idx..].esc_seq_len();
+                    let c_w = if esc_len > 0 {
+                        // sequence is ASCII only
+                        it.nth(esc_len - 2);
+                        0
                     }
                     else {
-                        // keep iterating -> return Some
-                        Some("".to_string()) // empty str -> will be filtered out
+                        UnicodeWidthChar::width(c).unwrap_or(0)
+                    };
+
+                    if line_w + c_w > max_disp_w && line_end > line_begin && !c.is_whitespace() {
+                        lines.push(src[line_begin..line_end].to_string());
+                        line_begin = line_end;
+                        line_w = 0;
                     }
+
+                    line_end += tetrary!(esc_len > 0, esc_len, c.len_utf8());
+                    line_w += c_w;
                 }
             }
-        })
-        .filter(|line| !line.is_empty());
+            else {
+                line_end += word.len();
+                line_w += word_w;
+            }
+
+            if word.ends_with('\n') {
+                // perhaps shorter than possible, but ends with a new line
+                lines.push(src[line_begin..line_end - 1].to_string());
+                line_begin = line_end;
+                line_w = 0;
+            }
+        }
+
+        if line_end > line_begin {
+            lines.push(src[line_begin..line_end].to_string());
+        }
+    }
 
-    let out = StringListRc::default();
-    out.borrow_mut().extend(it);
     out
 }
 
@@ -209,3 +220,30 @@ const UTF8_CHAR_WIDTH: &[u8; 256] = &[
 pub const fn utf8_char_width(b: u8) -> usize {
     UTF8_CHAR_WIDTH[b as usize] as usize
 }
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes the data to Base64 (standard alphabet, with `=` padding)
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - i * 6)) as usize & 0x3F] as char);
+            }
+            else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,282 @@
+//! # RTWins TextEdit validators
+//!
+//! Declarative checks of the TextEdit content, provided by `WindowState::get_text_edit_validator()`;
+//! invalid text is drawn on red background and cannot be committed with Enter;
+//! the error message is reported by `WindowState::on_text_edit_validate()`, to be displayed by the application
+
+use crate::tetrary;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// TextEdit content validator
+#[derive(Clone, Copy, Debug)]
+pub enum Validator {
+    /// Integer within the range
+    Int { min: i64, max: i64 },
+    /// Floating point number within the range
+    Float { min: f64, max: f64 },
+    /// Entire text matching the pattern: literal characters, `.`, sets like `[a-z_]` or `[^0-9]`,
+    /// `\d`, `\w`, `\s` classes, `\` escape, and `?`, `*`, `+` quantifiers
+    Pattern(&'static str),
+    /// Text not longer than given number of characters
+    MaxLen(u16),
+    /// Text containing only the characters from the set
+    CharSet(&'static str),
+    /// IPv4 address, like `192.168.0.1`
+    Ipv4,
+    /// MAC address, like `01:23:45:67:89:AB` or `01-23-45-67-89-AB`
+    Mac,
+    /// Hexadecimal number, with optional `0x` prefix
+    Hex,
+    /// All of the validators must pass
+    All(&'static [Validator]),
+}
+
+impl Validator {
+    /// Checks the text; returns the error message if the text is invalid
+    pub fn validate(&self, txt: &str) -> Result<(), &'static str> {
+        match *self {
+            Validator::Int { min, max } => match txt.parse::<i64>() {
+                Ok(val) if val >= min && val <= max => Ok(()),
+                Ok(_) => Err("Value out of range"),
+                Err(_) => Err("Not an integer number"),
+            },
+            Validator::Float { min, max } => match txt.parse::<f64>() {
+                Ok(val) if val.is_finite() && val >= min && val <= max => Ok(()),
+                Ok(val) if val.is_finite() => Err("Value out of range"),
+                _ => Err("Not a number"),
+            },
+            Validator::Pattern(pattern) => {
+                if pattern_match(pattern, txt) {
+                    Ok(())
+                }
+                else {
+                    Err("Text does not match the pattern")
+                }
+            }
+            Validator::MaxLen(max_len) => {
+                if txt.chars().count() <= max_len as usize {
+                    Ok(())
+                }
+                else {
+                    Err("Text too long")
+                }
+            }
+            Validator::CharSet(set) => {
+                if txt.chars().all(|c| set.contains(c)) {
+                    Ok(())
+                }
+                else {
+                    Err("Invalid character")
+                }
+            }
+            Validator::Ipv4 => {
+                let parts: Vec<&str> = txt.split('.').collect();
+                let valid = parts.len() == 4
+                    && parts.iter().all(|p| {
+                        !p.is_empty()
+                            && p.len() <= 3
+                            && p.bytes().all(|b| b.is_ascii_digit())
+                            && p.parse::<u8>().is_ok()
+                    });
+
+                if valid {
+                    Ok(())
+                }
+                else {
+                    Err("Invalid IPv4 address")
+                }
+            }
+            Validator::Mac => {
+                let sep = tetrary!(txt.contains('-'), '-', ':');
+                let parts: Vec<&str> = txt.split(sep).collect();
+                let valid = parts.len() == 6
+                    && parts
+                        .iter()
+                        .all(|p| p.len() == 2 && p.bytes().all(|b| b.is_ascii_hexdigit()));
+
+                if valid {
+                    Ok(())
+                }
+                else {
+                    Err("Invalid MAC address")
+                }
+            }
+            Validator::Hex => {
+                let digits = txt
+                    .strip_prefix("0x")
+                    .or_else(|| txt.strip_prefix("0X"))
+                    .unwrap_or(txt);
+
+                if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    Ok(())
+                }
+                else {
+                    Err("Invalid hexadecimal number")
+                }
+            }
+            Validator::All(validators) => validators.iter().try_for_each(|v| v.validate(txt)),
+        }
+    }
+
+    /// Checks if the text may be entered, eg. only digits into `Int`;
+    /// used to reject the keys while editing, before the text becomes complete
+    pub fn allows_input(&self, txt: &str) -> bool {
+        let allowed = |set: &str| txt.chars().all(|c| set.contains(c));
+
+        match *self {
+            Validator::Int { .. } => allowed("+-0123456789"),
+            Validator::Float { .. } => allowed("+-.eE0123456789"),
+            Validator::Pattern(_) => true,
+            Validator::MaxLen(max_len) => txt.chars().count() <= max_len as usize,
+            Validator::CharSet(set) => allowed(set),
+            Validator::Ipv4 => allowed(".0123456789"),
+            Validator::Mac => allowed(":-0123456789abcdefABCDEF"),
+            Validator::Hex => allowed("xX0123456789abcdefABCDEF"),
+            Validator::All(validators) => validators.iter().all(|v| v.allows_input(txt)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[derive(Clone, Copy, PartialEq)]
+enum Quantifier {
+    One,
+    ZeroOrOne,
+    ZeroOrMore,
+    OneOrMore,
+}
+
+/// Single pattern element: set of characters ranges (or any character if None)
+struct Token {
+    ranges: Option<Vec<(char, char)>>,
+    negated: bool,
+    quant: Quantifier,
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self.ranges {
+            Some(ref ranges) => ranges.iter().any(|r| c >= r.0 && c <= r.1) != self.negated,
+            None => true,
+        }
+    }
+}
+
+/// Ranges of the `\d`, `\w`, `\s` classes, or the escaped character
+fn escaped_ranges(c: char) -> Vec<(char, char)> {
+    match c {
+        'd' => alloc::vec![('0', '9')],
+        'w' => alloc::vec![('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')],
+        's' => alloc::vec![(' ', ' '), ('\t', '\t')],
+        c => alloc::vec![(c, c)],
+    }
+}
+
+/// Parses the pattern; returns None if the pattern is malformed
+fn parse_pattern(pattern: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut it = pattern.chars().peekable();
+
+    while let Some(c) = it.next() {
+        let mut negated = false;
+        let ranges = match c {
+            '.' => None,
+            '\\' => Some(escaped_ranges(it.next()?)),
+            '[' => {
+                let mut ranges = Vec::new();
+                negated = it.next_if_eq(&'^').is_some();
+
+                loop {
+                    let first = match it.next()? {
+                        ']' if !ranges.is_empty() => break,
+                        '\\' => {
+                            ranges.extend(escaped_ranges(it.next()?));
+                            continue;
+                        }
+                        c => c,
+                    };
+
+                    // range like `a-z`, unless `-` is the last one in the set
+                    if it.next_if_eq(&'-').is_some() {
+                        if it.peek() == Some(&']') {
+                            ranges.push((first, first));
+                            ranges.push(('-', '-'));
+                        }
+                        else {
+                            ranges.push((first, it.next()?));
+                        }
+                    }
+                    else {
+                        ranges.push((first, first));
+                    }
+                }
+
+                Some(ranges)
+            }
+            '?' | '*' | '+' => return None,
+            c => Some(alloc::vec![(c, c)]),
+        };
+
+        let quant = match it.peek() {
+            Some('?') => Quantifier::ZeroOrOne,
+            Some('*') => Quantifier::ZeroOrMore,
+            Some('+') => Quantifier::OneOrMore,
+            _ => Quantifier::One,
+        };
+
+        if quant != Quantifier::One {
+            it.next();
+        }
+
+        tokens.push(Token {
+            ranges,
+            negated,
+            quant,
+        });
+    }
+
+    Some(tokens)
+}
+
+/// Backtracking matcher
+fn match_tokens(tokens: &[Token], txt: &[char]) -> bool {
+    let tok = match tokens.first() {
+        Some(tok) => tok,
+        None => return txt.is_empty(),
+    };
+
+    let (min, max) = match tok.quant {
+        Quantifier::One => (1, 1),
+        Quantifier::ZeroOrOne => (0, 1),
+        Quantifier::ZeroOrMore => (0, usize::MAX),
+        Quantifier::OneOrMore => (1, usize::MAX),
+    };
+
+    // count of the matching characters, then try from the longest
+    let cnt = txt
+        .iter()
+        .take(max)
+        .take_while(|&&c| tok.matches(c))
+        .count();
+
+    (min..=cnt)
+        .rev()
+        .any(|n| match_tokens(&tokens[1..], &txt[n..]))
+}
+
+/// Checks if the entire `txt` matches the `pattern` (see `Validator::Pattern`);
+/// malformed pattern matches nothing
+pub fn pattern_match(pattern: &str, txt: &str) -> bool {
+    match parse_pattern(pattern) {
+        Some(tokens) => {
+            let txt: Vec<char> = txt.chars().collect();
+            match_tokens(&tokens, &txt)
+        }
+        None => false,
+    }
+}
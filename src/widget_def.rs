@@ -6,6 +6,7 @@
 
 use crate::common::*;
 use crate::input::*;
+use crate::validator::Validator;
 use crate::wgt;
 
 use core::fmt;
@@ -25,6 +26,9 @@ pub const WIDGET_ID_NONE: WId = WId::MIN;
 /// Used as a special function parameter
 pub const WIDGET_ID_ALL: WId = WId::MAX;
 
+/// Result of the closed modal window, of the type defined by the modal, eg. `dialog::DialogResult`
+pub type ModalResult = alloc::boxed::Box<dyn core::any::Any>;
+
 // This macro uses TT munchers technique:
 // https://danielkeep.github.io/tlborm/book/pat-incremental-tt-munchers.html
 // https://blog.logrocket.com/macros-in-rust-a-tutorial-with-examples/
@@ -89,6 +93,8 @@ pub mod prop {
         pub title: &'static str,
         pub fg_color: ColorFg,
         pub bg_color: ColorBg,
+        /// text with the `markup` tags, like `[b]bold[/b]`
+        pub markup: bool,
     }
 
     #[derive(Copy, Clone)]
@@ -96,6 +102,8 @@ pub mod prop {
         pub fg_color: ColorFg,
         pub bg_color: ColorBg,
         pub psw_mask: bool,
+        /// formatted input, like `"99:99:99"`; see `input_mask` module
+        pub input_mask: &'static str,
     }
 
     #[derive(Copy, Clone)]
@@ -167,6 +175,10 @@ pub mod prop {
     pub struct TextBox {
         pub fg_color: ColorFg,
         pub bg_color: ColorBg,
+        /// wrap the lines to the widget width instead of scrolling horizontally
+        pub word_wrap: bool,
+        /// lines with the `markup` tags, like `[b]bold[/b]`
+        pub markup: bool,
     }
 
     #[derive(Copy, Clone)]
@@ -327,12 +339,12 @@ impl Widget {
     }
 
     /// Returns iterator going through widget children
-    pub fn iter_children(&self) -> wgt::ChildrenIter {
+    pub fn iter_children(&self) -> wgt::ChildrenIter<'_> {
         wgt::ChildrenIter::new(self)
     }
 
     /// Returns iterator going up the parents hierarchy, but starting at the widget itself
-    pub fn iter_parents(&self) -> wgt::ParentsIter {
+    pub fn iter_parents(&self) -> wgt::ParentsIter<'_> {
         wgt::ParentsIter::new(self)
     }
 }
@@ -429,8 +441,12 @@ pub mod rstate {
     #[derive(Default)]
     pub struct TxtbxState {
         pub top_line: i16,
+        pub left_col: i16,
         // used only when returning current state
         pub lines: StringListRc,
+        /// Shall be changed when the `lines` are modified in place, keeping their count;
+        /// the wrapped lines are reused until then
+        pub generation: u32,
     }
 
     /// PageControl
@@ -491,6 +507,11 @@ pub trait WindowState {
     ) -> bool {
         false
     }
+    /// Called after each change of the text with the validator, and on Enter refused
+    /// because of the invalid text or not filled input mask;
+    /// `error` is None if the text is valid; the TextEdit is only drawn on red background,
+    /// the error message shall be displayed by the application, eg. in a status label
+    fn on_text_edit_validate(&mut self, wgt: &Widget, error: Option<&'static str>) {}
     fn on_checkbox_toggle(&mut self, wgt: &Widget) {}
     fn on_page_control_page_change(&mut self, wgt: &Widget, new_page_idx: i16) {}
     fn on_list_box_select(&mut self, wgt: &Widget, new_sel_idx: i16) {}
@@ -500,6 +521,7 @@ pub trait WindowState {
     fn on_combo_box_drop(&mut self, wgt: &Widget, drop_state: bool) {}
     fn on_radio_select(&mut self, wgt: &Widget) {}
     fn on_text_box_scroll(&mut self, wgt: &Widget, new_top_line: i16) {}
+    fn on_text_box_scroll_h(&mut self, wgt: &Widget, new_left_col: i16) {}
     fn on_custom_widget_draw(
         &mut self,
         wgt: &Widget,
@@ -512,6 +534,7 @@ pub trait WindowState {
     fn on_window_unhandled_input_evt(&mut self, wgt: &Widget, ii: &InputInfo) -> bool {
         false
     }
+    fn on_modal_result(&mut self, wnd_id: WId, result: &dyn core::any::Any) {}
 
     /// common state queries
     fn is_enabled(&self, wgt: &Widget) -> bool {
@@ -526,6 +549,24 @@ pub trait WindowState {
     fn is_desktop(&self) -> bool {
         false
     }
+    fn is_movable(&self) -> bool {
+        false
+    }
+    fn is_resizable(&self) -> bool {
+        false
+    }
+    /// Tab focuses widgets with the index first, in ascending order, then the others
+    fn get_tab_index(&self, wgt: &Widget) -> Option<u16> {
+        None
+    }
+    /// Widget skipped by Tab, but still focusable by mouse or arrow keys, if false
+    fn is_tab_stop(&self, wgt: &Widget) -> bool {
+        true
+    }
+    /// Tab cycles within the container (Panel, Layer), if true; Page is always a focus scope
+    fn is_focus_scope(&self, wgt: &Widget) -> bool {
+        false
+    }
     fn get_focused_id(&mut self) -> WId {
         WIDGET_ID_NONE
     }
@@ -536,6 +577,9 @@ pub trait WindowState {
     fn get_rstate(&mut self) -> Option<&mut wgt::RuntimeStates> {
         None
     }
+    fn take_modal_result(&mut self) -> Option<ModalResult> {
+        None
+    }
 
     /// widget-specific queries; all mutable params are outputs
     fn get_window_coord(&mut self) -> Coord {
@@ -550,6 +594,9 @@ pub trait WindowState {
     }
     fn get_label_text(&mut self, wgt: &Widget, out: &mut String) {}
     fn get_text_edit_text(&mut self, wgt: &Widget, out: &mut String, edit_mode: bool) {}
+    fn get_text_edit_validator(&mut self, wgt: &Widget) -> Option<Validator> {
+        None
+    }
     fn get_led_lit(&mut self, wgt: &Widget) -> bool {
         false
     }
@@ -570,6 +617,8 @@ pub trait WindowState {
 
     /// requests
     fn set_focused_id(&mut self, wid: WId) {}
+    fn set_window_coord(&mut self, coord: Coord) {}
+    fn set_window_size(&mut self, size: Size) {}
     #[inline]
     fn invalidate(&mut self, wid: WId) {
         self.invalidate_many(&[wid]);
@@ -6,6 +6,8 @@
 use crate::colors::*;
 use crate::common::*;
 use crate::esc;
+use crate::input_mask::InputMask;
+use crate::markup;
 use crate::string_ext::*;
 use crate::wgt;
 use crate::wgt::*;
@@ -14,9 +16,11 @@ use crate::*; // tr_info
 use core::cell::RefCell;
 
 extern crate alloc;
+use alloc::borrow::Cow;
 use alloc::format;
 use alloc::string::String;
 use alloc::string::ToString;
+use alloc::vec::Vec;
 
 // ---------------------------------------------------------------------------------------------- //
 
@@ -106,6 +110,94 @@ pub fn draw_widgets(term: &mut Term, ws: &mut dyn WindowState, wids: &[WId]) {
     term.flush_buff();
 }
 
+/// Fill the screen area with spaces, using the terminal default background
+pub fn clear_area(term: &mut Term, coord: Coord, size: Size) {
+    let mut fm = FontMementoManual::from_term(term);
+    term.push_cl_bg(ColorBg::Default);
+
+    for row in 0..size.height as u16 {
+        term.move_to(coord.col as u16, coord.row as u16 + row);
+        term.write_char_n(' ', size.width as i16);
+    }
+
+    term.pop_cl_bg();
+    fm.restore(term);
+    term.flush_buff();
+}
+
+/// Maximum displayed width of the toast text
+const TOAST_TEXT_MAX_WIDTH: usize = 40;
+
+/// Returns the toast text in a single line, limited to `TOAST_TEXT_MAX_WIDTH`
+fn get_toast_text(toast: &crate::wnd_manager::Toast, out: &mut String) {
+    out.clear();
+    out.extend(toast.text.chars().map(|c| tetrary!(c == '\n', ' ', c)));
+    let text_w = out.as_str().displayed_width().min(TOAST_TEXT_MAX_WIDTH);
+    out.set_displayed_width(text_w as i16);
+}
+
+/// Returns the screen areas of notifications stacked down from the `anchor` top-right corner,
+/// the newest on top; the areas are in the `toasts` order
+pub fn get_toasts_layout(anchor: Coord, toasts: &[crate::wnd_manager::Toast]) -> Vec<Rect> {
+    let mut strbuff = String::with_capacity(100);
+    let mut row = anchor.row;
+    let mut layout = Vec::with_capacity(toasts.len());
+
+    for toast in toasts.iter().rev() {
+        get_toast_text(toast, &mut strbuff);
+        let size = Size::new(strbuff.as_str().displayed_width() as u8 + 4, 3);
+        let col = (anchor.col as u16 + 1)
+            .saturating_sub(size.width as u16)
+            .max(1);
+        layout.push(Rect {
+            coord: Coord::new(col as u8, row),
+            size,
+        });
+        row = row.saturating_add(size.height);
+    }
+
+    layout.reverse();
+    layout
+}
+
+/// Draw notifications stacked down from the `anchor` top-right corner, the newest on top
+pub fn draw_toasts(term: &mut Term, anchor: Coord, toasts: &[crate::wnd_manager::Toast]) {
+    use crate::wnd_manager::Severity;
+
+    let mut fm = FontMementoManual::from_term(term);
+    let mut strbuff = String::with_capacity(100);
+    term.cursor_save_pos();
+
+    for (toast, rect) in toasts.iter().zip(get_toasts_layout(anchor, toasts)).rev() {
+        let (cl_bg, cl_fg) = match toast.severity {
+            Severity::Info => (ColorBg::Blue, ColorFg::WhiteIntense),
+            Severity::Success => (ColorBg::Green, ColorFg::WhiteIntense),
+            Severity::Warning => (ColorBg::Yellow, ColorFg::Black),
+            Severity::Error => (ColorBg::Red, ColorFg::WhiteIntense),
+        };
+
+        get_toast_text(toast, &mut strbuff);
+        draw_area(
+            term,
+            rect.coord,
+            rect.size,
+            cl_bg,
+            cl_fg,
+            FrameStyle::Single,
+            true,
+            false,
+        );
+        term.move_to(rect.coord.col as u16 + 2, rect.coord.row as u16 + 1);
+        term.write_str(strbuff.as_str());
+        term.pop_cl_fg();
+        term.pop_cl_bg();
+    }
+
+    fm.restore(term);
+    term.cursor_restore_pos();
+    term.flush_buff();
+}
+
 // ---------------------------------------------------------------------------------------------- //
 
 fn draw_widget_internal(dctx: &mut DrawCtx) {
@@ -168,10 +260,11 @@ fn draw_widget_internal(dctx: &mut DrawCtx) {
 #[inline(never)]
 fn draw_window(dctx: &mut DrawCtx, prp: &prop::Window) {
     let wnd_coord = dctx.wnd_state.get_window_coord();
+    let wnd_size = wgt::get_window_size(dctx.wnd_state);
     draw_area(
         &mut dctx.term_cell.borrow_mut(),
         wnd_coord,
-        dctx.wgt.size,
+        wnd_size,
         prp.bg_color,
         prp.fg_color,
         FrameStyle::Double,
@@ -193,7 +286,7 @@ fn draw_window(dctx: &mut DrawCtx, prp: &prop::Window) {
         let title_width = wnd_title.as_str().displayed_width() as u16 + 4;
         let mut term = dctx.term_cell.borrow_mut();
         term.move_to(
-            wnd_coord.col as u16 + (dctx.wgt.size.width as u16 - title_width) / 2,
+            wnd_coord.col as u16 + (wnd_size.width as u16).saturating_sub(title_width) / 2,
             wnd_coord.row as u16,
         );
         term.push_attr(FontAttrib::Bold);
@@ -220,7 +313,7 @@ fn draw_window(dctx: &mut DrawCtx, prp: &prop::Window) {
         let mut term = dctx.term_cell.borrow_mut();
         term.pop_cl_bg();
         term.pop_cl_fg();
-        term.move_to(0, wnd_coord.row as u16 + dctx.wgt.size.height as u16);
+        term.move_to(0, wnd_coord.row as u16 + wnd_size.height as u16);
     }
 }
 
@@ -290,6 +383,17 @@ fn draw_label(dctx: &mut DrawCtx, prp: &prop::Label) {
         dctx.wnd_state.get_label_text(dctx.wgt, &mut title);
     }
 
+    if prp.markup {
+        title = markup::word_wrap(
+            usize::MAX,
+            &title,
+            get_widget_fg_color(dctx.wgt),
+            get_widget_bg_color(dctx.wgt),
+        )
+        .take()
+        .join("\n");
+    }
+
     let _fm = FontMemento::new(&dctx.term_cell);
     let mut term = dctx.term_cell.borrow_mut();
 
@@ -316,6 +420,11 @@ fn draw_label(dctx: &mut DrawCtx, prp: &prop::Label) {
 
         if line_width > 0 {
             dctx.strbuff.set_displayed_width(line_width as i16);
+
+            if prp.markup && s.displayed_width() > line_width as usize {
+                // truncated; restore the attributes the line ends with
+                push_esc_sequences(&mut dctx.strbuff, s);
+            }
         }
 
         term.write_str(dctx.strbuff.as_str());
@@ -330,6 +439,8 @@ fn draw_label(dctx: &mut DrawCtx, prp: &prop::Label) {
 #[inline(never)]
 fn draw_text_edit(dctx: &mut DrawCtx, prp: &prop::TextEdit) {
     let mut display_pos = 0;
+    let mut invalid = false;
+    let mut selection = None;
     let max_w = dctx.wgt.size.width as i16 - 3;
 
     {
@@ -339,18 +450,18 @@ fn draw_text_edit(dctx: &mut DrawCtx, prp: &prop::TextEdit) {
         if dctx.wgt.id == te_state.wgt_id {
             // in edit mode; similar calculation in setCursorAt()
             dctx.strbuff = te_state.txt.clone();
-            let mut cursor_pos = te_state.cursor_pos;
-            let delta = max_w / 2;
-
-            while cursor_pos >= max_w - 1 {
-                cursor_pos -= delta;
-                display_pos += delta;
-            }
+            invalid = te_state.error.is_some();
+            selection = te_state.selection();
+            display_pos = wgt::text_edit_display_pos(dctx.wgt, te_state.cursor_pos);
         }
         else {
             dctx.strbuff.clear();
             dctx.wnd_state
                 .get_text_edit_text(dctx.wgt, &mut dctx.strbuff, false);
+
+            if !prp.input_mask.is_empty() {
+                dctx.strbuff = InputMask::new(prp.input_mask).apply(&dctx.strbuff);
+            }
         }
         // guard dropped
     }
@@ -378,10 +489,23 @@ fn draw_text_edit(dctx: &mut DrawCtx, prp: &prop::TextEdit) {
         dctx.strbuff
             .set_displayed_width(dctx.wgt.size.width as i16 - 3);
     }
+
+    if let Some((from, to)) = selection {
+        // displayed part of the selection
+        let from = (from as i16 - display_pos).max(tetrary!(display_pos > 0, 1, 0));
+        let to = (to as i16 - display_pos).min(max_w);
+
+        if from < to {
+            dctx.strbuff
+                .insert_str_at_char_idx(to as usize, esc::INVERSE_OFF);
+            dctx.strbuff
+                .insert_str_at_char_idx(from as usize, esc::INVERSE_ON);
+        }
+    }
     dctx.strbuff.append("[^]");
 
     let focused = dctx.wnd_state.is_focused(dctx.wgt);
-    let clbg = get_widget_bg_color(dctx.wgt).intensify_if(focused);
+    let clbg = tetrary!(invalid, ColorBg::Red, get_widget_bg_color(dctx.wgt)).intensify_if(focused);
     let _fm = FontMemento::new(&dctx.term_cell);
     let mut term = dctx.term_cell.borrow_mut();
     term.move_to(
@@ -1007,7 +1131,7 @@ fn draw_text_box(dctx: &mut DrawCtx, prp: &prop::TextBox) {
 
     let lines_visible = dctx.wgt.size.height as i16 - 2;
     let mut tbs = Default::default();
-    dctx.wnd_state.get_text_box_state(dctx.wgt, &mut tbs);
+    wgt::get_text_box_state_wrapped(dctx.wnd_state, dctx.wgt, &mut tbs);
 
     if alloc::sync::Arc::strong_count(&tbs.lines) > 0 {
         let lines = tbs.lines.as_ref().borrow();
@@ -1021,6 +1145,16 @@ fn draw_text_box(dctx: &mut DrawCtx, prp: &prop::TextBox) {
             tbs.top_line = 0;
         }
 
+        let (selection, search_pattern, prompt) = {
+            let wgtstate_guard = WGT_STATE.try_lock().unwrap();
+            let search = &wgtstate_guard.text_box_search;
+
+            (
+                wgtstate_guard.text_box_sel.lines(dctx.wgt.id),
+                search.pattern(dctx.wgt.id).map(String::from),
+                search.is_prompt(dctx.wgt.id),
+            )
+        };
         let mut term = dctx.term_cell.borrow_mut();
 
         draw_list_scroll_bar_v(
@@ -1031,23 +1165,41 @@ fn draw_text_box(dctx: &mut DrawCtx, prp: &prop::TextBox) {
             tbs.top_line,
         );
 
+        let cols_max = wgt::text_box_cols_max(dctx.wgt, &lines);
+        let left_col = tbs.left_col.clamp(0, cols_max);
+
+        if cols_max > 0 {
+            draw_list_scroll_bar_h(
+                &mut term,
+                my_coord + Coord::new(1, dctx.wgt.size.height - 1),
+                dctx.wgt.size.width as i16 - 2,
+                cols_max,
+                left_col,
+            );
+        }
+
+        if prompt {
+            // search prompt in place of the bottom frame
+            dctx.strbuff.clear();
+            dctx.strbuff.push('/');
+            dctx.strbuff
+                .push_str(search_pattern.as_deref().unwrap_or_default());
+            dctx.strbuff
+                .set_displayed_width(dctx.wgt.size.width as i16 - 2);
+            term.move_to(
+                my_coord.col as u16 + 1,
+                my_coord.row as u16 + dctx.wgt.size.height as u16 - 1,
+            );
+            term.write_str(dctx.strbuff.as_str());
+        }
+
         term.flush_buff();
         dctx.strbuff.clear();
 
         // scan invisible lines (because scrooled down) for ESC sequences: colors, font attributes
         for i in 0..tbs.top_line as usize {
             if let Some(line) = lines.get(i) {
-                // iterate over all ESC sequences in the line
-                for it in line.bytes().enumerate() {
-                    if it.1 == crate::esc::ESC_U8 {
-                        let esc = line.as_str();
-                        // get the sequence and push it to the output stream
-                        let esc = &esc[it.0..esc.len()];
-                        let esclen = esc.esc_seq_len();
-                        let sequence = &esc[0..esclen];
-                        dctx.strbuff.push_str(sequence);
-                    }
-                }
+                push_esc_sequences(&mut dctx.strbuff, line);
             }
         }
 
@@ -1055,15 +1207,60 @@ fn draw_text_box(dctx: &mut DrawCtx, prp: &prop::TextBox) {
 
         // draw lines
         for i in 0..lines_visible as usize {
+            let line_idx = tbs.top_line + i as i16;
+            let selected = selection.is_some_and(|(from, to)| line_idx >= from && line_idx <= to);
             dctx.strbuff.clear();
 
-            if tbs.top_line as usize + i < lines.len() {
-                if let Some(line) = lines.get(tbs.top_line as usize + i) {
+            if selected {
+                dctx.strbuff.push_str(esc::INVERSE_ON);
+            }
+
+            let line = lines.get(line_idx as usize).map(|line| {
+                let matches = search_pattern
+                    .as_deref()
+                    .map(|pattern| wgt::text_box_matches(line, pattern))
+                    .unwrap_or_default();
+
+                if matches.is_empty() {
+                    Cow::Borrowed(line.as_str())
+                }
+                else {
+                    Cow::Owned(highlight_matches(
+                        line,
+                        &matches,
+                        get_widget_fg_color(dctx.wgt),
+                        get_widget_bg_color(dctx.wgt),
+                    ))
+                }
+            });
+
+            if let Some(ref line) = line {
+                if left_col > 0 {
+                    dctx.strbuff
+                        .push_str(&line.skip_displayed_cols(left_col as usize));
+                }
+                else {
                     dctx.strbuff.push_str(line);
                 }
             }
             dctx.strbuff
                 .set_displayed_width(dctx.wgt.size.width as i16 - 2); //, true);
+
+            if let Some(ref line) = line {
+                let styled = prp.markup || matches!(line, Cow::Owned(_));
+
+                if styled
+                    && line.displayed_width()
+                        > (dctx.wgt.size.width as usize - 2 + left_col as usize)
+                {
+                    // truncated; restore the attributes the line ends with
+                    push_esc_sequences(&mut dctx.strbuff, line);
+                }
+            }
+
+            if selected {
+                dctx.strbuff.push_str(esc::INVERSE_OFF);
+            }
             term.move_to(my_coord.col as u16 + 1, my_coord.row as u16 + i as u16 + 1);
             term.write_str(dctx.strbuff.as_str());
         }
@@ -1181,7 +1378,7 @@ fn draw_area(
         strbuff.push_str(cl_fg.encode());
     }
 
-    for r in coord.row + 1..coord.row + size.height - 1 {
+    for _ in 2..size.height {
         term.write_str(strbuff.as_str());
         term.move_by(-(size.width as i16 + shadow as i16), 1);
         term.flush_buff();
@@ -1206,7 +1403,7 @@ fn draw_area(
         term.move_by(-(size.width as i16), 1);
         strbuff.clear();
         // trailing shadow
-        draw_line(&mut strbuff, '█', size.width + 2);
+        draw_line(&mut strbuff, '█', size.width.saturating_add(2));
         term.write_str(strbuff.as_str());
         term.write_str(cl_fg.encode());
         term.flush_buff();
@@ -1216,7 +1413,7 @@ fn draw_area(
 }
 
 fn draw_line(strbuff: &mut String, c: char, len: u8) {
-    if cfg!(fast_line) {
+    if cfg!(feature = "fast_line") {
         strbuff.push(c);
         strbuff.push_esc_fmt(esc::CHAR_REPEAT_LAST_FMT, len as i16 - 3);
     }
@@ -1226,7 +1423,64 @@ fn draw_line(strbuff: &mut String, c: char, len: u8) {
     }
 }
 
+/// Returns the TextBox line with the search matches (character index ranges) highlighted;
+/// after the match, the widget colors `fg`, `bg` and the line styles are restored
+fn highlight_matches(line: &str, matches: &[(usize, usize)], fg: ColorFg, bg: ColorBg) -> String {
+    let mut out = String::with_capacity(line.len() + matches.len() * 24);
+    let mut matches = matches.iter().peekable();
+    let mut char_idx = 0;
+    let mut it = line.char_indices();
+
+    while let Some((byte_idx, c)) = it.next() {
+        let esc_len = line[byte_idx..].esc_seq_len();
+
+        if esc_len > 0 {
+            out.push_str(&line[byte_idx..byte_idx + esc_len]);
+            it.nth(esc_len - 2);
+            continue;
+        }
+
+        if let Some(&&(from, to)) = matches.peek() {
+            if char_idx == from {
+                out.push_str(ColorFg::Black.encode());
+                out.push_str(ColorBg::Yellow.encode());
+            }
+
+            out.push(c);
+            char_idx += 1;
+
+            if char_idx == to {
+                out.push_str(fg.encode());
+                out.push_str(bg.encode());
+                // styles of the line, like markup colors, set before the match
+                push_esc_sequences(&mut out, &line[..byte_idx]);
+                matches.next();
+            }
+        }
+        else {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+/// Appends all ESC sequences found in the text, like colors and font attributes
+fn push_esc_sequences(out: &mut String, s: &str) {
+    for (byte_idx, b) in s.bytes().enumerate() {
+        if b == esc::ESC_U8 {
+            let esclen = s[byte_idx..].esc_seq_len();
+            out.push_str(&s[byte_idx..byte_idx + esclen]);
+        }
+    }
+}
+
 fn draw_list_scroll_bar_v(term: &mut Term, coord: Coord, height: i16, pos_max: i16, pos: i16) {
+    if pos_max == 0 {
+        // nothing to scroll
+        return;
+    }
+
     if pos > pos_max {
         tr_debug!("W: pos ({}) > max ({})", pos, pos_max);
         return;
@@ -1241,6 +1495,24 @@ fn draw_list_scroll_bar_v(term: &mut Term, coord: Coord, height: i16, pos_max: i
     }
 }
 
+fn draw_list_scroll_bar_h(term: &mut Term, coord: Coord, width: i16, pos_max: i16, pos: i16) {
+    if pos_max == 0 {
+        return;
+    }
+
+    if pos > pos_max {
+        tr_debug!("W: pos ({}) > max ({})", pos, pos_max);
+        return;
+    }
+
+    let slider_at = ((width - 1) * pos) / pos_max;
+    term.move_to(coord.col.into(), coord.row.into());
+
+    for i in 0..width {
+        term.write_char(if i == slider_at { '◘' } else { '▒' });
+    }
+}
+
 #[derive(Default)]
 struct DrawListParams {
     coord: Coord,
@@ -1314,7 +1586,8 @@ where
     }
 }
 
-fn get_widget_bg_color(wgt: &Widget) -> ColorBg {
+/// Returns the widget background color, inherited from the parent if not set
+pub(crate) fn get_widget_bg_color(wgt: &Widget) -> ColorBg {
     let mut cl = match wgt.prop {
         Property::Window(ref p) => p.bg_color,
         Property::Panel(ref p) => p.bg_color,
@@ -1337,7 +1610,8 @@ fn get_widget_bg_color(wgt: &Widget) -> ColorBg {
     cl
 }
 
-fn get_widget_fg_color(wgt: &Widget) -> ColorFg {
+/// Returns the widget foreground color, inherited from the parent if not set
+pub(crate) fn get_widget_fg_color(wgt: &Widget) -> ColorFg {
     let mut cl = match wgt.prop {
         Property::Window(ref p) => p.fg_color,
         Property::Panel(ref p) => p.fg_color,
@@ -3,8 +3,12 @@
 #![allow(dead_code)]
 #![allow(unused_variables)]
 
+use crate::colors::{ColorBg, ColorFg};
 use crate::common::*;
 use crate::input::*;
+use crate::input_mask::InputMask;
+use crate::keymap::{self, Action};
+use crate::markup;
 use crate::string_ext::*;
 use crate::widget_def::*;
 use crate::*; // tr_info
@@ -13,9 +17,12 @@ use atomic_once_cell::AtomicLazy;
 use try_lock::TryLock;
 
 extern crate alloc;
+use alloc::collections::VecDeque;
 use alloc::format;
 use alloc::string::String;
 use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
 // ---------------------------------------------------------------------------------------------- //
 
@@ -28,11 +35,22 @@ pub(crate) struct WidgetState {
     pub cbx_drop_down: WId,
     pub text_edit_state: TextEditState,
     pub mouse_down_ii: InputInfo,
+    pub text_box_sel: TextBoxSelection,
+    pub text_box_search: TextBoxSearch,
+    pub text_box_wrap: Vec<TextBoxWrapCache>,
+    // timestamp and position of the last click, to detect the double-click
+    pub last_click: Option<(u32, Coord)>,
+    pub clipboard: String,
 }
 
 impl WidgetState {
     pub fn reset(&mut self) {
-        *self = Self::default();
+        // clipboard content survives the window change
+        let clipboard = core::mem::take(&mut self.clipboard);
+        *self = Self {
+            clipboard,
+            ..Self::default()
+        };
     }
 }
 
@@ -42,8 +60,124 @@ pub(crate) struct TextEditState {
     pub wgt_id: WId,
     pub cursor_pos: i16,
     pub txt: String,
+    // validation error of the text being edited
+    pub error: Option<&'static str>,
+    // selection spans from the anchor to the cursor
+    pub sel_anchor: Option<i16>,
+    // edit session history: the text and cursor position before the change
+    pub undo: VecDeque<(String, i16)>,
+    pub redo: VecDeque<(String, i16)>,
+    // the last change was typing; next typed characters join the same undo step
+    pub typing: bool,
+}
+
+impl TextEditState {
+    /// Starts new edit session history
+    pub fn reset_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.typing = false;
+    }
+
+    /// Saves the text state before the change; the oldest step is dropped if the history is full
+    pub fn push_undo(&mut self, txt: String, cursor_pos: i16) {
+        if self.undo.len() >= UNDO_MAX_STEPS {
+            self.undo.pop_front();
+        }
+        self.undo.push_back((txt, cursor_pos));
+        self.redo.clear();
+    }
+
+    /// Returns the selected characters range
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        match self.sel_anchor {
+            Some(anchor) if anchor != self.cursor_pos => {
+                let from = anchor.min(self.cursor_pos).max(0) as usize;
+                let to = anchor.max(self.cursor_pos).max(0) as usize;
+                Some((from, to))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Lines selected in the TextBox
+#[derive(Default, Clone, Copy)]
+pub(crate) struct TextBoxSelection {
+    pub wgt_id: WId,
+    // selection spans from the anchor line to the cursor line, inclusive
+    pub anchor: i16,
+    pub cursor: i16,
+}
+
+impl TextBoxSelection {
+    /// Returns the selected lines range of given TextBox, inclusive
+    pub fn lines(&self, wid: WId) -> Option<(i16, i16)> {
+        if self.wgt_id == wid && wid != WIDGET_ID_NONE {
+            Some((self.anchor.min(self.cursor), self.anchor.max(self.cursor)))
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// TextBox lines wrapped to the widget width, reused until the source lines or the width change
+#[derive(Clone)]
+pub(crate) struct TextBoxWrapCache {
+    pub wgt_id: WId,
+    pub max_w: usize,
+    pub colors: (ColorFg, ColorBg),
+    // source list kept alive, so its address identifies it;
+    // changes in place are detected by the length and the generation
+    pub src: utils::StringListRc,
+    pub src_len: usize,
+    pub generation: u32,
+    pub wrapped: utils::StringListRc,
+    // source line index of each wrapped line
+    pub src_lines: Arc<Vec<usize>>,
+}
+
+// SAFETY:
+// the lists are cloned and borrowed only by the `get_text_box_state_wrapped()`, called while
+// processing the input or drawing the window, on the thread owning the `WindowState`
+// that provides the source lines
+unsafe impl Send for TextBoxWrapCache {}
+
+/// Text searched in the TextBox
+#[derive(Default, Clone)]
+pub(crate) struct TextBoxSearch {
+    pub wgt_id: WId,
+    pub pattern: String,
+    // the pattern is being typed in the prompt
+    pub prompt: bool,
+    // line of the current match, -1 if none
+    pub line: i16,
+}
+
+impl TextBoxSearch {
+    /// Returns the searched text of given TextBox
+    pub fn pattern(&self, wid: WId) -> Option<&str> {
+        if self.wgt_id == wid && wid != WIDGET_ID_NONE && !self.pattern.is_empty() {
+            Some(self.pattern.as_str())
+        }
+        else {
+            None
+        }
+    }
+
+    /// Returns true if the search prompt of given TextBox is open
+    pub fn is_prompt(&self, wid: WId) -> bool {
+        self.wgt_id == wid && wid != WIDGET_ID_NONE && self.prompt
+    }
 }
 
+/// TextEdit undo history length
+const UNDO_MAX_STEPS: usize = 32;
+
+/// Maximum time between clicks of the double-click
+const DOUBLE_CLICK_MS: u32 = 400;
+
 pub(crate) static WGT_STATE: AtomicLazy<TryLock<WidgetState>> =
     AtomicLazy::new(|| TryLock::new(WidgetState::default()));
 
@@ -160,64 +294,18 @@ pub fn find_at(
     let wgts = ws.get_widgets();
 
     for wgt in wgts.iter() {
-        let mut stop_searching = true;
-        let mut wgt_screen_rect = Rect::cdeflt();
-
-        wgt_screen_rect.coord = get_screen_coord(ws, wgt);
-        wgt_screen_rect.size = wgt.size;
-
-        // eprintln!("{1:2}:{0:} at {2:2}:{3:-2}", wgt.prop, wgt.id, wgt_screen_rect.coord.col, wgt_screen_rect.coord.row);
-
-        // correct the widget size
-        match wgt.prop {
-            Property::TextEdit(ref _p) => {}
-            Property::CheckBox(ref p) => {
-                wgt_screen_rect.size.height = 1;
-                wgt_screen_rect.size.width = 4 + p.text.displayed_width() as u8;
-            }
-            Property::Radio(ref p) => {
-                wgt_screen_rect.size.height = 1;
-                wgt_screen_rect.size.width = 4 + p.text.displayed_width() as u8;
-            }
-            Property::Button(ref p) => {
-                let txt_w = {
-                    if !p.text.is_empty() {
-                        p.text.displayed_width() as u8
-                    }
-                    else if wgt.size.width > 0 {
-                        wgt.size.width
-                    }
-                    else {
-                        let mut s = String::new();
-                        ws.get_button_text(wgt, &mut s);
-                        s.displayed_width() as u8
-                    }
-                };
-
-                match p.style {
-                    ButtonStyle::Simple => {
-                        wgt_screen_rect.size.height = 1;
-                        wgt_screen_rect.size.width = 4 + txt_w;
-                    }
-                    ButtonStyle::Solid => {
-                        wgt_screen_rect.size.height = 1;
-                        wgt_screen_rect.size.width = 2 + txt_w;
-                    }
-                    ButtonStyle::Solid1p5 => {
-                        wgt_screen_rect.size.height = 3;
-                        wgt_screen_rect.size.width = 2 + txt_w;
-                    }
-                }
-            }
-            Property::PageCtrl(ref p) => {
-                wgt_screen_rect.size.width = p.tab_width;
-            }
-            Property::ListBox(ref _p) => {}
-            Property::ComboBox(ref _p) => {}
-            _ => {
-                stop_searching = false;
-            }
-        }
+        // other widgets may contain smaller ones, so keep searching
+        let stop_searching = matches!(
+            wgt.prop,
+            Property::TextEdit(_)
+                | Property::CheckBox(_)
+                | Property::Radio(_)
+                | Property::Button(_)
+                | Property::PageCtrl(_)
+                | Property::ListBox(_)
+                | Property::ComboBox(_)
+        );
+        let wgt_screen_rect = get_screen_rect(ws, wgt);
 
         if wgt_screen_rect.is_point_within(col, row) {
             let is_visible = is_visible(ws, wgt); // controls on tabs? solved
@@ -238,6 +326,65 @@ pub fn find_at(
     found_wgt
 }
 
+/// Returns the widget screen-based rectangle, with the size
+/// corrected to the actually drawn area, like the button width computed from its text
+pub fn get_screen_rect(ws: &mut dyn WindowState, wgt: &Widget) -> Rect {
+    let mut wgt_screen_rect = Rect::cdeflt();
+
+    wgt_screen_rect.coord = get_screen_coord(ws, wgt);
+    wgt_screen_rect.size = wgt.size;
+
+    match wgt.prop {
+        Property::CheckBox(ref p) => {
+            wgt_screen_rect.size.height = 1;
+            wgt_screen_rect.size.width = 4 + p.text.displayed_width() as u8;
+        }
+        Property::Radio(ref p) => {
+            wgt_screen_rect.size.height = 1;
+            wgt_screen_rect.size.width = 4 + p.text.displayed_width() as u8;
+        }
+        Property::Button(ref p) => {
+            let txt_w = {
+                if !p.text.is_empty() {
+                    p.text.displayed_width() as u8
+                }
+                else if wgt.size.width > 0 {
+                    wgt.size.width
+                }
+                else {
+                    let mut s = String::new();
+                    ws.get_button_text(wgt, &mut s);
+                    s.displayed_width() as u8
+                }
+            };
+
+            match p.style {
+                ButtonStyle::Simple => {
+                    wgt_screen_rect.size.height = 1;
+                    wgt_screen_rect.size.width = 4 + txt_w;
+                }
+                ButtonStyle::Solid => {
+                    wgt_screen_rect.size.height = 1;
+                    wgt_screen_rect.size.width = 2 + txt_w;
+                }
+                ButtonStyle::Solid1p5 => {
+                    wgt_screen_rect.size.height = 3;
+                    wgt_screen_rect.size.width = 2 + txt_w;
+                }
+            }
+        }
+        Property::PageCtrl(ref p) => {
+            wgt_screen_rect.size.width = p.tab_width;
+        }
+        Property::Window(_) => {
+            wgt_screen_rect.size = get_window_size(ws);
+        }
+        _ => {}
+    }
+
+    wgt_screen_rect
+}
+
 pub fn get_screen_coord(ws: &mut dyn WindowState, wgt: &Widget) -> Coord {
     if matches!(wgt.prop, Property::Window(_)) {
         return ws.get_window_coord();
@@ -261,6 +408,21 @@ pub fn get_screen_coord(ws: &mut dyn WindowState, wgt: &Widget) -> Coord {
         })
 }
 
+/// Returns the window size; the size provided by the window state takes precedence
+/// over the window widget size, allowing the window to be resized
+pub fn get_window_size(ws: &mut dyn WindowState) -> Size {
+    let size = ws.get_window_size();
+
+    if size.width > 0 && size.height > 0 {
+        size
+    }
+    else {
+        ws.get_widgets()
+            .first()
+            .map_or(Size::cdeflt(), |wnd| wnd.size)
+    }
+}
+
 /// Move cursor to the best position for given type of the widget
 pub fn set_cursor_at(term: &mut Term, ws: &mut dyn WindowState, wgt: &Widget) {
     let mut coord = get_screen_coord(ws, wgt);
@@ -307,6 +469,21 @@ pub fn set_cursor_at(term: &mut Term, ws: &mut dyn WindowState, wgt: &Widget) {
             coord.row += 1 + p.vert_offs;
             coord.row += ws.get_page_ctrl_page_index(wgt) as u8
         }
+        Property::TextBox(_) => {
+            let wgtstate_guard = WGT_STATE.try_lock().unwrap();
+            let search = &wgtstate_guard.text_box_search;
+
+            if search.is_prompt(wgt.id) {
+                // after the search prompt text
+                let pattern_w = search.pattern.displayed_width().min(i16::MAX as usize) as i16;
+                let prompt_w = pattern_w
+                    .saturating_add(2)
+                    .min(wgt.size.width as i16 - 2)
+                    .max(0);
+                coord.col = coord.col.saturating_add(prompt_w as u8);
+                coord.row = coord.row.saturating_add(wgt.size.height.saturating_sub(1));
+            }
+        }
         Property::ListBox(ref p) => {
             let mut lbs = Default::default();
             let frame_size = p.no_frame as u8;
@@ -337,6 +514,21 @@ pub fn reset_internal_state() {
     WGT_STATE.try_lock().unwrap().reset();
 }
 
+/// Puts the text into the internal clipboard;
+/// also sends it to the terminal host system clipboard, if enabled by `Term::osc52_clipboard()`
+pub fn clipboard_set(text: &str) {
+    WGT_STATE.try_lock().unwrap().clipboard = text.to_string();
+
+    if let Some(mut term_guard) = TERM.try_lock() {
+        term_guard.clipboard_copy(text);
+    }
+}
+
+/// Returns the internal clipboard text
+pub fn clipboard_get() -> String {
+    WGT_STATE.try_lock().unwrap().clipboard.clone()
+}
+
 pub fn process_input(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
     let mut input_handled;
 
@@ -348,30 +540,77 @@ pub fn process_input(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
         InputEvent::Mouse(_) => {
             input_handled = process_mouse(ws, ii);
         }
-        InputEvent::Key(_) | InputEvent::Char(_) => {
-            input_handled = process_key(ws, ii);
+        InputEvent::Focus(_) => {
+            // terminal focus concerns the entire window
+            input_handled = match ws.get_widgets().first() {
+                Some(wnd) => ws.on_window_unhandled_input_evt(wnd, ii),
+                None => false,
+            };
+        }
+        InputEvent::Key(_) | InputEvent::Char(_) | InputEvent::Paste(_) => {
+            let act = keymap::action_of(ii);
+            input_handled = process_key(ws, ii, act);
+
+            if !input_handled && (ii.kmod.has_special() || act.is_some()) {
+                // plain characters bound to eg. Toggle or FindNext keep the drop-down open
+                let close_drop_down = ii.kmod.has_special()
+                    || matches!(
+                        act,
+                        Some(
+                            Action::FocusNext
+                                | Action::FocusPrev
+                                | Action::FocusParent
+                                | Action::FocusLeft
+                                | Action::FocusRight
+                                | Action::FocusUp
+                                | Action::FocusDown
+                                | Action::Cancel
+                        )
+                    );
 
-            if !input_handled && ii.kmod.has_special() {
-                let dd_combo_id = WGT_STATE.try_lock().unwrap().cbx_drop_down;
+                if close_drop_down {
+                    let dd_combo_id = WGT_STATE.try_lock().unwrap().cbx_drop_down;
 
-                if let Some(wgt) = find_by_id(ws.get_widgets(), dd_combo_id) {
-                    hide_combo_box_dropdown_list(ws, wgt);
+                    if let Some(wgt) = find_by_id(ws.get_widgets(), dd_combo_id) {
+                        hide_combo_box_dropdown_list(ws, wgt);
+                    }
                 }
 
-                if let InputEvent::Key(ref key) = ii.evnt {
-                    match *key {
-                        Key::Esc => {
-                            let curr_id = ws.get_focused_id();
-                            let new_id = get_parent_to_focus(ws, curr_id);
-                            input_handled = change_focus_to(ws, new_id);
-                        }
-                        Key::Tab => {
+                match act {
+                    Some(Action::FocusParent) | Some(Action::Cancel) => {
+                        let curr_id = ws.get_focused_id();
+                        let new_id = get_parent_to_focus(ws, curr_id);
+                        input_handled = change_focus_to(ws, new_id);
+                    }
+                    Some(Action::FocusNext) | Some(Action::FocusPrev) => {
+                        let curr_id = ws.get_focused_id();
+                        let forward = act == Some(Action::FocusNext);
+                        let new_id = get_next_to_focus(ws, curr_id, forward);
+                        input_handled = change_focus_to(ws, new_id);
+                    }
+                    Some(act) if !is_text_edit_active() => {
+                        // arrow keys not used by the focused widget move the focus
+                        let dir = match act {
+                            Action::LineUp => Action::FocusUp,
+                            Action::LineDown => Action::FocusDown,
+                            Action::CursorLeft => Action::FocusLeft,
+                            Action::CursorRight => Action::FocusRight,
+                            _ => act,
+                        };
+
+                        if matches!(
+                            dir,
+                            Action::FocusLeft
+                                | Action::FocusRight
+                                | Action::FocusUp
+                                | Action::FocusDown
+                        ) {
                             let curr_id = ws.get_focused_id();
-                            let new_id = get_next_to_focus(ws, curr_id, !ii.kmod.has_shift());
+                            let new_id = get_nearest_to_focus(ws, curr_id, dir);
                             input_handled = change_focus_to(ws, new_id);
                         }
-                        _ => {}
                     }
+                    _ => {}
                 }
             }
 
@@ -578,220 +817,194 @@ fn is_focusable_by_id(ws: &mut dyn WindowState, widget_id: WId) -> bool {
     }
 }
 
-fn get_next_focusable(
+/// Returns the container that Tab cycles within: the nearest Page
+/// or widget marked by `WindowState::is_focus_scope()`, or the window
+fn get_focus_scope(ws: &mut dyn WindowState, wgt: &'static Widget) -> &'static Widget {
+    wgt.iter_parents()
+        .skip(1) // because iterator starts at the widget itself
+        .find(|parent| matches!(parent.prop, Property::Page(_)) || ws.is_focus_scope(parent))
+        .unwrap_or(&ws.get_widgets()[0])
+}
+
+/// Collects visible widgets reachable by Tab, with their ordering key;
+/// focused widget is collected even if it is not a tab stop
+fn collect_tab_stops(
     ws: &mut dyn WindowState,
-    mut parent: &'static Widget,
+    parent: &'static Widget,
     focused_id: WId,
-    forward: bool,
-    mut first_parent: Option<&'static Widget>,
-    break_search: &mut bool,
-) -> Option<&'static Widget> {
-    if let Some(fp) = first_parent {
-        if core::ptr::eq(parent, fp) {
-            tr_warn!("full loop detected"); // (pFirstParent id=%d)", pFirstParent?pFirstParent->id:-1);
-            *break_search = true;
-            return None;
-        }
-    }
-
-    if parent.id == focused_id {
-        return None;
-    }
-
-    let children;
-    let child_cnt;
-
-    // get childrens and their number
-    match parent.prop {
-        Property::Window(_) | Property::Panel(_) | Property::Page(_) | Property::Layer(_) => {
-            children = &ws.get_widgets()[parent.link.children_idx as usize..];
-            child_cnt = parent.link.children_cnt;
+    out: &mut Vec<(WId, (bool, u16))>,
+) {
+    for wgt in parent.iter_children() {
+        if !ws.is_visible(wgt) {
+            continue;
         }
-        Property::PageCtrl(_) => {
-            // get selected page childrens
-            let idx = ws.get_page_ctrl_page_index(parent);
 
-            if idx < parent.link.children_cnt as i16 {
-                let wgts = ws.get_widgets();
-                let idx = parent.link.children_idx as usize + idx as usize;
-                parent = &wgts[idx];
-                children = &wgts[parent.link.children_idx as usize..];
-                child_cnt = parent.link.children_cnt;
+        match wgt.prop {
+            Property::PageCtrl(_) => {
+                // only the selected page
+                let idx = ws.get_page_ctrl_page_index(wgt);
+                if let Some(page) = wgt.iter_children().nth(idx as usize) {
+                    if ws.is_visible(page) {
+                        collect_tab_stops(ws, page, focused_id, out);
+                    }
+                }
             }
-            else {
-                return None;
+            _ if is_parent(wgt) => {
+                collect_tab_stops(ws, wgt, focused_id, out);
             }
-        }
-        _ => {
-            tr_warn!(
-                "Widget [{} id:{}] is not a parent type widget",
-                parent.prop.to_string(),
-                parent.id
-            );
-            return None;
-        }
-    }
-
-    if child_cnt == 0 {
-        return None;
-    }
-
-    match parent.prop {
-        Property::Page(_) | Property::Panel(_) | Property::Layer(_) => {
-            if first_parent.is_none() {
-                // it must be Panel/Page/Layer because while traversing we never step below Page level
-                tr_debug!("1st parent[{} id:{}]", parent.prop.to_string(), parent.id);
-                first_parent = Some(parent);
+            _ => {
+                if is_focusable(ws, wgt) && (wgt.id == focused_id || ws.is_tab_stop(wgt)) {
+                    // widgets with tab index go first
+                    let key = ws.get_tab_index(wgt).map_or((true, 0), |idx| (false, idx));
+                    out.push((wgt.id, key));
+                }
             }
         }
-        _ => {}
     }
+}
+
+fn get_next_to_focus(ws: &mut dyn WindowState, focused_id: WId, forward: bool) -> WId {
+    let wgts = ws.get_widgets();
+    let focused_wgt = match find_by_id(wgts, focused_id).or(wgts.first()) {
+        Some(wgt) => wgt,
+        None => return WIDGET_ID_NONE,
+    };
 
-    assert!(!children.is_empty());
-    let mut child_idx = 0;
     tr_debug!(
-        "parent[{} id:{}] focused_id={}",
-        parent.prop.to_string(),
-        parent.id,
-        focused_id
+        "focused_wgt: {} id={}",
+        focused_wgt.prop.to_string(),
+        focused_wgt.id
     );
 
-    if focused_id == WIDGET_ID_NONE {
-        // get first/last of the children ID
-        child_idx = tetrary!(forward, 0, child_cnt as usize - 1);
-        let child = &children[child_idx];
+    let scope = get_focus_scope(ws, focused_wgt);
+    let mut tab_stops = Vec::new();
+    collect_tab_stops(ws, scope, focused_id, &mut tab_stops);
+    // stable sort keeps the tree order of widgets with the same tab index
+    tab_stops.sort_by_key(|(_, key)| *key);
 
-        if is_focusable(ws, child) && is_visible(ws, child) {
-            return Some(child);
+    let cnt = tab_stops.len();
+    if cnt == 0 {
+        return WIDGET_ID_NONE;
+    }
+
+    let idx = match tab_stops.iter().position(|(id, _)| *id == focused_id) {
+        Some(pos) => tetrary!(forward, (pos + 1) % cnt, (pos + cnt - 1) % cnt),
+        None => tetrary!(forward, 0, cnt - 1),
+    };
+
+    tab_stops[idx].0
+}
+
+/// Finds the focusable widget nearest to the focused one in the direction given by
+/// `FocusLeft`/`FocusRight`/`FocusUp`/`FocusDown` action;
+/// if there is none, the next or previous widget in the Tab order is returned
+fn get_nearest_to_focus(ws: &mut dyn WindowState, focused_id: WId, dir: Action) -> WId {
+    let forward = matches!(dir, Action::FocusRight | Action::FocusDown);
+    let focused_rect = match find_by_id(ws.get_widgets(), focused_id) {
+        Some(focused_wgt) => get_screen_rect(ws, focused_wgt),
+        None => return get_next_to_focus(ws, focused_id, forward),
+    };
+
+    let mut best_id = WIDGET_ID_NONE;
+    let mut best_score = (true, i32::MAX, i32::MAX);
+
+    for wgt in ws.get_widgets().iter() {
+        if wgt.id == focused_id
+            || !is_focusable(ws, wgt)
+            || !is_visible(ws, wgt)
+            || !is_on_selected_page(ws, wgt)
+        {
+            continue;
         }
 
-        if is_parent(child) {
-            if let Some(nf) = get_next_focusable(
-                ws,
-                parent,
-                WIDGET_ID_NONE,
-                forward,
-                first_parent,
-                break_search,
-            ) {
-                return Some(nf);
+        let rect = get_screen_rect(ws, wgt);
+        if let Some(score) = spatial_distance(&focused_rect, &rect, dir) {
+            if score < best_score {
+                best_score = score;
+                best_id = wgt.id;
             }
         }
     }
-    else {
-        // get pointer to focusedID
-        while child_idx < child_cnt as usize && children[child_idx].id != focused_id {
-            child_idx += 1;
-        }
 
-        if child_idx >= child_cnt as usize {
-            tr_warn!(
-                "Focused ID={} not found on parent ID={}",
-                focused_id,
-                parent.id
-            );
-            return None;
-        }
+    if best_id == WIDGET_ID_NONE {
+        return get_next_to_focus(ws, focused_id, forward);
     }
 
-    tr_debug!(
-        "Search in [{} id:{} children cnt:{}]",
-        parent.prop.to_string(),
-        parent.id,
-        child_cnt
-    );
+    best_id
+}
 
-    // iterate until focusable found or children border reached
-    for _ in 0..child_cnt {
-        if forward {
-            child_idx += 1;
+/// Returns the distance from `from` to `to` rectangle if `to` lies in the `dir` direction;
+/// rectangles in line with `from` are always closer than the others;
+/// rows are counted twice, as the terminal cell is about twice as high as wide
+fn spatial_distance(from: &Rect, to: &Rect, dir: Action) -> Option<(bool, i32, i32)> {
+    // (start, end) ranges along the horizontal and vertical axis
+    let h_range = |r: &Rect| (r.coord.col as i32, r.coord.col as i32 + r.size.width as i32);
+    let v_range = |r: &Rect| {
+        (
+            r.coord.row as i32,
+            r.coord.row as i32 + r.size.height as i32,
+        )
+    };
+
+    // gap between two ranges, 0 if they overlap
+    let gap = |a: (i32, i32), b: (i32, i32)| {
+        if a.1 <= b.0 {
+            b.0 - a.1 + 1
+        }
+        else if b.1 <= a.0 {
+            a.0 - b.1 + 1
         }
         else {
-            child_idx = child_idx.overflowing_sub(1).0;
+            0
         }
+    };
 
-        if child_idx == child_cnt as usize || child_idx == usize::MAX {
-            // border reached: if we are on Panel or Layer, jump to next sibling
-            match parent.prop {
-                Property::Panel(_) | Property::Layer(_) => {
-                    let parents_parent = get_parent(parent);
-                    let mut brk = false;
-
-                    return get_next_focusable(
-                        ws,
-                        parents_parent,
-                        parent.id,
-                        forward,
-                        first_parent,
-                        &mut brk,
-                    );
-                }
-                _ => {}
-            }
-
-            // make a turn around
-            if child_idx >= child_cnt as usize {
-                child_idx = 0;
-            }
-            else {
-                child_idx = child_cnt as usize - 1;
-            }
+    let (along_from, along_to, across_from, across_to, scale) = match dir {
+        Action::FocusLeft | Action::FocusRight => {
+            (h_range(from), h_range(to), v_range(from), v_range(to), 2)
         }
-
-        let child = &children[child_idx];
-        if is_focusable(ws, child) && is_visible(ws, child) {
-            return Some(child);
+        Action::FocusUp | Action::FocusDown => {
+            (v_range(from), v_range(to), h_range(from), h_range(to), 1)
         }
+        _ => return None,
+    };
 
-        if is_parent(&children[child_idx]) {
-            let mut brk = false;
-
-            if let Some(nf) = get_next_focusable(
-                ws,
-                &children[child_idx],
-                WIDGET_ID_NONE,
-                forward,
-                first_parent,
-                &mut brk,
-            ) {
-                return Some(nf);
-            }
+    // doubled centers, to stay on integers
+    let center = |r: (i32, i32)| r.0 + r.1;
+    let is_ahead = tetrary!(
+        matches!(dir, Action::FocusRight | Action::FocusDown),
+        center(along_to) > center(along_from) && along_to.0 > along_from.0,
+        center(along_to) < center(along_from) && along_to.1 < along_from.1
+    );
 
-            if brk {
-                break;
-            }
-        }
+    if !is_ahead {
+        return None;
     }
 
-    None
+    let along = gap(along_from, along_to) * 2 / scale;
+    let across = gap(across_from, across_to) * scale;
+    // on equal distance, prefer the closest centers
+    let misalign = (center(across_to) - center(across_from)).abs();
+    Some((across > 0, along + across * 2, misalign))
 }
 
-fn get_next_to_focus(ws: &mut dyn WindowState, focused_id: WId, forward: bool) -> WId {
-    let mut focused_wgt = find_by_id(ws.get_widgets(), focused_id);
-
-    if focused_wgt.is_none() {
-        // here, fail is possible only if invalid focused_id was passed
-        focused_wgt = ws.get_widgets().first();
-    }
-
-    if let Some(focused_wgt) = focused_wgt {
-        tr_debug!(
-            "focused_wgt: {} id={}",
-            focused_wgt.prop.to_string(),
-            focused_wgt.id
-        );
-
-        // use the parent to get next widget
-        let focused_wgt_parent = get_parent(focused_wgt);
-        let mut brk = false;
-        if let Some(nf) =
-            get_next_focusable(ws, focused_wgt_parent, focused_id, forward, None, &mut brk)
-        {
-            return nf.id;
+/// Checks if the widget is not placed on a hidden page of the page control
+fn is_on_selected_page(ws: &mut dyn WindowState, wgt: &Widget) -> bool {
+    for parent in wgt.iter_parents() {
+        if let Some(page_idx) = page_page_idx(parent) {
+            let pgctrl = get_parent(parent);
+            if ws.get_page_ctrl_page_index(pgctrl) != page_idx {
+                return false;
+            }
         }
     }
 
-    WIDGET_ID_NONE
+    true
+}
+
+/// Checks if any TextEdit is in the edit mode
+fn is_text_edit_active() -> bool {
+    WGT_STATE.try_lock().unwrap().text_edit_state.wgt_id != WIDGET_ID_NONE
 }
 
 fn get_parent_to_focus(ws: &mut dyn WindowState, focused_id: WId) -> WId {
@@ -801,7 +1014,7 @@ fn get_parent_to_focus(ws: &mut dyn WindowState, focused_id: WId) -> WId {
         return parent_wgt.id;
     }
 
-    return ws.get_widgets().first().unwrap().id;
+    ws.get_widgets().first().unwrap().id
 }
 
 fn change_focus_to(ws: &mut dyn WindowState, new_id: WId) -> bool {
@@ -812,6 +1025,18 @@ fn change_focus_to(ws: &mut dyn WindowState, new_id: WId) -> bool {
         let prev_id = curr_id;
         ws.set_focused_id(new_id);
 
+        {
+            // lines stay selected only in the focused TextBox
+            let mut wgtstate_guard = WGT_STATE.try_lock().unwrap();
+            if wgtstate_guard.text_box_sel.wgt_id == prev_id {
+                wgtstate_guard.text_box_sel.wgt_id = WIDGET_ID_NONE;
+            }
+            // as well as the search
+            if wgtstate_guard.text_box_search.wgt_id == prev_id {
+                wgtstate_guard.text_box_search = Default::default();
+            }
+        }
+
         if let Some(new_focused_wgt) = find_by_id(ws.get_widgets(), new_id) {
             // tr_debug!(
             //     "new_focused_wgt: {} id={}",
@@ -933,7 +1158,7 @@ fn invalidate_radio_group(ws: &mut dyn WindowState, wgt: &Widget) {
 // ---- WIDGETS KEYBOARD PROCESSING FUNCTIONS --------------------------------------------------- //
 // ---------------------------------------------------------------------------------------------- //
 
-fn process_key(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
+fn process_key(ws: &mut dyn WindowState, ii: &InputInfo, act: Option<Action>) -> bool {
     let focused_id = ws.get_focused_id();
     let wgt = find_by_id(ws.get_widgets(), focused_id);
     let mut key_handled = false;
@@ -944,14 +1169,14 @@ fn process_key(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
         }
 
         key_handled = match wgt.prop {
-            Property::TextEdit(_) => process_key_text_edit(ws, wgt, ii),
-            Property::CheckBox(_) => process_key_check_box(ws, wgt, ii),
-            Property::Radio(_) => process_key_radio(ws, wgt, ii),
-            Property::Button(_) => process_key_button(ws, wgt, ii),
-            Property::PageCtrl(_) => process_key_page_ctrl(ws, wgt, ii),
-            Property::ListBox(_) => process_key_list_box(ws, wgt, ii),
-            Property::ComboBox(_) => process_key_combo_box(ws, wgt, ii),
-            Property::TextBox(_) => process_key_text_box(ws, wgt, ii),
+            Property::TextEdit(_) => process_key_text_edit(ws, wgt, ii, act),
+            Property::CheckBox(_) => process_key_check_box(ws, wgt, ii, act),
+            Property::Radio(_) => process_key_radio(ws, wgt, ii, act),
+            Property::Button(_) => process_key_button(ws, wgt, ii, act),
+            Property::PageCtrl(_) => process_key_page_ctrl(ws, wgt, ii, act),
+            Property::ListBox(_) => process_key_list_box(ws, wgt, ii, act),
+            Property::ComboBox(_) => process_key_combo_box(ws, wgt, ii, act),
+            Property::TextBox(_) => process_key_text_box(ws, wgt, ii, act),
             _ => false,
         };
     }
@@ -959,7 +1184,12 @@ fn process_key(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
     key_handled
 }
 
-fn process_key_text_edit(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
+fn process_key_text_edit(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
     let mut te_state;
 
     let handled = {
@@ -975,8 +1205,12 @@ fn process_key_text_edit(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
             // user let us continue checking the key
         }
 
-        // TODO: improve to avoid cloning
+        // the history is moved out, not cloned on every key
+        let undo = core::mem::take(&mut testate.undo);
+        let redo = core::mem::take(&mut testate.redo);
         te_state = testate.clone();
+        te_state.undo = undo;
+        te_state.redo = redo;
         false
     };
 
@@ -984,28 +1218,141 @@ fn process_key_text_edit(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
         return true;
     }
 
+    // clipboard paste is processed like the terminal one
+    let paste_ii;
+    let (ii, act) = if act == Some(Action::Paste) {
+        paste_ii = InputInfo {
+            evnt: InputEvent::Paste(clipboard_get()),
+            ..InputInfo::default()
+        };
+        (&paste_ii, None)
+    }
+    else {
+        (ii, act)
+    };
+
     let mut key_handled = false;
-    let is_psw_masked = {
+    let (is_psw_masked, mask) = {
         if let Property::TextEdit(ref prop) = wgt.prop {
-            prop.psw_mask
+            let mask = tetrary!(
+                prop.input_mask.is_empty(),
+                None,
+                Some(InputMask::new(prop.input_mask))
+            );
+            (prop.psw_mask, mask)
         }
         else {
-            false
+            (false, None)
         }
     };
 
+    let validator = ws.get_text_edit_validator(wgt);
+
     if te_state.wgt_id != WIDGET_ID_NONE {
         let mut cursor_pos = te_state.cursor_pos as isize;
+        let prev_txt = te_state.txt.clone();
+        let selection = te_state.selection();
+        let sel_anchor = te_state.sel_anchor.take();
+
+        match act {
+            Some(Action::Undo) | Some(Action::Redo) => {
+                let (from, to) = tetrary!(
+                    act == Some(Action::Undo),
+                    (&mut te_state.undo, &mut te_state.redo),
+                    (&mut te_state.redo, &mut te_state.undo)
+                );
+
+                if let Some((txt, pos)) = from.pop_back() {
+                    to.push_back((
+                        core::mem::replace(&mut te_state.txt, txt),
+                        cursor_pos as i16,
+                    ));
+                    cursor_pos = pos as isize;
+                }
+                te_state.typing = false;
+                key_handled = true;
+            }
+            Some(Action::SelectAll) => {
+                te_state.sel_anchor = Some(0);
+                cursor_pos = te_state.txt.chars().count() as isize;
+                key_handled = true;
+            }
+            Some(Action::SelectLeft)
+            | Some(Action::SelectRight)
+            | Some(Action::SelectHome)
+            | Some(Action::SelectEnd) => {
+                if !is_psw_masked {
+                    let len = te_state.txt.chars().count() as isize;
+                    te_state.sel_anchor = Some(sel_anchor.unwrap_or(cursor_pos as i16));
+                    cursor_pos = match act {
+                        Some(Action::SelectLeft) => (cursor_pos - 1).max(0),
+                        Some(Action::SelectRight) => (cursor_pos + 1).min(len),
+                        Some(Action::SelectHome) => 0,
+                        _ => len,
+                    };
+                }
+                key_handled = true;
+            }
+            Some(Action::Copy) | Some(Action::Cut) => {
+                if is_psw_masked {
+                    // password is never copied
+                    ring_bell();
+                }
+                else {
+                    let (from, to) = selection.unwrap_or((0, te_state.txt.chars().count()));
+                    let text: String = te_state.txt.chars().skip(from).take(to - from).collect();
+                    clipboard_set(&text);
 
-        if let InputEvent::Key(ref key) = ii.evnt {
-            match *key {
-                Key::Esc => {
-                    // cancel editing
-                    te_state.wgt_id = WIDGET_ID_NONE;
+                    if act == Some(Action::Cut) {
+                        text_edit_erase(&mut te_state.txt, mask.as_ref(), from, to);
+                        cursor_pos = mask.as_ref().map_or(from, |m| m.next_pos(from)) as isize;
+                    }
+                    else {
+                        te_state.sel_anchor = sel_anchor;
+                    }
+                }
+                key_handled = true;
+            }
+            Some(Action::DeleteBack)
+            | Some(Action::DeleteForward)
+            | Some(Action::DeleteToStart)
+            | Some(Action::DeleteToEnd)
+                if selection.is_some() =>
+            {
+                let (from, to) = selection.unwrap();
+                text_edit_erase(&mut te_state.txt, mask.as_ref(), from, to);
+                cursor_pos = mask.as_ref().map_or(from, |m| m.next_pos(from)) as isize;
+                key_handled = true;
+            }
+            _ => {
+                if let (Some((from, to)), InputEvent::Char(_) | InputEvent::Paste(_)) =
+                    (selection, &ii.evnt)
+                {
+                    // typed or pasted text replaces the selection
+                    text_edit_erase(&mut te_state.txt, mask.as_ref(), from, to);
+                    cursor_pos = mask.as_ref().map_or(from, |m| m.next_pos(from)) as isize;
+                }
+                else if selection.is_some() {
                     ws.invalidate(wgt.id);
-                    key_handled = true;
                 }
-                Key::Tab => {
+            }
+        }
+
+        if !key_handled {
+            if let Some(ref mask) = mask {
+                key_handled =
+                    process_key_masked_edit(mask, ii, act, &mut te_state.txt, &mut cursor_pos);
+            }
+        }
+
+        if key_handled {
+            // handled by the selection or the mask
+            ws.invalidate(wgt.id);
+        }
+        else {
+            // the action is resolved first, so it may be bound to a character, like Ctrl+H
+            match act {
+                Some(Action::FocusNext) | Some(Action::FocusPrev) => {
                     if !is_psw_masked {
                         // real TAB may have different widths and require extra processing
                         te_state.txt.insert_str(cursor_pos.max(0) as usize, "    ");
@@ -1014,16 +1361,38 @@ fn process_key_text_edit(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
                     }
                     key_handled = true;
                 }
-                Key::Enter => {
-                    // finish editing
-                    ws.on_text_edit_change(wgt, &mut te_state.txt);
+                Some(Action::Cancel) => {
+                    // cancel editing
                     te_state.wgt_id = WIDGET_ID_NONE;
+                    te_state.error = None;
+                    ws.invalidate(wgt.id);
+                    key_handled = true;
+                }
+                Some(Action::Activate) => {
+                    te_state.error = validator.and_then(|v| v.validate(&te_state.txt).err());
+
+                    if te_state.error.is_none()
+                        && mask.as_ref().is_some_and(|m| !m.is_complete(&te_state.txt))
+                    {
+                        te_state.error = Some("Incomplete input");
+                    }
+
+                    if te_state.error.is_some() {
+                        // refuse to commit invalid text
+                        ring_bell();
+                        ws.on_text_edit_validate(wgt, te_state.error);
+                    }
+                    else {
+                        // finish editing
+                        ws.on_text_edit_change(wgt, &mut te_state.txt);
+                        te_state.wgt_id = WIDGET_ID_NONE;
+                    }
                     ws.invalidate(wgt.id);
                     key_handled = true;
                 }
-                Key::Backspace => {
+                Some(Action::DeleteBack) | Some(Action::DeleteToStart) => {
                     if cursor_pos > 0 {
-                        if ii.kmod.has_ctrl() {
+                        if act == Some(Action::DeleteToStart) {
                             te_state.txt.erase_char_range(0, cursor_pos as usize);
                             cursor_pos = 0;
                         }
@@ -1037,9 +1406,9 @@ fn process_key_text_edit(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
                     }
                     key_handled = true;
                 }
-                Key::Delete => {
+                Some(Action::DeleteForward) | Some(Action::DeleteToEnd) => {
                     if !is_psw_masked {
-                        if ii.kmod.has_ctrl() {
+                        if act == Some(Action::DeleteToEnd) {
                             te_state.txt.trim_at_char_idx(cursor_pos as usize);
                         }
                         else {
@@ -1050,155 +1419,344 @@ fn process_key_text_edit(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
                     }
                     key_handled = true;
                 }
-                Key::Up | Key::Down => {
-                    //
-                }
-                Key::Left => {
+                Some(Action::CursorLeft) => {
                     if !is_psw_masked && cursor_pos > 0 {
                         cursor_pos -= 1;
                         ws.invalidate(wgt.id);
                     }
                     key_handled = true;
                 }
-                Key::Right => {
+                Some(Action::CursorRight) => {
                     if !is_psw_masked && (cursor_pos < te_state.txt.chars().count() as isize) {
                         cursor_pos += 1;
                         ws.invalidate(wgt.id);
                     }
                     key_handled = true;
                 }
-                Key::Home => {
+                Some(Action::CursorHome) => {
                     if !is_psw_masked {
                         cursor_pos = 0;
                         ws.invalidate(wgt.id);
                     }
                     key_handled = true;
                 }
-                Key::End => {
+                Some(Action::CursorEnd) => {
                     if !is_psw_masked {
                         cursor_pos = te_state.txt.chars().count() as isize;
                         ws.invalidate(wgt.id);
                     }
                     key_handled = true;
                 }
-                _ => {}
+                _ => match ii.evnt {
+                    InputEvent::Char(ref cb) => {
+                        te_state
+                            .txt
+                            .insert_str_at_char_idx(cursor_pos as usize, cb.as_str());
+                        cursor_pos += 1;
+                        ws.invalidate(wgt.id);
+                        key_handled = true;
+                    }
+                    InputEvent::Paste(ref text) => {
+                        let text = text_edit_paste_filter(text);
+                        te_state
+                            .txt
+                            .insert_str_at_char_idx(cursor_pos as usize, &text);
+                        cursor_pos += text.chars().count() as isize;
+                        ws.invalidate(wgt.id);
+                        key_handled = true;
+                    }
+                    _ => {}
+                },
             }
         }
-        else if let InputEvent::Char(ref cb) = ii.evnt {
-            te_state
-                .txt
-                .insert_str_at_char_idx(cursor_pos as usize, cb.as_str());
-            cursor_pos += 1;
-            ws.invalidate(wgt.id);
-            key_handled = true;
+
+        if te_state.txt != prev_txt {
+            match validator {
+                Some(validator) if !validator.allows_input(&te_state.txt) => {
+                    // reject the key
+                    cursor_pos = te_state.cursor_pos as isize;
+                    te_state.txt.clone_from(&prev_txt);
+                    ring_bell();
+                }
+                Some(validator) => {
+                    te_state.error = validator.validate(&te_state.txt).err();
+                    ws.on_text_edit_validate(wgt, te_state.error);
+                }
+                // clear the error of refused incomplete mask
+                None => te_state.error = None,
+            }
+        }
+
+        if act != Some(Action::Undo) && act != Some(Action::Redo) {
+            let typing = matches!(ii.evnt, InputEvent::Char(_));
+
+            if te_state.txt != prev_txt {
+                // consecutive typing is undone at once, unless it replaced the selection
+                if !(typing && te_state.typing && selection.is_none()) {
+                    let pos = te_state.cursor_pos;
+                    te_state.push_undo(prev_txt, pos);
+                }
+                te_state.typing = typing;
+            }
+            else {
+                te_state.typing = false;
+            }
         }
 
         te_state.cursor_pos = cursor_pos as i16;
     }
-    else if let InputEvent::Key(ref key) = ii.evnt {
-        if *key == Key::Enter {
-            // enter edit mode
-            te_state.wgt_id = wgt.id;
-            te_state.txt.clear();
-            ws.get_text_edit_text(wgt, &mut te_state.txt, true);
+    else if act == Some(Action::Copy) {
+        if is_psw_masked {
+            ring_bell();
+        }
+        else {
+            let mut txt = String::new();
+            ws.get_text_edit_text(wgt, &mut txt, false);
+            clipboard_set(&txt);
+        }
+        key_handled = true;
+    }
+    else if act == Some(Action::Activate) {
+        // enter edit mode
+        te_state.wgt_id = wgt.id;
+        te_state.sel_anchor = None;
+        te_state.reset_history();
+        te_state.txt.clear();
+        ws.get_text_edit_text(wgt, &mut te_state.txt, true);
+        te_state.cursor_pos = te_state.txt.chars().count() as i16;
+
+        if let Some(ref mask) = mask {
+            te_state.txt = mask.apply(&te_state.txt);
+            te_state.cursor_pos = mask.first_empty(&te_state.txt) as i16;
+        }
+
+        te_state.error = validator.and_then(|v| v.validate(&te_state.txt).err());
+        ws.invalidate(wgt.id);
+        key_handled = true;
+    }
+    else if let InputEvent::Paste(ref text) = ii.evnt {
+        // enter edit mode, appending the pasted text
+        te_state.wgt_id = wgt.id;
+        te_state.sel_anchor = None;
+        te_state.reset_history();
+        te_state.txt.clear();
+        ws.get_text_edit_text(wgt, &mut te_state.txt, true);
+
+        if let Some(ref mask) = mask {
+            te_state.txt = mask.apply(&te_state.txt);
+            let mut pos = mask.first_empty(&te_state.txt) as isize;
+            // the paste may be undone
+            te_state.push_undo(te_state.txt.clone(), pos as i16);
+            process_key_masked_edit(mask, ii, act, &mut te_state.txt, &mut pos);
+            te_state.cursor_pos = pos as i16;
+        }
+        else {
+            let pos = te_state.txt.chars().count() as i16;
+            te_state.push_undo(te_state.txt.clone(), pos);
+            te_state.txt.push_str(&text_edit_paste_filter(text));
             te_state.cursor_pos = te_state.txt.chars().count() as i16;
-            ws.invalidate(wgt.id);
-            key_handled = true;
         }
+
+        if let Some(validator) = validator {
+            if !validator.allows_input(&te_state.txt) {
+                // reject the paste, edit the original text
+                if let Some((txt, pos)) = te_state.undo.pop_back() {
+                    te_state.txt = txt;
+                    te_state.cursor_pos = pos;
+                }
+                ring_bell();
+            }
+            te_state.error = validator.validate(&te_state.txt).err();
+            ws.on_text_edit_validate(wgt, te_state.error);
+        }
+        ws.invalidate(wgt.id);
+        key_handled = true;
     }
 
-    WGT_STATE.try_lock().unwrap().text_edit_state = te_state.clone();
+    WGT_STATE.try_lock().unwrap().text_edit_state = te_state;
     key_handled
 }
 
-fn process_key_check_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
-    if let InputEvent::Char(ref cb) = ii.evnt {
-        if ii.kmod.is_empty() && cb.utf8seq[0] == b' ' {
-            ws.on_checkbox_toggle(wgt);
-            ws.invalidate(wgt.id);
-            return true;
-        }
+/// Edits the text formatted with the input mask: typed characters overwrite the placeholders,
+/// the cursor skips the literals; returns true if the key was handled
+fn process_key_masked_edit(
+    mask: &InputMask,
+    ii: &InputInfo,
+    act: Option<Action>,
+    txt: &mut String,
+    cursor_pos: &mut isize,
+) -> bool {
+    let pos = (*cursor_pos).max(0) as usize;
+
+    match act {
+        // no indentation in the masked text
+        Some(Action::FocusNext) | Some(Action::FocusPrev) => {}
+        Some(Action::DeleteBack) => {
+            if let Some(prev) = mask.prev_pos(pos) {
+                mask.clear(txt, prev, prev + 1);
+                *cursor_pos = prev as isize;
+            }
+        }
+        Some(Action::DeleteToStart) => {
+            mask.clear(txt, 0, pos);
+            *cursor_pos = mask.next_pos(0) as isize;
+        }
+        Some(Action::DeleteForward) => mask.clear(txt, pos, pos + 1),
+        Some(Action::DeleteToEnd) => mask.clear(txt, pos, mask.len()),
+        Some(Action::CursorLeft) => {
+            if let Some(prev) = mask.prev_pos(pos) {
+                *cursor_pos = prev as isize;
+            }
+        }
+        Some(Action::CursorRight) => *cursor_pos = mask.next_pos(pos + 1) as isize,
+        Some(Action::CursorHome) => *cursor_pos = mask.next_pos(0) as isize,
+        Some(Action::CursorEnd) => *cursor_pos = mask.len() as isize,
+        _ => match ii.evnt {
+            InputEvent::Char(ref cb) => {
+                let c = cb.as_char();
+
+                if let Some(next) = mask.put_char(txt, pos, c) {
+                    *cursor_pos = next as isize;
+                }
+                else if !mask.is_literal(c) {
+                    // typed literals are silently skipped
+                    ring_bell();
+                }
+            }
+            InputEvent::Paste(ref text) => {
+                let mut pos = pos;
+                for c in text.chars() {
+                    if let Some(next) = mask.put_char(txt, pos, c) {
+                        pos = next;
+                    }
+                }
+                *cursor_pos = pos as isize;
+            }
+            _ => return false,
+        },
     }
 
-    if let InputEvent::Key(ref key) = ii.evnt {
-        if *key == Key::Enter {
-            ws.on_checkbox_toggle(wgt);
-            ws.invalidate(wgt.id);
-            return true;
-        }
+    true
+}
+
+/// Erases the `from..to` characters range; with the input mask, the placeholders are cleared
+fn text_edit_erase(txt: &mut String, mask: Option<&InputMask>, from: usize, to: usize) {
+    match mask {
+        Some(mask) => mask.clear(txt, from, to),
+        None => txt.erase_char_range(from, to - from),
     }
+}
 
-    false
+/// Signals the rejected input
+pub(crate) fn ring_bell() {
+    if let Some(mut term_guard) = TERM.try_lock() {
+        term_guard.write_str(esc::BELL);
+        term_guard.flush_buff();
+    }
 }
 
-fn process_key_radio(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
-    if let InputEvent::Char(ref cb) = ii.evnt {
-        if ii.kmod.is_empty() && cb.utf8seq[0] == b' ' {
-            ws.on_radio_select(wgt);
-            invalidate_radio_group(ws, wgt);
-            return true;
-        }
+/// Makes the pasted text a single line: trailing line breaks are removed,
+/// tabs and inner line breaks are replaced with spaces, other control characters are dropped
+fn text_edit_paste_filter(text: &str) -> String {
+    text.trim_end_matches(['\r', '\n'])
+        .chars()
+        .filter(|c| *c != '\r')
+        .map(|c| tetrary!(c == '\n' || c == '\t', ' ', c))
+        .filter(|c| !c.is_control())
+        .collect()
+}
+
+fn process_key_check_box(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
+    if act == Some(Action::Toggle) || act == Some(Action::Activate) {
+        ws.on_checkbox_toggle(wgt);
+        ws.invalidate(wgt.id);
+        return true;
     }
 
-    if let InputEvent::Key(ref key) = ii.evnt {
-        if *key == Key::Enter {
-            ws.on_radio_select(wgt);
-            invalidate_radio_group(ws, wgt);
-            return true;
-        }
+    false
+}
+
+fn process_key_radio(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
+    if act == Some(Action::Toggle) || act == Some(Action::Activate) {
+        ws.on_radio_select(wgt);
+        invalidate_radio_group(ws, wgt);
+        return true;
     }
 
     false
 }
 
-fn process_key_button(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
+fn process_key_button(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
     if ws.on_button_key(wgt, ii) {
         // user handled the keyboard event
         return true;
     }
 
-    if let InputEvent::Key(ref key) = ii.evnt {
-        if *key == Key::Enter {
-            // pointer may change between onButtonUp and onButtonClick, so remember it
-            WGT_STATE.try_lock().unwrap().mouse_down_wgt = wgt.id;
-            ws.on_button_down(wgt, ii);
-            ws.instant_redraw(wgt.id);
-            if let Some(term_guard) = TERM.try_lock() {
-                term_guard.pal.sleep(200);
-            }
-            WGT_STATE.try_lock().unwrap().mouse_down_wgt = WIDGET_ID_NONE;
-            ws.on_button_up(wgt, ii);
-            ws.on_button_click(wgt, ii);
-            ws.invalidate(wgt.id);
-            return true;
+    if act == Some(Action::Activate) {
+        // pointer may change between onButtonUp and onButtonClick, so remember it
+        WGT_STATE.try_lock().unwrap().mouse_down_wgt = wgt.id;
+        ws.on_button_down(wgt, ii);
+        ws.instant_redraw(wgt.id);
+        if let Some(term_guard) = TERM.try_lock() {
+            term_guard.pal.sleep(200);
         }
+        WGT_STATE.try_lock().unwrap().mouse_down_wgt = WIDGET_ID_NONE;
+        ws.on_button_up(wgt, ii);
+        ws.on_button_click(wgt, ii);
+        ws.invalidate(wgt.id);
+        return true;
     }
 
     false
 }
 
-fn process_key_page_ctrl(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
-    if let InputEvent::Key(ref key) = ii.evnt {
-        match *key {
-            Key::PgDown | Key::PgUp | Key::F11 | Key::F12 => {
-                pagectrl_change_page(ws, wgt, *key == Key::PgDown || *key == Key::F12);
-                return true;
-            }
-            _ => {}
+fn process_key_page_ctrl(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
+    match act {
+        Some(Action::PageUp) | Some(Action::PrevPage) => {
+            pagectrl_change_page(ws, wgt, false);
+            true
         }
+        Some(Action::PageDown) | Some(Action::NextPage) => {
+            pagectrl_change_page(ws, wgt, true);
+            true
+        }
+        _ => false,
     }
-
-    false
 }
 
-fn process_key_list_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
+fn process_key_list_box(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
     let mut delta = 0;
 
-    if let InputEvent::Key(ref key) = ii.evnt {
+    if let Some(act) = act {
         let items_visible = wgt.size.height as i16 - 2;
 
-        match *key {
-            Key::Enter => {
+        match act {
+            Action::Activate => {
                 let mut lbs = Default::default();
                 ws.get_list_box_state(wgt, &mut lbs);
 
@@ -1211,18 +1769,10 @@ fn process_key_list_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
 
                 return true;
             }
-            Key::Up => {
-                delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, -1, 0);
-            }
-            Key::Down => {
-                delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, 1, 0);
-            }
-            Key::PgUp => {
-                delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, -items_visible, 0);
-            }
-            Key::PgDown => {
-                delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, items_visible, 0);
-            }
+            Action::LineUp => delta = -1,
+            Action::LineDown => delta = 1,
+            Action::PageUp => delta = -items_visible,
+            Action::PageDown => delta = items_visible,
             _ => {}
         }
     }
@@ -1252,13 +1802,18 @@ fn process_key_list_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
     false
 }
 
-fn process_key_combo_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
+fn process_key_combo_box(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
     let mut cbs = Default::default();
     ws.get_combo_box_state(wgt, &mut cbs);
     let mut input_handled = false;
 
-    if let InputEvent::Char(ref cb) = ii.evnt {
-        if cb.utf8seq[0] == b' ' && cbs.items_cnt > 0 {
+    if act == Some(Action::Toggle) {
+        if cbs.items_cnt > 0 {
             cbs.drop_down = !cbs.drop_down;
 
             if cbs.drop_down {
@@ -1272,29 +1827,29 @@ fn process_key_combo_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
             input_handled = true;
         }
     }
-    else if let InputEvent::Key(ref key) = ii.evnt {
-        if *key == Key::Esc {
+    else if let Some(act) = act {
+        if act == Action::Cancel {
             hide_combo_box_dropdown_list(ws, wgt);
             input_handled = true;
         }
         else if cbs.drop_down {
             input_handled = true;
 
-            if *key == Key::Up {
+            if act == Action::LineUp {
                 cbs.sel_idx -= 1;
                 if cbs.sel_idx < 0 {
                     cbs.sel_idx = cbs.items_cnt - 1;
                 }
                 ws.on_combo_box_select(wgt, cbs.sel_idx);
             }
-            else if *key == Key::Down {
+            else if act == Action::LineDown {
                 cbs.sel_idx += 1;
                 if cbs.sel_idx >= cbs.items_cnt {
                     cbs.sel_idx = 0;
                 }
                 ws.on_combo_box_select(wgt, cbs.sel_idx);
             }
-            else if *key == Key::PgUp && ii.kmod.mask == KEY_MOD_SPECIAL {
+            else if act == Action::PageUp {
                 if let Property::ComboBox(ref prop) = wgt.prop {
                     cbs.sel_idx -= prop.drop_down_size as i16;
                 }
@@ -1304,7 +1859,7 @@ fn process_key_combo_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
                 }
                 ws.on_combo_box_select(wgt, cbs.sel_idx);
             }
-            else if *key == Key::PgDown && ii.kmod.mask == KEY_MOD_SPECIAL {
+            else if act == Action::PageDown {
                 if let Property::ComboBox(ref prop) = wgt.prop {
                     cbs.sel_idx += prop.drop_down_size as i16;
                 }
@@ -1314,7 +1869,7 @@ fn process_key_combo_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
                 }
                 ws.on_combo_box_select(wgt, cbs.sel_idx);
             }
-            else if *key == Key::Enter {
+            else if act == Action::Activate {
                 ws.on_combo_box_change(wgt, cbs.sel_idx);
                 hide_combo_box_dropdown_list(ws, wgt);
             }
@@ -1331,22 +1886,111 @@ fn process_key_combo_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
     input_handled
 }
 
-fn process_key_text_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo) -> bool {
-    if let InputEvent::Key(ref key) = ii.evnt {
+fn process_key_text_box(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
+    if WGT_STATE
+        .try_lock()
+        .unwrap()
+        .text_box_search
+        .is_prompt(wgt.id)
+    {
+        return process_key_text_box_search(ws, wgt, ii, act);
+    }
+
+    match act {
+        Some(Action::Find) => {
+            WGT_STATE.try_lock().unwrap().text_box_search = TextBoxSearch {
+                wgt_id: wgt.id,
+                pattern: String::new(),
+                prompt: true,
+                line: -1,
+            };
+            ws.invalidate(wgt.id);
+            return true;
+        }
+        Some(Action::FindNext) | Some(Action::FindPrev) => {
+            // n/N keys are free to use if nothing is searched
+            let search = WGT_STATE.try_lock().unwrap().text_box_search.clone();
+
+            if search.pattern(wgt.id).is_some() {
+                text_box_find(ws, wgt, tetrary!(act == Some(Action::FindNext), 1, -1));
+                return true;
+            }
+        }
+        Some(Action::Copy) => {
+            let mut tbs = Default::default();
+            let src_lines = get_text_box_state_wrapped(ws, wgt, &mut tbs);
+            let mut text = String::new();
+            {
+                let lines = tbs.lines.borrow();
+                let sel = WGT_STATE.try_lock().unwrap().text_box_sel;
+                let (from, to) = sel.lines(wgt.id).unwrap_or((0, lines.len() as i16 - 1));
+
+                for idx in from.max(0) as usize..(to + 1).max(0) as usize {
+                    if let Some(line) = lines.get(idx) {
+                        // wrapped parts of the same line are joined back
+                        if idx > from as usize
+                            && (src_lines.is_empty() || src_lines[idx] != src_lines[idx - 1])
+                        {
+                            text.push('\n');
+                        }
+
+                        text.push_str(&line.strip_esc());
+                    }
+                }
+            }
+
+            clipboard_set(&text);
+            return true;
+        }
+        Some(Action::Cancel) => {
+            let mut wgtstate_guard = WGT_STATE.try_lock().unwrap();
+
+            if wgtstate_guard.text_box_sel.lines(wgt.id).is_some() {
+                wgtstate_guard.text_box_sel.wgt_id = WIDGET_ID_NONE;
+                drop(wgtstate_guard);
+                ws.invalidate(wgt.id);
+                return true;
+            }
+
+            if wgtstate_guard.text_box_search.pattern(wgt.id).is_some() {
+                // hide the matches
+                wgtstate_guard.text_box_search = Default::default();
+                drop(wgtstate_guard);
+                ws.invalidate(wgt.id);
+                return true;
+            }
+        }
+        Some(Action::SelectUp)
+        | Some(Action::SelectDown)
+        | Some(Action::SelectHome)
+        | Some(Action::SelectEnd)
+        | Some(Action::SelectAll) => {
+            text_box_select(ws, wgt, act.unwrap());
+            return true;
+        }
+        _ => {}
+    }
+
+    if let Some(act) = act {
         let mut delta = 0;
         let lines_visible = wgt.size.height as i16 - 2;
 
-        match *key {
-            Key::Up => delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, -1, 0),
-            Key::Down => delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, 1, 0),
-            Key::PgUp => delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, -lines_visible, 0),
-            Key::PgDown => delta = tetrary!(ii.kmod.mask == KEY_MOD_SPECIAL, lines_visible, 0),
+        match act {
+            Action::LineUp => delta = -1,
+            Action::LineDown => delta = 1,
+            Action::PageUp => delta = -lines_visible,
+            Action::PageDown => delta = lines_visible,
             _ => {}
         }
 
         if delta != 0 {
             let mut tbs = Default::default();
-            ws.get_text_box_state(wgt, &mut tbs);
+            get_text_box_state_wrapped(ws, wgt, &mut tbs);
 
             let lines_len = tbs.lines.borrow().len() as i16;
             tbs.top_line += delta;
@@ -1363,11 +2007,242 @@ fn process_key_text_box(ws: &mut dyn WindowState, wgt: &Widget, ii: &InputInfo)
             ws.invalidate(wgt.id);
             return true;
         }
+
+        let cols_delta = match act {
+            Action::CursorLeft => -1,
+            Action::CursorRight => 1,
+            _ => 0,
+        };
+
+        if cols_delta != 0 && text_box_scroll_h(ws, wgt, cols_delta) {
+            return true;
+        }
     }
 
     false
 }
 
+/// Scrolls the TextBox lines horizontally; returns false if the lines fit the widget
+fn text_box_scroll_h(ws: &mut dyn WindowState, wgt: &Widget, delta: i16) -> bool {
+    let mut tbs = Default::default();
+    get_text_box_state_wrapped(ws, wgt, &mut tbs);
+    let cols_max = text_box_cols_max(wgt, &tbs.lines.borrow());
+
+    if cols_max <= 0 {
+        return false;
+    }
+
+    let left_col = (tbs.left_col + delta).clamp(0, cols_max);
+
+    if left_col != tbs.left_col {
+        ws.on_text_box_scroll_h(wgt, left_col);
+        ws.invalidate(wgt.id);
+    }
+
+    true
+}
+
+/// Edits the TextBox search prompt; the matches are searched while typing
+fn process_key_text_box_search(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    ii: &InputInfo,
+    act: Option<Action>,
+) -> bool {
+    let mut search = WGT_STATE.try_lock().unwrap().text_box_search.clone();
+    let mut changed = false;
+
+    match ii.evnt {
+        InputEvent::Char(ref cb) if !ii.kmod.has_ctrl() && !ii.kmod.has_alt() => {
+            search.pattern.push_str(cb.as_str());
+            changed = true;
+        }
+        InputEvent::Paste(ref text) => {
+            search
+                .pattern
+                .push_str(text.lines().next().unwrap_or_default());
+            changed = true;
+        }
+        _ => match act {
+            Some(Action::DeleteBack) => {
+                changed = search.pattern.pop().is_some();
+            }
+            Some(Action::Activate) => {
+                // matches stay highlighted
+                search.prompt = false;
+                if search.pattern.is_empty() {
+                    search = Default::default();
+                }
+            }
+            Some(Action::Cancel) => {
+                search = Default::default();
+            }
+            Some(Action::FindNext) | Some(Action::FindPrev) => {
+                search.prompt = false;
+                if search.pattern.is_empty() {
+                    search = Default::default();
+                }
+                else {
+                    WGT_STATE.try_lock().unwrap().text_box_search = search;
+                    text_box_find(ws, wgt, tetrary!(act == Some(Action::FindNext), 1, -1));
+                    return true;
+                }
+            }
+            _ => {}
+        },
+    }
+
+    if search.pattern.is_empty() {
+        search.line = -1;
+    }
+
+    let find = changed && !search.pattern.is_empty();
+    WGT_STATE.try_lock().unwrap().text_box_search = search;
+
+    if find {
+        // the current match is kept while the pattern is extended
+        text_box_find(ws, wgt, 0);
+    }
+
+    ws.invalidate(wgt.id);
+    // the prompt takes all the keys
+    true
+}
+
+/// Finds the line with the searched text, `step` lines from the current match
+/// (or from the top line if none), wrapping around; scrolls the found line into view
+fn text_box_find(ws: &mut dyn WindowState, wgt: &Widget, step: i16) {
+    let lines_visible = wgt.size.height as i16 - 2;
+    let mut tbs = Default::default();
+    get_text_box_state_wrapped(ws, wgt, &mut tbs);
+
+    let found = {
+        let wgtstate_guard = WGT_STATE.try_lock().unwrap();
+        let search = &wgtstate_guard.text_box_search;
+        let lines = tbs.lines.borrow();
+        let lines_len = lines.len() as i16;
+        let from_line = if search.line >= 0 {
+            search.line + step
+        }
+        else {
+            tbs.top_line
+        };
+
+        (0..lines_len)
+            .map(|n| (from_line + tetrary!(step >= 0, n, -n)).rem_euclid(lines_len))
+            .find(|&idx| !text_box_matches(&lines[idx as usize], &search.pattern).is_empty())
+            .map(|idx| (idx, lines_len))
+    };
+
+    WGT_STATE.try_lock().unwrap().text_box_search.line = found.map_or(-1, |(idx, _)| idx);
+
+    if let Some((line, lines_len)) = found {
+        if line < tbs.top_line || line >= tbs.top_line + lines_visible {
+            let top_line = line.min(lines_len - lines_visible).max(0);
+            ws.on_text_box_scroll(wgt, top_line);
+        }
+    }
+    else if step != 0 {
+        ring_bell();
+    }
+
+    ws.invalidate(wgt.id);
+}
+
+/// Returns the character index ranges of the searched text in the line, ignoring ESC sequences;
+/// letters case is ignored
+pub(crate) fn text_box_matches(line: &str, pattern: &str) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let text: Vec<char> = line
+        .strip_esc()
+        .chars()
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+    if pattern.is_empty() {
+        return matches;
+    }
+
+    let mut idx = 0;
+    while idx + pattern.len() <= text.len() {
+        if text[idx..idx + pattern.len()] == pattern[..] {
+            matches.push((idx, idx + pattern.len()));
+            idx += pattern.len();
+        }
+        else {
+            idx += 1;
+        }
+    }
+
+    matches
+}
+
+/// Extends the TextBox selected lines; scrolls to keep the extended end visible
+fn text_box_select(ws: &mut dyn WindowState, wgt: &Widget, act: Action) {
+    let lines_visible = wgt.size.height as i16 - 2;
+    let mut tbs = Default::default();
+    get_text_box_state_wrapped(ws, wgt, &mut tbs);
+    let lines_len = tbs.lines.borrow().len() as i16;
+
+    if lines_len == 0 || lines_visible <= 0 {
+        return;
+    }
+
+    let mut sel = WGT_STATE.try_lock().unwrap().text_box_sel;
+
+    if sel.lines(wgt.id).is_none() {
+        // start at the first or the last visible line
+        let line = tetrary!(
+            act == Action::SelectUp || act == Action::SelectHome,
+            tbs.top_line + lines_visible - 1,
+            tbs.top_line
+        )
+        .clamp(0, lines_len - 1);
+
+        sel = TextBoxSelection {
+            wgt_id: wgt.id,
+            anchor: line,
+            cursor: line,
+        };
+    }
+    else {
+        sel.cursor += match act {
+            Action::SelectUp => -1,
+            Action::SelectDown => 1,
+            _ => 0,
+        };
+    }
+
+    match act {
+        Action::SelectHome => sel.cursor = 0,
+        Action::SelectEnd => sel.cursor = lines_len - 1,
+        Action::SelectAll => {
+            sel.anchor = 0;
+            sel.cursor = lines_len - 1;
+        }
+        _ => {}
+    }
+    sel.cursor = sel.cursor.clamp(0, lines_len - 1);
+
+    let top_line = if sel.cursor < tbs.top_line {
+        sel.cursor
+    }
+    else if sel.cursor >= tbs.top_line + lines_visible {
+        sel.cursor - lines_visible + 1
+    }
+    else {
+        tbs.top_line
+    };
+
+    if top_line != tbs.top_line {
+        ws.on_text_box_scroll(wgt, top_line);
+    }
+
+    WGT_STATE.try_lock().unwrap().text_box_sel = sel;
+    ws.invalidate(wgt.id);
+}
+
 // ---------------------------------------------------------------------------------------------- //
 // ---- WIDGETS MOUSE PROCESSING FUNCTIONS ------------------------------------------------------ //
 // ---------------------------------------------------------------------------------------------- //
@@ -1387,9 +2262,39 @@ fn process_mouse(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
             }
         }
 
+        if let MouseEvent::Drag(_) = mouse.evt {
+            return process_mouse_drag(ws, ii);
+        }
+
+        let mouse_coord = match mouse.coord() {
+            Some(coord) => coord,
+            None => {
+                if mouse.evt == MouseEvent::ButtonReleased {
+                    // released beyond the widgets, eg. after dragging
+                    process_mouse_release_outside(ws, ii);
+                    return true;
+                }
+                return false;
+            }
+        };
+
         let mut rct = Rect::cdeflt();
 
-        if let Some(mut wgt) = find_at(ws, mouse.col, mouse.row, &mut rct) {
+        if mouse.evt == MouseEvent::Move {
+            // only custom widgets are interested in plain mouse motion
+            if let Some(wgt) = find_at(ws, mouse_coord.col, mouse_coord.row, &mut rct) {
+                if let Property::CustomWgt(_) = wgt.prop {
+                    if is_enabled(ws, wgt) {
+                        process_mouse_custom_wgt(ws, wgt, &rct, ii);
+                        return true;
+                    }
+                }
+            }
+
+            return false;
+        }
+
+        if let Some(mut wgt) = find_at(ws, mouse_coord.col, mouse_coord.row, &mut rct) {
             let ret = {
                 let mouse_down_wgt = WGT_STATE.try_lock().unwrap().mouse_down_wgt;
 
@@ -1428,7 +2333,7 @@ fn process_mouse(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
                     dropdownlist_rct.size.width = cbx.size.width;
                     dropdownlist_rct.size.height = prop.drop_down_size + 1;
 
-                    if dropdownlist_rct.is_point_within(mouse.col, mouse.row) {
+                    if dropdownlist_rct.is_point_within(mouse_coord.col, mouse_coord.row) {
                         // yes -> replace data for processing with g_ds.pDropDownCombo
                         wgt = cbx;
                         rct.coord = get_screen_coord(ws, wgt);
@@ -1467,11 +2372,54 @@ fn process_mouse(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
                 WGT_STATE.try_lock().unwrap().mouse_down_wgt = WIDGET_ID_NONE;
             }
         }
+        else if mouse.evt == MouseEvent::ButtonReleased {
+            // released outside of any widget, eg. after dragging
+            process_mouse_release_outside(ws, ii);
+        }
     }
 
     true
 }
 
+/// Button released outside of any widget
+fn process_mouse_release_outside(ws: &mut dyn WindowState, ii: &InputInfo) {
+    let mouse_down_wgt = WGT_STATE.try_lock().unwrap().mouse_down_wgt;
+
+    if let Some(md_wgt) = find_by_id(ws.get_widgets(), mouse_down_wgt) {
+        if let Property::Button(_) = md_wgt.prop {
+            process_mouse_button_release(ws, md_wgt, ii);
+        }
+    }
+
+    WGT_STATE.try_lock().unwrap().mouse_down_wgt = WIDGET_ID_NONE;
+}
+
+/// Motion with the button pressed is given to the widget that received the button press
+fn process_mouse_drag(ws: &mut dyn WindowState, ii: &InputInfo) -> bool {
+    let mouse_down_wgt = WGT_STATE.try_lock().unwrap().mouse_down_wgt;
+
+    if let Some(wgt) = find_by_id(ws.get_widgets(), mouse_down_wgt) {
+        if is_enabled(ws, wgt) {
+            let rct = Rect {
+                coord: get_screen_coord(ws, wgt),
+                size: wgt.size,
+            };
+
+            match wgt.prop {
+                Property::TextEdit(_) => process_mouse_text_edit_drag(ws, wgt, &rct, ii),
+                Property::ListBox(_) => process_mouse_list_box_drag(ws, wgt, &rct, ii),
+                Property::CustomWgt(_) => process_mouse_custom_wgt(ws, wgt, &rct, ii),
+                Property::TextBox(_) => process_mouse_text_box_drag(ws, wgt, &rct, ii),
+                _ => return false,
+            }
+
+            return true;
+        }
+    }
+
+    false
+}
+
 fn process_mouse_text_edit(
     ws: &mut dyn WindowState,
     wgt: &Widget,
@@ -1481,6 +2429,259 @@ fn process_mouse_text_edit(
     if let InputEvent::Mouse(ref mouse) = ii.evnt {
         if mouse.evt == MouseEvent::ButtonLeft {
             change_focus_to(ws, wgt.id);
+            let double_click = is_double_click(mouse);
+            let is_psw_masked = matches!(wgt.prop, Property::TextEdit(ref p) if p.psw_mask);
+            let edited_id = WGT_STATE.try_lock().unwrap().text_edit_state.wgt_id;
+
+            let entered = double_click && edited_id != wgt.id;
+
+            if entered {
+                // enter edit mode
+                process_key_text_edit(ws, wgt, &InputInfo::default(), Some(Action::Activate));
+            }
+
+            {
+                let mut wgtstate_guard = WGT_STATE.try_lock().unwrap();
+                let te_state = &mut wgtstate_guard.text_edit_state;
+
+                if te_state.wgt_id != wgt.id || is_psw_masked {
+                    return;
+                }
+
+                // the text was displayed from the beginning before entering the edit mode
+                let display_pos =
+                    tetrary!(entered, 0, text_edit_display_pos(wgt, te_state.cursor_pos));
+                let pos = text_edit_pos_at(wgt_rect, te_state, display_pos, mouse.col);
+
+                if double_click {
+                    // select the word
+                    let (from, to) = word_at(&te_state.txt, pos as usize);
+                    te_state.sel_anchor = Some(from as i16);
+                    te_state.cursor_pos = to as i16;
+                }
+                else {
+                    te_state.sel_anchor = None;
+                    te_state.cursor_pos = pos;
+                }
+                // guard dropped
+            }
+
+            ws.invalidate(wgt.id);
+        }
+    }
+}
+
+/// Selects the text from the mouse press to the mouse pointer
+fn process_mouse_text_edit_drag(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    wgt_rect: &Rect,
+    ii: &InputInfo,
+) {
+    if let InputEvent::Mouse(ref mouse) = ii.evnt {
+        let is_psw_masked = matches!(wgt.prop, Property::TextEdit(ref p) if p.psw_mask);
+
+        {
+            let mut wgtstate_guard = WGT_STATE.try_lock().unwrap();
+            let te_state = &mut wgtstate_guard.text_edit_state;
+
+            if te_state.wgt_id != wgt.id || is_psw_masked {
+                return;
+            }
+
+            let display_pos = text_edit_display_pos(wgt, te_state.cursor_pos);
+            let pos = text_edit_pos_at(wgt_rect, te_state, display_pos, mouse.col);
+            te_state.sel_anchor.get_or_insert(te_state.cursor_pos);
+            te_state.cursor_pos = pos;
+            // guard dropped
+        }
+
+        ws.invalidate(wgt.id);
+    }
+}
+
+/// Returns the text position at the screen column of the edited TextEdit
+fn text_edit_pos_at(wgt_rect: &Rect, te_state: &TextEditState, display_pos: i16, col: u16) -> i16 {
+    let pos = display_pos + col as i16 - wgt_rect.coord.col as i16;
+    pos.clamp(0, te_state.txt.chars().count() as i16)
+}
+
+/// Gets the TextBox state, with the lines wrapped to the widget width if `word_wrap` is set
+/// and converted from the markup if `markup` is set;
+/// returns the source line index of each returned line, or empty if the lines are unchanged
+pub(crate) fn get_text_box_state_wrapped(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    out: &mut rstate::TxtbxState,
+) -> Arc<Vec<usize>> {
+    ws.get_text_box_state(wgt, out);
+    let mut src_lines = Arc::default();
+
+    if let Property::TextBox(ref p) = wgt.prop {
+        if p.word_wrap || p.markup {
+            let max_w = if p.word_wrap {
+                (wgt.size.width as usize).saturating_sub(2).max(1)
+            }
+            else {
+                usize::MAX
+            };
+            // markup styles are restored to the colors the widget is drawn with
+            let colors = (
+                crate::widget_draw::get_widget_fg_color(wgt),
+                crate::widget_draw::get_widget_bg_color(wgt),
+            );
+            let src_len = out.lines.borrow().len();
+
+            let cached = WGT_STATE.try_lock().and_then(|wgtstate_guard| {
+                wgtstate_guard
+                    .text_box_wrap
+                    .iter()
+                    .find(|c| {
+                        c.wgt_id == wgt.id
+                            && c.max_w == max_w
+                            && c.colors == colors
+                            && Arc::ptr_eq(&c.src, &out.lines)
+                            && c.src_len == src_len
+                            && c.generation == out.generation
+                    })
+                    .map(|c| (Arc::clone(&c.wrapped), Arc::clone(&c.src_lines)))
+            });
+
+            if let Some((wrapped, lines_idx)) = cached {
+                out.lines = wrapped;
+                src_lines = lines_idx;
+            }
+            else {
+                let wrapped = utils::StringListRc::default();
+                let mut lines_idx = Vec::new();
+
+                for (idx, line) in out.lines.borrow().iter().enumerate() {
+                    let parts = if p.markup {
+                        markup::word_wrap(max_w, line, colors.0, colors.1)
+                    }
+                    else {
+                        utils::word_wrap(max_w, line)
+                    };
+                    let mut parts = parts.borrow_mut();
+
+                    if parts.is_empty() {
+                        parts.push(String::new());
+                    }
+
+                    lines_idx.resize(lines_idx.len() + parts.len(), idx);
+                    wrapped.borrow_mut().append(&mut parts);
+                }
+
+                src_lines = Arc::new(lines_idx);
+
+                if let Some(mut wgtstate_guard) = WGT_STATE.try_lock() {
+                    let cache = TextBoxWrapCache {
+                        wgt_id: wgt.id,
+                        max_w,
+                        colors,
+                        src: Arc::clone(&out.lines),
+                        src_len,
+                        generation: out.generation,
+                        wrapped: Arc::clone(&wrapped),
+                        src_lines: Arc::clone(&src_lines),
+                    };
+                    let caches = &mut wgtstate_guard.text_box_wrap;
+
+                    match caches.iter_mut().find(|c| c.wgt_id == wgt.id) {
+                        Some(c) => *c = cache,
+                        None => caches.push(cache),
+                    }
+                }
+
+                out.lines = wrapped;
+            }
+
+            if p.word_wrap {
+                out.left_col = 0;
+            }
+        }
+    }
+
+    src_lines
+}
+
+/// Returns the maximum TextBox horizontal scroll, 0 if the lines fit the widget or are wrapped
+pub(crate) fn text_box_cols_max(wgt: &Widget, lines: &[String]) -> i16 {
+    if let Property::TextBox(ref p) = wgt.prop {
+        if p.word_wrap {
+            return 0;
+        }
+    }
+
+    let cols_visible = wgt.size.width as i16 - 2;
+    let cols = lines
+        .iter()
+        .map(|line| line.displayed_width())
+        .max()
+        .unwrap_or(0);
+
+    (cols as i16 - cols_visible).max(0)
+}
+
+/// Returns the first displayed character index of the edited TextEdit text,
+/// scrolled to keep the cursor visible
+pub(crate) fn text_edit_display_pos(wgt: &Widget, mut cursor_pos: i16) -> i16 {
+    let max_w = wgt.size.width as i16 - 3;
+    let delta = max_w / 2;
+    let mut display_pos = 0;
+
+    while delta > 0 && cursor_pos >= max_w - 1 {
+        cursor_pos -= delta;
+        display_pos += delta;
+    }
+
+    display_pos
+}
+
+/// Returns the characters range of the word, or of the non-word characters, at given position
+fn word_at(txt: &str, pos: usize) -> (usize, usize) {
+    let chars: Vec<char> = txt.chars().collect();
+
+    if chars.is_empty() {
+        return (0, 0);
+    }
+
+    let pos = pos.min(chars.len() - 1);
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    let word = is_word(&chars[pos]);
+    let from = chars[..pos]
+        .iter()
+        .rposition(|c| is_word(c) != word)
+        .map_or(0, |i| i + 1);
+    let to = chars[pos..]
+        .iter()
+        .position(|c| is_word(c) != word)
+        .map_or(chars.len(), |i| pos + i);
+    (from, to)
+}
+
+/// Checks if the click is the second one of the double-click, at the same position
+fn is_double_click(mouse: &MouseInfo) -> bool {
+    let now = TERM
+        .try_lock()
+        .map_or(0, |term| term.pal.get_timestamp_ms());
+    let coord = match mouse.coord() {
+        Some(coord) => coord,
+        None => return false,
+    };
+    let mut wgtstate_guard = WGT_STATE.try_lock().unwrap();
+
+    match wgtstate_guard.last_click {
+        Some((ts, click_coord))
+            if click_coord == coord && now.wrapping_sub(ts) <= DOUBLE_CLICK_MS =>
+        {
+            // the third click starts a new double-click
+            wgtstate_guard.last_click = None;
+            true
+        }
+        _ => {
+            wgtstate_guard.last_click = Some((now, coord));
+            false
         }
     }
 }
@@ -1637,6 +2838,103 @@ fn process_mouse_list_box(ws: &mut dyn WindowState, wgt: &Widget, wgt_rect: &Rec
     }
 }
 
+/// Moves the selection to the item under the cursor; scrolls if dragged above or below the list
+fn process_mouse_list_box_drag(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    wgt_rect: &Rect,
+    ii: &InputInfo,
+) {
+    if let InputEvent::Mouse(ref mouse) = ii.evnt {
+        let items_visible = wgt.size.height as i16 - 2;
+        let mut lbs = Default::default();
+        ws.get_list_box_state(wgt, &mut lbs);
+
+        if lbs.items_cnt <= 0 || items_visible <= 0 {
+            return;
+        }
+
+        let row = mouse.row as i16 - wgt_rect.coord.row as i16 - 1;
+        let new_selidx = if row < 0 {
+            lbs.sel_idx - 1
+        }
+        else if row >= items_visible {
+            lbs.sel_idx + 1
+        }
+        else {
+            (lbs.sel_idx / items_visible) * items_visible + row
+        }
+        .clamp(0, lbs.items_cnt - 1);
+
+        if new_selidx != lbs.sel_idx {
+            ws.on_list_box_select(wgt, new_selidx);
+            ws.invalidate(wgt.id);
+        }
+    }
+}
+
+/// Selects the lines from the mouse press to the mouse pointer; scrolls if dragged above or below the text
+fn process_mouse_text_box_drag(
+    ws: &mut dyn WindowState,
+    wgt: &Widget,
+    wgt_rect: &Rect,
+    ii: &InputInfo,
+) {
+    if let InputEvent::Mouse(ref mouse) = ii.evnt {
+        let lines_visible = wgt.size.height as i16 - 2;
+        let mut tbs = Default::default();
+        get_text_box_state_wrapped(ws, wgt, &mut tbs);
+        let lines_len = tbs.lines.borrow().len() as i16;
+
+        if lines_len == 0 || lines_visible <= 0 {
+            return;
+        }
+
+        let line_at = |top_line: i16, row: u16| {
+            let row = row as i16 - wgt_rect.coord.row as i16 - 1;
+            (top_line + row.clamp(0, lines_visible - 1)).clamp(0, lines_len - 1)
+        };
+
+        let row = mouse.row as i16 - wgt_rect.coord.row as i16 - 1;
+        let top_line = if row < 0 {
+            (tbs.top_line - 1).max(0)
+        }
+        else if row >= lines_visible {
+            (tbs.top_line + 1).min((lines_len - lines_visible).max(0))
+        }
+        else {
+            tbs.top_line
+        };
+
+        {
+            let mut wgtstate_guard = WGT_STATE.try_lock().unwrap();
+
+            if wgtstate_guard.text_box_sel.lines(wgt.id).is_none() {
+                // anchored at the line pressed
+                let anchor = match wgtstate_guard.mouse_down_ii.evnt {
+                    InputEvent::Mouse(ref md) => line_at(tbs.top_line, md.row),
+                    _ => line_at(tbs.top_line, mouse.row),
+                };
+
+                wgtstate_guard.text_box_sel = TextBoxSelection {
+                    wgt_id: wgt.id,
+                    anchor,
+                    cursor: anchor,
+                };
+            }
+
+            wgtstate_guard.text_box_sel.cursor = line_at(top_line, mouse.row);
+            // guard dropped
+        }
+
+        if top_line != tbs.top_line {
+            ws.on_text_box_scroll(wgt, top_line);
+        }
+
+        ws.invalidate(wgt.id);
+    }
+}
+
 fn process_mouse_combo_box(
     ws: &mut dyn WindowState,
     wgt: &Widget,
@@ -1720,6 +3018,7 @@ fn process_mouse_combo_box(
             let evnt_btn_left = InputInfo {
                 evnt: InputEvent::Mouse(MouseInfo {
                     evt: MouseEvent::ButtonLeft,
+                    btn: Some(MouseButton::Left),
                     col: mouse.col,
                     row: mouse.row,
                 }),
@@ -1754,9 +3053,36 @@ fn process_mouse_text_box(ws: &mut dyn WindowState, wgt: &Widget, wgt_rect: &Rec
     if let InputEvent::Mouse(ref mouse) = ii.evnt {
         change_focus_to(ws, wgt.id);
 
+        if mouse.evt == MouseEvent::ButtonLeft {
+            let double_click = is_double_click(mouse);
+            let lines_visible = wgt.size.height as i16 - 2;
+            let mut tbs = Default::default();
+            get_text_box_state_wrapped(ws, wgt, &mut tbs);
+            let lines_len = tbs.lines.borrow().len() as i16;
+
+            let row = mouse.row as i16 - wgt_rect.coord.row as i16 - 1;
+            let line = tbs.top_line + row;
+            let mut wgtstate_guard = WGT_STATE.try_lock().unwrap();
+
+            if double_click && row >= 0 && row < lines_visible && line < lines_len {
+                // select the line
+                wgtstate_guard.text_box_sel = TextBoxSelection {
+                    wgt_id: wgt.id,
+                    anchor: line,
+                    cursor: line,
+                };
+            }
+            else if wgtstate_guard.text_box_sel.wgt_id == wgt.id {
+                wgtstate_guard.text_box_sel.wgt_id = WIDGET_ID_NONE;
+            }
+
+            drop(wgtstate_guard);
+            ws.invalidate(wgt.id);
+        }
+
         if mouse.evt == MouseEvent::WheelUp || mouse.evt == MouseEvent::WheelDown {
             let mut tbs = Default::default();
-            ws.get_text_box_state(wgt, &mut tbs);
+            get_text_box_state_wrapped(ws, wgt, &mut tbs);
 
             let lines = tbs.lines.borrow();
 
@@ -1,15 +1,256 @@
 //! # RTWins Window Manager
 
-use crate::wgt::WId;
+use crate::common::{Coord, Rect, Size};
+use crate::input::{InputEvent, InputInfo, MouseEvent};
+use crate::wgt;
 use crate::wgt::WindowState;
+use crate::wgt::{ModalResult, WId};
 use crate::TERM;
 
+use atomic_once_cell::AtomicLazy;
+use try_lock::TryLock;
+
 extern crate alloc;
 use alloc::borrow::ToOwned;
+use alloc::string::String;
 use alloc::vec::Vec;
 
 // ---------------------------------------------------------------------------------------------- //
 
+/// Toast notification severity, determines its colors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// Single transient notification
+#[derive(Clone, Debug)]
+pub struct Toast {
+    pub text: String,
+    pub severity: Severity,
+    /// Timestamp the toast was shown at, in milliseconds;
+    /// `None` until the first `ToastQueue::remove_expired()` if added without the timestamp
+    pub shown_at: Option<u32>,
+    /// How long the toast stays visible, in milliseconds
+    pub duration: u32,
+}
+
+impl Toast {
+    /// Checks if the toast should be hidden at `now` timestamp; handles timer overflow
+    #[inline]
+    pub fn is_expired(&self, now: u32) -> bool {
+        self.shown_at
+            .is_some_and(|shown_at| now.wrapping_sub(shown_at) >= self.duration)
+    }
+}
+
+/// List of visible toasts, the oldest first
+#[derive(Default)]
+pub struct ToastQueue {
+    toasts: Vec<Toast>,
+}
+
+impl ToastQueue {
+    /// Maximum number of toasts displayed at once; the oldest one is dropped when exceeded
+    pub const MAX_TOASTS: usize = 5;
+
+    /// Adds a new toast
+    pub fn push(&mut self, text: &str, severity: Severity, duration: u32, now: u32) {
+        self.push_toast(text, severity, duration, Some(now));
+    }
+
+    /// Adds a new toast when the current timestamp is not known;
+    /// its timer starts at the next `remove_expired()`
+    pub fn push_untimed(&mut self, text: &str, severity: Severity, duration: u32) {
+        self.push_toast(text, severity, duration, None);
+    }
+
+    fn push_toast(&mut self, text: &str, severity: Severity, duration: u32, shown_at: Option<u32>) {
+        if self.toasts.len() == Self::MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+
+        self.toasts.push(Toast {
+            text: text.to_owned(),
+            severity,
+            shown_at,
+            duration,
+        });
+    }
+
+    /// Removes expired toasts; returns the screen areas no longer covered by the toasts
+    /// laid out at the `anchor`: of the removed ones and the old areas of moved ones
+    pub fn remove_expired(&mut self, now: u32, anchor: Coord) -> Vec<Rect> {
+        self.toasts
+            .iter_mut()
+            .filter(|t| t.shown_at.is_none())
+            .for_each(|t| t.shown_at = Some(now));
+
+        let mut uncovered = Vec::new();
+
+        if self.toasts.iter().any(|t| t.is_expired(now)) {
+            let mut kept_layout = Vec::with_capacity(self.toasts.len());
+
+            for (toast, rect) in self
+                .toasts
+                .iter()
+                .zip(wgt::get_toasts_layout(anchor, &self.toasts))
+            {
+                if toast.is_expired(now) {
+                    uncovered.push(rect);
+                }
+                else {
+                    kept_layout.push(rect);
+                }
+            }
+
+            self.toasts.retain(|t| !t.is_expired(now));
+
+            // older toasts are moved up, if a newer one was removed
+            let new_layout = wgt::get_toasts_layout(anchor, &self.toasts);
+            uncovered.extend(
+                kept_layout
+                    .into_iter()
+                    .zip(new_layout)
+                    .filter(|(old, new)| old != new)
+                    .map(|(old, _)| old),
+            );
+        }
+
+        uncovered
+    }
+
+    /// Removes all toasts
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    /// Returns visible toasts, the oldest first
+    pub fn as_slice(&self) -> &[Toast] {
+        &self.toasts[..]
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+/// Global instance of toasts queue, shared by all window managers
+pub static TOASTS: AtomicLazy<TryLock<ToastQueue>> =
+    AtomicLazy::new(|| TryLock::new(ToastQueue::default()));
+
+/// Modal window and the window it was opened from
+#[derive(Clone, Copy, Debug)]
+pub struct ModalInfo {
+    pub wnd_id: WId,
+    /// `None` if there was no visible window
+    pub opener_id: Option<WId>,
+    /// Widget focused in the opener, restored when the modal is closed
+    pub opener_focused_id: WId,
+}
+
+/// Stack of visible modal windows, the most recent on top
+static MODAL_STACK: AtomicLazy<TryLock<Vec<ModalInfo>>> =
+    AtomicLazy::new(|| TryLock::new(Vec::new()));
+
+/// Window that received the last mouse button press
+static MOUSE_OWNER: AtomicLazy<TryLock<Option<WId>>> = AtomicLazy::new(|| TryLock::new(None));
+
+/// Window being moved or resized by the mouse
+#[derive(Clone, Copy)]
+struct WndDrag {
+    wnd_id: WId,
+    resize: bool,
+    /// mouse position when the drag has started
+    start: Coord,
+    /// window area when the drag has started
+    wnd_rect: Rect,
+}
+
+static WND_DRAG: AtomicLazy<TryLock<Option<WndDrag>>> = AtomicLazy::new(|| TryLock::new(None));
+
+/// Minimal size of resized window
+const WND_MIN_SIZE: Size = Size::new(10, 3);
+
+/// Checks if the mouse press at `col:row` starts moving (title bar)
+/// or resizing (bottom-right corner) of the window
+fn window_drag_begin<WM: WindowManager + ?Sized>(
+    wm: &mut WM,
+    wnd_id: WId,
+    col: u8,
+    row: u8,
+) -> Option<WndDrag> {
+    let wnd_rect = wm.get_window_rect(wnd_id)?;
+    let ws = wm.get_ref(wnd_id)?;
+    let right = wnd_rect.coord.col as u16 + wnd_rect.size.width.saturating_sub(1) as u16;
+    let bottom = wnd_rect.coord.row as u16 + wnd_rect.size.height.saturating_sub(1) as u16;
+
+    let resize = if ws.is_resizable() && col as u16 == right && row as u16 == bottom {
+        true
+    }
+    else if ws.is_movable() && row == wnd_rect.coord.row && wnd_rect.is_point_within(col, row) {
+        false
+    }
+    else {
+        return None;
+    };
+
+    Some(WndDrag {
+        wnd_id,
+        resize,
+        start: Coord::new(col, row),
+        wnd_rect,
+    })
+}
+
+/// Move or resize the dragged window, according to the current mouse position
+fn window_drag_apply<WM: WindowManager + ?Sized>(wm: &mut WM, drag: &WndDrag, col: u8, row: u8) {
+    let delta_col = col as i16 - drag.start.col as i16;
+    let delta_row = row as i16 - drag.start.row as i16;
+
+    // the window right-bottom corner must not exceed the u8 coordinates
+    let coord = drag.wnd_rect.coord;
+    let size = drag.wnd_rect.size;
+
+    if drag.resize {
+        let max_w = (u8::MAX - coord.col)
+            .saturating_add(1)
+            .max(WND_MIN_SIZE.width);
+        let max_h = (u8::MAX - coord.row)
+            .saturating_add(1)
+            .max(WND_MIN_SIZE.height);
+        let width = (size.width as i16 + delta_col).clamp(WND_MIN_SIZE.width as i16, max_w as i16);
+        let height =
+            (size.height as i16 + delta_row).clamp(WND_MIN_SIZE.height as i16, max_h as i16);
+        wm.resize_window(drag.wnd_id, Size::new(width as u8, height as u8));
+    }
+    else {
+        let max_col = (u8::MAX - size.width.max(1)).saturating_add(1).max(1);
+        let max_row = (u8::MAX - size.height.max(1)).saturating_add(1).max(1);
+        let wnd_col = (coord.col as i16 + delta_col).clamp(1, max_col as i16);
+        let wnd_row = (coord.row as i16 + delta_row).clamp(1, max_row as i16);
+        wm.move_window(drag.wnd_id, Coord::new(wnd_col as u8, wnd_row as u8));
+    }
+}
+
+/// Removes the `wnd_id` from the modal windows stack
+fn modal_stack_remove(wnd_id: WId) -> Option<ModalInfo> {
+    let mut stack_guard = MODAL_STACK.try_lock()?;
+    let idx = stack_guard.iter().position(|mi| mi.wnd_id == wnd_id)?;
+    Some(stack_guard.remove(idx))
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
 pub trait WindowManager {
     // pub trait WindowManager<IT> {
     // where IT: Iterator {
@@ -52,8 +293,15 @@ pub trait WindowManager {
         }
     }
 
-    /// Hide window
+    /// Hide window; if it was modal, restores the focus in the window it was opened from
     fn hide(&mut self, wnd_id: WId) {
+        if let Some(mi) = modal_stack_remove(wnd_id) {
+            if let Some(opener) = mi.opener_id.and_then(|opener_id| self.get_mut(opener_id)) {
+                opener.set_focused_id(mi.opener_focused_id);
+            }
+            wgt::reset_internal_state();
+        }
+
         let visible = self.get_visible_mut();
 
         let idx = visible
@@ -69,6 +317,9 @@ pub trait WindowManager {
                 self.draw_all();
             }
             else if let Some(mut term_guard) = TERM.try_lock() {
+                if let Some(mut toasts_guard) = TOASTS.try_lock() {
+                    toasts_guard.clear();
+                }
                 term_guard.screen_clr_all();
                 term_guard.flush_buff();
             }
@@ -128,15 +379,24 @@ pub trait WindowManager {
                 term_guard.draw_wnd(ws);
             }
         }
+
+        self.draw_toasts();
     }
 
     /// Draw the top window invalidated widgets
     fn draw_top_invalidated(&mut self) {
+        let mut drawn = false;
+
         if let Some(mut term_guard) = TERM.try_lock() {
             if let Some(ws) = self.get_top_mut() {
-                term_guard.draw_invalidated(ws);
+                drawn = term_guard.draw_invalidated(ws);
             }
         }
+
+        if drawn {
+            // toasts might be covered by the redrawn widgets
+            self.draw_toasts();
+        }
     }
 
     /// Redraw windows from bottom to top
@@ -144,14 +404,322 @@ pub trait WindowManager {
         if let Some(mut term_guard) = TERM.try_lock() {
             let visible = self.get_visible().to_owned();
 
-            for wnd_idx in 0..16 {
-                if let Some(ws) = self.get_mut(wnd_idx) {
-                    if visible.contains(&wnd_idx) {
-                        term_guard.draw_wnd(ws);
+            for wnd_id in visible {
+                if let Some(ws) = self.get_mut(wnd_id) {
+                    term_guard.draw_wnd(ws);
+                }
+            }
+        }
+
+        self.draw_toasts();
+    }
+
+    /// Show window on the top, as modal to the current top window;
+    /// until closed, mouse clicks outside of it are ignored
+    fn show_modal(&mut self, wnd_id: WId) {
+        if self.is_visible(wnd_id) {
+            return;
+        }
+
+        let opener_id = self.get_visible().last().copied();
+        let opener_focused_id = opener_id
+            .and_then(|opener_id| self.get_mut(opener_id))
+            .map_or(wgt::WIDGET_ID_NONE, |ws| ws.get_focused_id());
+
+        if let Some(mut stack_guard) = MODAL_STACK.try_lock() {
+            stack_guard.push(ModalInfo {
+                wnd_id,
+                opener_id,
+                opener_focused_id,
+            });
+        }
+        else {
+            crate::tr_warn!("Cannot lock MODAL_STACK");
+        }
+
+        if let Some(mut owner_guard) = MOUSE_OWNER.try_lock() {
+            *owner_guard = None;
+        }
+
+        wgt::reset_internal_state();
+        self.show(wnd_id);
+    }
+
+    /// Close modal window and pass the `result` to the window it was opened from
+    fn close_modal(&mut self, wnd_id: WId, result: ModalResult) {
+        if let Some(mi) = modal_stack_remove(wnd_id) {
+            if let Some(opener) = mi.opener_id.and_then(|opener_id| self.get_mut(opener_id)) {
+                opener.set_focused_id(mi.opener_focused_id);
+                opener.on_modal_result(wnd_id, result.as_ref());
+            }
+            wgt::reset_internal_state();
+        }
+
+        self.hide(wnd_id);
+    }
+
+    /// Check if given window is shown as modal
+    fn is_modal(&self, wnd_id: WId) -> bool {
+        MODAL_STACK
+            .try_lock()
+            .is_some_and(|stack_guard| stack_guard.iter().any(|mi| mi.wnd_id == wnd_id))
+    }
+
+    /// Returns the screen area occupied by the window
+    fn get_window_rect(&mut self, wnd_id: WId) -> Option<Rect> {
+        let ws = self.get_mut(wnd_id)?;
+
+        Some(Rect {
+            coord: ws.get_window_coord(),
+            size: wgt::get_window_size(ws),
+        })
+    }
+
+    /// Move the window to the new position, redrawing the uncovered screen area
+    fn move_window(&mut self, wnd_id: WId, coord: Coord) {
+        if let Some(old_rect) = self.get_window_rect(wnd_id) {
+            if old_rect.coord != coord {
+                if let Some(ws) = self.get_mut(wnd_id) {
+                    ws.set_window_coord(coord);
+                }
+
+                self.redraw_area(old_rect);
+            }
+        }
+    }
+
+    /// Resize the window, redrawing the uncovered screen area
+    fn resize_window(&mut self, wnd_id: WId, size: Size) {
+        if let Some(old_rect) = self.get_window_rect(wnd_id) {
+            if old_rect.size != size {
+                if let Some(ws) = self.get_mut(wnd_id) {
+                    ws.set_window_size(size);
+                }
+
+                self.redraw_area(old_rect);
+            }
+        }
+    }
+
+    /// Clear the screen area and redraw all windows
+    fn redraw_area(&mut self, rect: Rect) {
+        if let Some(mut term_guard) = TERM.try_lock() {
+            // include the popup shadow
+            let size = Size::new(
+                rect.size.width.saturating_add(1),
+                rect.size.height.saturating_add(1),
+            );
+            term_guard.clear_area(rect.coord, size);
+        }
+
+        self.draw_all();
+    }
+
+    /// Returns the top-most visible window at the screen position `col:row`
+    fn find_window_at(&mut self, col: u8, row: u8) -> Option<WId> {
+        let visible = self.get_visible().to_owned();
+
+        visible.into_iter().rev().find(|wnd_id| {
+            self.get_window_rect(*wnd_id)
+                .is_some_and(|rect| rect.is_point_within(col, row))
+        })
+    }
+
+    /// Pass the input event to the proper window:
+    /// - keyboard events are given to the top window,
+    /// - mouse button press is given to the window under the cursor, raising it to the top,
+    /// - mouse button release is given to the window that received the press,
+    /// - mouse drag is given to the window that received the press,
+    /// - mouse wheel and motion is given to the window under the cursor, without raising it,
+    /// - mouse press on the title bar of movable window, or the bottom-right corner
+    ///   of resizable window, starts moving or resizing it, until the button is released.
+    ///
+    /// Mouse events outside of the modal window are ignored.
+    /// Closes the modal window as soon as it provides the result
+    fn process_input(&mut self, ii: &InputInfo) -> bool {
+        let top_id = match self.get_visible().last() {
+            Some(top_id) => *top_id,
+            None => return false,
+        };
+        let is_modal = self.is_modal(top_id);
+        let mut target_id = top_id;
+
+        if let InputEvent::Mouse(ref mi) = ii.evnt {
+            let mc = match mi.coord() {
+                Some(mc) => mc,
+                None => {
+                    if mi.evt == MouseEvent::ButtonReleased {
+                        // released beyond the windows: the drag is over, the window is not changed
+                        if let Some(mut drag_guard) = WND_DRAG.try_lock() {
+                            *drag_guard = None;
+                        }
+
+                        let owner_id = MOUSE_OWNER.try_lock().and_then(|mut guard| guard.take());
+
+                        if let Some(ws) = owner_id.and_then(|owner_id| self.get_mut(owner_id)) {
+                            return wgt::process_input(ws, ii);
+                        }
                     }
+                    return false;
                 }
-                else {
-                    break;
+            };
+            // drag or release of the button pressed inside of the modal window is allowed
+            let is_captured = matches!(mi.evt, MouseEvent::ButtonReleased | MouseEvent::Drag(_));
+
+            if is_modal
+                && !is_captured
+                && !self
+                    .get_window_rect(top_id)
+                    .is_some_and(|rect| rect.is_point_within(mc.col, mc.row))
+            {
+                // swallow the click
+                return true;
+            }
+
+            match mi.evt {
+                MouseEvent::None => {}
+                MouseEvent::ButtonReleased => {
+                    let drag = WND_DRAG.try_lock().and_then(|mut guard| guard.take());
+
+                    if let Some(drag) = drag {
+                        window_drag_apply(self, &drag, mc.col, mc.row);
+                        return true;
+                    }
+
+                    let owner_id = MOUSE_OWNER.try_lock().and_then(|mut guard| guard.take());
+
+                    if let Some(owner_id) = owner_id {
+                        if self.is_visible(owner_id) {
+                            target_id = owner_id;
+                        }
+                    }
+                }
+                MouseEvent::Drag(_) => {
+                    let drag = WND_DRAG.try_lock().and_then(|guard| *guard);
+
+                    if let Some(drag) = drag {
+                        window_drag_apply(self, &drag, mc.col, mc.row);
+                        return true;
+                    }
+
+                    let owner_id = MOUSE_OWNER.try_lock().and_then(|guard| *guard);
+
+                    if let Some(owner_id) = owner_id {
+                        if self.is_visible(owner_id) {
+                            target_id = owner_id;
+                        }
+                    }
+                }
+                MouseEvent::WheelUp | MouseEvent::WheelDown | MouseEvent::Move => {
+                    if let Some(wnd_id) = self.find_window_at(mc.col, mc.row) {
+                        target_id = wnd_id;
+                    }
+                }
+                _ => {
+                    if let Some(wnd_id) = self.find_window_at(mc.col, mc.row) {
+                        if wnd_id != top_id {
+                            // widgets state belongs to the previously active window
+                            wgt::reset_internal_state();
+                            self.show(wnd_id);
+                        }
+                        target_id = wnd_id;
+                    }
+
+                    if let Some(mut owner_guard) = MOUSE_OWNER.try_lock() {
+                        *owner_guard = Some(target_id);
+                    }
+
+                    if mi.evt == MouseEvent::ButtonLeft {
+                        if let Some(drag) = window_drag_begin(self, target_id, mc.col, mc.row) {
+                            if let Some(mut drag_guard) = WND_DRAG.try_lock() {
+                                *drag_guard = Some(drag);
+                            }
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+
+        let ws = match self.get_mut(target_id) {
+            Some(ws) => ws,
+            None => return false,
+        };
+
+        let handled = wgt::process_input(ws, ii);
+
+        if is_modal {
+            if let Some(result) = ws.take_modal_result() {
+                self.close_modal(top_id, result);
+            }
+        }
+
+        handled
+    }
+
+    /// Show non-modal notification above all windows, for `duration_ms` milliseconds
+    fn notify(&mut self, text: &str, severity: Severity, duration_ms: u32) {
+        let now = TERM
+            .try_lock()
+            .map(|term_guard| term_guard.pal.get_timestamp_ms());
+
+        if let Some(mut toasts_guard) = TOASTS.try_lock() {
+            match now {
+                Some(now) => toasts_guard.push(text, severity, duration_ms, now),
+                // timer started by the `toasts_tick()`
+                None => toasts_guard.push_untimed(text, severity, duration_ms),
+            }
+        }
+        else {
+            crate::tr_warn!("Cannot lock TOASTS");
+        }
+
+        self.draw_toasts();
+    }
+
+    /// Hide expired notifications, clearing the screen area and redrawing the windows beneath them;
+    /// shall be called periodically from the main loop
+    fn toasts_tick(&mut self) {
+        let mut removed = false;
+
+        if let Some(anchor) = self.get_toasts_anchor() {
+            if let Some(mut term_guard) = TERM.try_lock() {
+                let now = term_guard.pal.get_timestamp_ms();
+
+                if let Some(mut toasts_guard) = TOASTS.try_lock() {
+                    // the area outside the windows is not redrawn by the `draw_all()`
+                    for rect in toasts_guard.remove_expired(now, anchor) {
+                        term_guard.clear_area(rect.coord, rect.size);
+                        removed = true;
+                    }
+                }
+            }
+        }
+
+        if removed {
+            self.draw_all();
+        }
+    }
+
+    /// Returns the screen position of the notifications area top-right corner;
+    /// by default, it is the top-right corner of the screen, `Term::screen_size`
+    fn get_toasts_anchor(&mut self) -> Option<Coord> {
+        let screen_size = TERM.try_lock()?.screen_size;
+
+        Some(Coord {
+            col: screen_size.width.max(1),
+            row: 1,
+        })
+    }
+
+    /// Draw all visible notifications
+    fn draw_toasts(&mut self) {
+        if let Some(anchor) = self.get_toasts_anchor() {
+            if let Some(mut term_guard) = TERM.try_lock() {
+                if let Some(toasts_guard) = TOASTS.try_lock() {
+                    if !toasts_guard.is_empty() {
+                        term_guard.draw_toasts(anchor, toasts_guard.as_slice());
+                    }
                 }
             }
         }
@@ -0,0 +1,323 @@
+//! # RTWins tests common window, its state and input helpers
+
+#![allow(dead_code)]
+
+use rtwins::colors::*;
+use rtwins::common::*;
+use rtwins::input::*;
+use rtwins::pal::Pal;
+use rtwins::tetrary;
+use rtwins::utils::StringListRc;
+use rtwins::validator::Validator;
+use rtwins::wgt::*;
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// ---------------------------------------------------------------------------------------------- //
+
+pub mod id {
+    use rtwins::wgt::{WId, WIDGET_ID_NONE};
+
+    #[rustfmt::skip]
+    rtwins::generate_ids!(
+        WND_MAIN
+            EDT_NAME
+            TBX_LOG
+            EDT_SERIAL
+            EDT_TARGET
+            EDT_PSW
+            EDT_PORT
+            EDT_TIME
+            TBX_WRAP
+            TBX_SCROLL
+            LBL_INFO
+            TBX_NOTES
+    );
+}
+
+const fn text_edit(
+    id: WId,
+    row: u8,
+    width: u8,
+    psw_mask: bool,
+    input_mask: &'static str,
+) -> Widget {
+    Widget {
+        id,
+        coord: Coord { col: 2, row },
+        size: Size { width, height: 1 },
+        prop: Property::TextEdit(prop::TextEdit {
+            fg_color: ColorFg::White,
+            bg_color: ColorBg::Black,
+            psw_mask,
+            input_mask,
+        }),
+        ..Widget::cdeflt()
+    }
+}
+
+const fn text_box(id: WId, coord: Coord, size: Size, word_wrap: bool) -> Widget {
+    Widget {
+        id,
+        coord,
+        size,
+        prop: Property::TextBox(prop::TextBox {
+            fg_color: ColorFg::White,
+            bg_color: ColorBg::Black,
+            word_wrap,
+            markup: false,
+        }),
+        ..Widget::cdeflt()
+    }
+}
+
+/// Widgets are not overlapping, so the mouse events find the right one
+#[rustfmt::skip]
+pub const WINDOW_MAIN: Widget = Widget {
+    id: id::WND_MAIN,
+    link: Link::cdeflt(),
+    coord: Coord { col: 1, row: 1 },
+    size: Size { width: 72, height: 22 },
+    prop: prop::Window {
+        title: "Main",
+        fg_color: ColorFg::White,
+        bg_color: ColorBg::Blue,
+        is_popup: false,
+    }.into(),
+    children: &[
+        text_edit(id::EDT_NAME, 2, 20, false, ""),
+        text_box(id::TBX_LOG, Coord { col: 2, row: 4 }, Size { width: 20, height: 5 }, false),
+        text_edit(id::EDT_SERIAL, 10, 20, false, ""),
+        text_edit(id::EDT_TARGET, 12, 20, false, ""),
+        text_edit(id::EDT_PSW, 14, 20, true, ""),
+        text_edit(id::EDT_PORT, 16, 10, false, ""),
+        text_edit(id::EDT_TIME, 18, 12, false, "99:99:99"),
+        text_box(id::TBX_WRAP, Coord { col: 30, row: 1 }, Size { width: 12, height: 4 }, true),
+        text_box(id::TBX_SCROLL, Coord { col: 30, row: 6 }, Size { width: 12, height: 4 }, false),
+        Widget {
+            id: id::LBL_INFO,
+            coord: Coord { col: 44, row: 2 },
+            size: Size { width: 8, height: 2 },
+            prop: prop::Label {
+                title: "",
+                fg_color: ColorFg::Yellow,
+                bg_color: ColorBg::Blue,
+                markup: true,
+            }.into(),
+            ..Widget::cdeflt()
+        },
+        Widget {
+            id: id::TBX_NOTES,
+            coord: Coord { col: 54, row: 1 },
+            size: Size { width: 14, height: 4 },
+            prop: prop::TextBox {
+                fg_color: ColorFg::Inherit,
+                bg_color: ColorBg::Inherit,
+                word_wrap: true,
+                markup: true,
+            }.into(),
+            ..Widget::cdeflt()
+        },
+    ]
+};
+
+pub const WND_MAIN_WGTS: [Widget; transform::tree_wgt_count(&WINDOW_MAIN)] =
+    transform::tree_to_array(&WINDOW_MAIN);
+
+/// Returns the widget of the main window
+pub fn wgt(id: WId) -> &'static Widget {
+    WND_MAIN_WGTS.iter().find(|wgt| wgt.id == id).unwrap()
+}
+
+/// State of the main window; only the focused text box has the lines
+#[derive(Default)]
+pub struct MainState {
+    pub focused_id: WId,
+    pub name: String,
+    pub serial: String,
+    pub target: String,
+    pub port: String,
+    pub time: String,
+    pub errors: Vec<Option<&'static str>>,
+    pub lines: StringListRc,
+    pub lines_generation: u32,
+    pub top_line: i16,
+    pub left_col: i16,
+    pub label_disabled: bool,
+}
+
+impl MainState {
+    pub fn new(focused_id: WId) -> Self {
+        Self {
+            focused_id,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_lines(focused_id: WId, src: &[&str]) -> Self {
+        let ws = Self::new(focused_id);
+        ws.lines
+            .borrow_mut()
+            .extend(src.iter().map(|line| line.to_string()));
+        ws
+    }
+}
+
+impl WindowState for MainState {
+    fn on_text_edit_change(&mut self, wgt: &Widget, txt: &mut String) {
+        match wgt.id {
+            id::EDT_NAME => self.name = txt.clone(),
+            id::EDT_SERIAL => self.serial = txt.clone(),
+            id::EDT_TARGET => self.target = txt.clone(),
+            id::EDT_PORT => self.port = txt.clone(),
+            id::EDT_TIME => self.time = txt.clone(),
+            _ => {}
+        }
+    }
+
+    fn on_text_edit_validate(&mut self, _wgt: &Widget, error: Option<&'static str>) {
+        self.errors.push(error);
+    }
+
+    fn on_text_box_scroll(&mut self, wgt: &Widget, new_top_line: i16) {
+        if wgt.id == self.focused_id {
+            self.top_line = new_top_line;
+        }
+    }
+
+    fn on_text_box_scroll_h(&mut self, wgt: &Widget, new_left_col: i16) {
+        if wgt.id == self.focused_id {
+            self.left_col = new_left_col;
+        }
+    }
+
+    fn is_enabled(&self, wgt: &Widget) -> bool {
+        wgt.id != id::LBL_INFO || !self.label_disabled
+    }
+
+    fn get_label_text(&mut self, _wgt: &Widget, out: &mut String) {
+        out.push_str("[b]Hi[/b] [u]there, world[/u]\n[[ok]");
+    }
+
+    fn get_text_edit_text(&mut self, wgt: &Widget, out: &mut String, _edit_mode: bool) {
+        match wgt.id {
+            id::EDT_NAME => out.push_str(&self.name),
+            id::EDT_SERIAL => out.push_str(&self.serial),
+            id::EDT_TARGET => out.push_str(&self.target),
+            id::EDT_PSW => out.push_str("secret"),
+            id::EDT_PORT => out.push_str(&self.port),
+            id::EDT_TIME => out.push_str(&self.time),
+            _ => {}
+        }
+    }
+
+    fn get_text_edit_validator(&mut self, wgt: &Widget) -> Option<Validator> {
+        tetrary!(
+            wgt.id == id::EDT_PORT,
+            Some(Validator::Int { min: 1, max: 65535 }),
+            None
+        )
+    }
+
+    fn get_text_box_state(&mut self, wgt: &Widget, out: &mut rstate::TxtbxState) {
+        if wgt.id == self.focused_id {
+            out.lines = self.lines.clone();
+            out.generation = self.lines_generation;
+            out.top_line = self.top_line;
+            out.left_col = self.left_col;
+        }
+    }
+
+    fn get_focused_id(&mut self) -> WId {
+        self.focused_id
+    }
+
+    fn set_focused_id(&mut self, wid: WId) {
+        self.focused_id = wid;
+    }
+
+    fn get_widgets(&self) -> &'static [Widget] {
+        &WND_MAIN_WGTS
+    }
+
+    fn get_window_coord(&mut self) -> Coord {
+        WND_MAIN_WGTS[0].coord
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+/// Tests use the global widgets state, clipboard and terminal
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Serializes the tests; the lock is not poisoned by a failed test
+pub fn lock() -> MutexGuard<'static, ()> {
+    STATE_LOCK.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Collects the terminal output
+#[derive(Default)]
+pub struct PalCapture {
+    pub out: Arc<Mutex<String>>,
+}
+
+impl Pal for PalCapture {
+    fn write_char_n(&mut self, c: char, repeat: i16) {
+        self.out
+            .lock()
+            .unwrap()
+            .push_str(&c.to_string().repeat(repeat as usize));
+    }
+
+    fn write_str_n(&mut self, s: &str, repeat: i16) {
+        self.out
+            .lock()
+            .unwrap()
+            .push_str(&s.repeat(repeat as usize));
+    }
+}
+
+/// Redirects the terminal output into the returned buffer
+pub fn capture_output() -> Arc<Mutex<String>> {
+    let out = Arc::new(Mutex::new(String::new()));
+    rtwins::TERM.try_lock().unwrap().pal = Box::new(PalCapture { out: out.clone() });
+    out
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+pub fn key(key: Key) -> InputInfo {
+    key_mod(key, KEY_MOD_NONE)
+}
+
+pub fn key_mod(key: Key, kmod: u8) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Key(key),
+        kmod: KeyMod {
+            mask: KEY_MOD_SPECIAL | kmod,
+        },
+        ..Default::default()
+    }
+}
+
+pub fn chr(c: char) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Char(CharBuff::from(c)),
+        ..Default::default()
+    }
+}
+
+pub fn ctrl(c: char) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Char(CharBuff::from(c)),
+        kmod: KeyMod { mask: KEY_MOD_CTRL },
+        ..Default::default()
+    }
+}
+
+pub fn paste(text: &str) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Paste(text.into()),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,146 @@
+//! # RTWins clipboard tests
+
+extern crate rtwins;
+use rtwins::input::*;
+use rtwins::wgt::*;
+
+mod common;
+use common::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn text_edit_copy() {
+    let _lock = lock();
+    reset_internal_state();
+    let out = capture_output();
+    let mut ws = MainState {
+        serial: "SN-123".into(),
+        ..MainState::new(id::EDT_SERIAL)
+    };
+
+    // copy the entire text, not in edit mode
+    assert!(process_input(&mut ws, &ctrl('C')));
+    assert_eq!("SN-123", clipboard_get());
+
+    // copy the selected text in edit mode
+    ws.serial = "yxx".into();
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &ctrl('A'));
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("yxx", clipboard_get());
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    let selected = format!("{}yxx{}", rtwins::esc::INVERSE_ON, rtwins::esc::INVERSE_OFF);
+    assert!(out.lock().unwrap().contains(&selected));
+    process_input(&mut ws, &key(Key::Esc));
+}
+
+#[test]
+fn text_edit_copy_osc52() {
+    let _lock = lock();
+    reset_internal_state();
+    let out = capture_output();
+    rtwins::TERM.try_lock().unwrap().osc52_clipboard(true);
+    let mut ws = MainState {
+        serial: "SN-123".into(),
+        ..MainState::new(id::EDT_SERIAL)
+    };
+
+    // copied text also sent to the terminal
+    assert!(process_input(&mut ws, &ctrl('C')));
+    assert!(out.lock().unwrap().contains("\x1b]52;c;U04tMTIz\x07"));
+    rtwins::TERM.try_lock().unwrap().osc52_clipboard(false);
+}
+
+#[test]
+fn text_edit_paste() {
+    let _lock = lock();
+    reset_internal_state();
+    clipboard_set("SN-123");
+    let mut ws = MainState {
+        target: "#".into(),
+        ..MainState::new(id::EDT_TARGET)
+    };
+
+    // paste starts editing, appending the text
+    assert!(process_input(&mut ws, &ctrl('V')));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("#SN-123", ws.target);
+
+    // selected text replaced with the pasted one
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &ctrl('A'));
+    process_input(&mut ws, &ctrl('V'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("SN-123", ws.target);
+
+    // selected text replaced with the typed one
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &ctrl('A'));
+    process_input(&mut ws, &chr('x'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("x", ws.target);
+}
+
+#[test]
+fn text_edit_cut() {
+    let _lock = lock();
+    reset_internal_state();
+    let mut ws = MainState {
+        target: "x".into(),
+        ..MainState::new(id::EDT_TARGET)
+    };
+
+    // cut and paste
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &ctrl('A'));
+    process_input(&mut ws, &ctrl('X'));
+    assert_eq!("x", clipboard_get());
+    process_input(&mut ws, &chr('y'));
+    process_input(&mut ws, &ctrl('V'));
+    process_input(&mut ws, &ctrl('V'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("yxx", ws.target);
+
+    // selection deleted, then cleared by the cursor move
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &ctrl('A'));
+    process_input(&mut ws, &key(Key::Backspace));
+    process_input(&mut ws, &chr('a'));
+    process_input(&mut ws, &ctrl('A'));
+    process_input(&mut ws, &key(Key::Left));
+    process_input(&mut ws, &chr('b'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("ba", ws.target);
+    assert_eq!("x", clipboard_get());
+}
+
+#[test]
+fn text_edit_password_copy() {
+    let _lock = lock();
+    reset_internal_state();
+    clipboard_set("yxx");
+    let mut ws = MainState::new(id::EDT_PSW);
+
+    // password is never copied
+    assert!(process_input(&mut ws, &ctrl('C')));
+    assert_eq!("yxx", clipboard_get());
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &ctrl('A'));
+    process_input(&mut ws, &ctrl('X'));
+    process_input(&mut ws, &key(Key::Esc));
+    assert_eq!("yxx", clipboard_get());
+}
+
+#[test]
+fn clipboard_reset() {
+    let _lock = lock();
+    clipboard_set("yxx");
+
+    // clipboard survives the window change
+    reset_internal_state();
+    assert_eq!("yxx", clipboard_get());
+
+    clipboard_set("");
+    assert!(clipboard_get().is_empty());
+}
@@ -0,0 +1,163 @@
+//! # RTWins Dialogs tests
+
+extern crate rtwins;
+use rtwins::common::*;
+use rtwins::dialog::*;
+use rtwins::input::*;
+use rtwins::wgt::{self, WindowState};
+use rtwins::wnd_manager::Severity;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn is_wgt_visible(dlg: &DialogState, wid: wgt::WId) -> bool {
+    let wgt = wgt::find_by_id(dlg.get_widgets(), wid).unwrap();
+    wgt::is_visible(dlg, wgt)
+}
+
+#[test]
+fn dialog_auto_size() {
+    let mut dlg = DialogState::default();
+
+    dlg.message("Info", "Short message", Severity::Info, DialogButtons::Ok);
+    let small = dlg.get_window_size();
+
+    dlg.message(
+        "Info",
+        "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+        tempor incididunt ut labore et dolore magna aliqua. \
+        Ut enim ad minim veniam, quis nostrud exercitation ullamco",
+        Severity::Info,
+        DialogButtons::Ok,
+    );
+    let bigger = dlg.get_window_size();
+
+    assert!(bigger.width > small.width);
+    assert!(bigger.height > small.height);
+}
+
+#[test]
+fn dialog_center_on() {
+    let mut dlg = DialogState::default();
+    dlg.center_on(Rect::new(1, 1, 80, 25));
+    dlg.confirm("Question", "Are you sure?");
+
+    let size = dlg.get_window_size();
+    let coord = dlg.get_window_coord();
+    assert_eq!(1 + (80 - size.width) / 2, coord.col);
+    assert_eq!(1 + (25 - size.height) / 2, coord.row);
+}
+
+#[test]
+fn dialog_buttons_visibility() {
+    let mut dlg = DialogState::default();
+
+    dlg.message("", "", Severity::Error, DialogButtons::YesNoCancel);
+    assert!(is_wgt_visible(&dlg, id::BTN_YES));
+    assert!(is_wgt_visible(&dlg, id::BTN_NO));
+    assert!(is_wgt_visible(&dlg, id::BTN_CANCEL));
+    assert!(!is_wgt_visible(&dlg, id::BTN_OK));
+    assert!(!is_wgt_visible(&dlg, id::EDT_INPUT));
+    assert!(!is_wgt_visible(&dlg, id::LBX_ITEMS));
+
+    dlg.prompt_text("", "Name:", "");
+    assert!(is_wgt_visible(&dlg, id::BTN_OK));
+    assert!(is_wgt_visible(&dlg, id::BTN_CANCEL));
+    assert!(!is_wgt_visible(&dlg, id::BTN_YES));
+    assert!(is_wgt_visible(&dlg, id::EDT_INPUT));
+    assert_eq!(id::EDT_INPUT, dlg.get_focused_id());
+
+    dlg.select_item("", "", vec!["a".into(), "b".into()], 1);
+    assert!(is_wgt_visible(&dlg, id::LBX_ITEMS));
+    assert!(!is_wgt_visible(&dlg, id::EDT_INPUT));
+    assert_eq!(id::LBX_ITEMS, dlg.get_focused_id());
+}
+
+#[test]
+fn dialog_result_callback() {
+    let received = Rc::new(RefCell::new(None));
+    let mut dlg = DialogState::default();
+
+    let rcv = Rc::clone(&received);
+    dlg.confirm("Question", "Are you sure?")
+        .on_result(Box::new(move |res| *rcv.borrow_mut() = Some(res.clone())));
+    assert!(!dlg.is_closed());
+
+    let btn = wgt::find_by_id(dlg.get_widgets(), id::BTN_YES).unwrap();
+    dlg.on_button_click(btn, &InputInfo::default());
+
+    assert!(dlg.is_closed());
+    assert_eq!(Some(DialogResult::Yes), *received.borrow());
+    assert_eq!(Some(DialogResult::Yes), dlg.take_result());
+    assert_eq!(None, dlg.take_result());
+}
+
+#[test]
+fn dialog_number_prompt() {
+    let mut dlg = DialogState::default();
+    dlg.prompt_number("Number", "Value:", 500, -10, 10);
+
+    let mut txt = String::new();
+    let edt = wgt::find_by_id(dlg.get_widgets(), id::EDT_INPUT).unwrap();
+    dlg.get_text_edit_text(edt, &mut txt, false);
+    assert_eq!("10", txt);
+
+    let btn = wgt::find_by_id(dlg.get_widgets(), id::BTN_OK).unwrap();
+    dlg.on_button_click(btn, &InputInfo::default());
+    assert_eq!(Some(DialogResult::Number(10)), dlg.take_result());
+}
+
+#[test]
+fn dialog_number_prompt_reversed_range() {
+    let mut dlg = DialogState::default();
+    dlg.prompt_number("Number", "Value:", 500, 10, -10);
+    assert_eq!(
+        DialogKind::NumberPrompt { min: -10, max: 10 },
+        dlg.get_kind()
+    );
+
+    let mut txt = String::new();
+    let edt = wgt::find_by_id(dlg.get_widgets(), id::EDT_INPUT).unwrap();
+    dlg.get_text_edit_text(edt, &mut txt, false);
+    assert_eq!("10", txt);
+
+    dlg.on_text_edit_change(edt, &mut "-7".to_string());
+    assert_eq!(Some(DialogResult::Number(-7)), dlg.take_result());
+}
+
+#[test]
+fn dialog_number_prompt_invalid() {
+    let mut dlg = DialogState::default();
+    dlg.prompt_number("Number", "Value:", 0, -10, 10);
+    let edt = wgt::find_by_id(dlg.get_widgets(), id::EDT_INPUT).unwrap();
+
+    // rejected, the dialog stays open with the entered text
+    for input in ["", "abc", "11", "-"] {
+        dlg.on_text_edit_change(edt, &mut input.to_string());
+        assert!(!dlg.is_closed(), "input: '{input}'");
+
+        let mut txt = String::new();
+        dlg.get_text_edit_text(edt, &mut txt, false);
+        assert_eq!(input, txt);
+    }
+
+    dlg.on_text_edit_change(edt, &mut "-7".to_string());
+    assert_eq!(Some(DialogResult::Number(-7)), dlg.take_result());
+}
+
+#[test]
+fn dialog_esc_cancels() {
+    let mut dlg = DialogState::default();
+    dlg.prompt_text("Text", "Name:", "Bob");
+
+    let ii = InputInfo {
+        evnt: InputEvent::Key(Key::Esc),
+        kmod: KeyMod {
+            mask: KEY_MOD_SPECIAL,
+        },
+        ..Default::default()
+    };
+    let wnd = dlg.get_widgets().first().unwrap();
+    assert!(dlg.on_window_unhandled_input_evt(wnd, &ii));
+    assert_eq!(Some(DialogResult::Cancel), dlg.take_result());
+}
@@ -394,5 +394,11 @@ fn screen_scroll() {
 fn link_url() {
     let s = String::new() + rtwins::url_link!("https://github.com/marmidr/rtwins", "RTWins");
 
-    assert!(s.len() > 0);
+    assert!(!s.is_empty());
+}
+
+#[test]
+fn clipboard_osc52() {
+    assert_eq!("\x1b]52;c;U04tMTIz\x07", osc52_copy("SN-123"));
+    assert_eq!("\x1b]52;c;\x07", osc52_copy(""));
 }
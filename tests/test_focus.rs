@@ -0,0 +1,318 @@
+//! # RTWins focus navigation tests
+
+extern crate rtwins;
+use rtwins::colors::*;
+use rtwins::common::*;
+use rtwins::input::*;
+use rtwins::wgt::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+mod id {
+    use rtwins::wgt::{WId, WIDGET_ID_NONE};
+
+    #[rustfmt::skip]
+    rtwins::generate_ids!(
+        WND_MAIN
+            BTN_A
+            BTN_B
+            BTN_C
+            BTN_D
+            BTN_E
+    );
+}
+
+mod idf {
+    use rtwins::wgt::{WId, WIDGET_ID_NONE};
+
+    #[rustfmt::skip]
+    rtwins::generate_ids!(
+        WND_FORM
+            BTN_NAME
+            PNL_ADDRESS
+                BTN_STREET
+                BTN_CITY
+            BTN_HELP
+            BTN_NEXT
+            BTN_BACK
+    );
+}
+
+const fn button(id: WId, col: u8, row: u8, text: &'static str) -> Widget {
+    Widget {
+        id,
+        coord: Coord { col, row },
+        prop: Property::Button(prop::Button {
+            text,
+            fg_color: ColorFg::White,
+            bg_color: ColorBg::Green,
+            style: ButtonStyle::Simple,
+        }),
+        ..Widget::cdeflt()
+    }
+}
+
+// [A]         [B]
+// [C]                   [D]
+//           [E]
+#[rustfmt::skip]
+const WINDOW_MAIN: Widget = Widget {
+    id: id::WND_MAIN,
+    link: Link::cdeflt(),
+    coord: Coord { col: 1, row: 1 },
+    size: Size { width: 50, height: 12 },
+    prop: prop::Window {
+        title: "Main",
+        fg_color: ColorFg::White,
+        bg_color: ColorBg::Blue,
+        is_popup: false,
+    }.into(),
+    children: &[
+        button(id::BTN_A, 2, 2, "A"),
+        button(id::BTN_B, 20, 2, "B"),
+        button(id::BTN_C, 2, 5, "C"),
+        button(id::BTN_D, 30, 5, "D"),
+        button(id::BTN_E, 12, 8, "E"),
+    ]
+};
+
+const WND_MAIN_WGTS: [Widget; transform::tree_wgt_count(&WINDOW_MAIN)] =
+    transform::tree_to_array(&WINDOW_MAIN);
+
+#[rustfmt::skip]
+const WINDOW_FORM: Widget = Widget {
+    id: idf::WND_FORM,
+    link: Link::cdeflt(),
+    coord: Coord { col: 1, row: 1 },
+    size: Size { width: 50, height: 12 },
+    prop: prop::Window {
+        title: "Form",
+        fg_color: ColorFg::White,
+        bg_color: ColorBg::Blue,
+        is_popup: false,
+    }.into(),
+    children: &[
+        button(idf::BTN_NAME, 2, 1, "Name"),
+        Widget {
+            id: idf::PNL_ADDRESS,
+            coord: Coord { col: 2, row: 3 },
+            size: Size { width: 30, height: 4 },
+            prop: prop::Panel {
+                title: "Address",
+                fg_color: ColorFg::White,
+                bg_color: ColorBg::Blue,
+                no_frame: false,
+            }.into(),
+            children: &[
+                button(idf::BTN_STREET, 2, 1, "Street"),
+                button(idf::BTN_CITY, 2, 2, "City"),
+            ],
+            ..Widget::cdeflt()
+        },
+        button(idf::BTN_HELP, 2, 8, "Help"),
+        button(idf::BTN_NEXT, 20, 10, "Next"),
+        button(idf::BTN_BACK, 2, 10, "Back"),
+    ]
+};
+
+const WND_FORM_WGTS: [Widget; transform::tree_wgt_count(&WINDOW_FORM)] =
+    transform::tree_to_array(&WINDOW_FORM);
+
+#[derive(Default)]
+struct FormState {
+    focused_id: WId,
+    address_scope: bool,
+}
+
+impl WindowState for FormState {
+    fn get_tab_index(&self, wgt: &Widget) -> Option<u16> {
+        // Next before Back, although Back is first in the tree
+        match wgt.id {
+            idf::BTN_NEXT => Some(1),
+            idf::BTN_BACK => Some(2),
+            _ => None,
+        }
+    }
+
+    fn is_tab_stop(&self, wgt: &Widget) -> bool {
+        wgt.id != idf::BTN_HELP
+    }
+
+    fn is_focus_scope(&self, wgt: &Widget) -> bool {
+        self.address_scope && wgt.id == idf::PNL_ADDRESS
+    }
+
+    fn get_focused_id(&mut self) -> WId {
+        self.focused_id
+    }
+
+    fn set_focused_id(&mut self, wid: WId) {
+        self.focused_id = wid;
+    }
+
+    fn get_widgets(&self) -> &'static [Widget] {
+        &WND_FORM_WGTS
+    }
+
+    fn get_window_coord(&mut self) -> Coord {
+        WND_FORM_WGTS[0].coord
+    }
+}
+
+#[derive(Default)]
+struct MainState {
+    focused_id: WId,
+}
+
+impl WindowState for MainState {
+    fn get_focused_id(&mut self) -> WId {
+        self.focused_id
+    }
+
+    fn set_focused_id(&mut self, wid: WId) {
+        self.focused_id = wid;
+    }
+
+    fn get_widgets(&self) -> &'static [Widget] {
+        &WND_MAIN_WGTS
+    }
+
+    fn get_window_coord(&mut self) -> Coord {
+        WND_MAIN_WGTS[0].coord
+    }
+}
+
+fn key(key: Key) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Key(key),
+        kmod: KeyMod {
+            mask: KEY_MOD_SPECIAL,
+        },
+        ..Default::default()
+    }
+}
+
+fn shift_tab() -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Key(Key::Tab),
+        kmod: KeyMod {
+            mask: KEY_MOD_SPECIAL | KEY_MOD_SHIFT,
+        },
+        ..Default::default()
+    }
+}
+
+fn move_focus(ws: &mut dyn WindowState, from: WId, k: Key) -> WId {
+    ws.set_focused_id(from);
+    assert!(process_input(ws, &key(k)));
+    ws.get_focused_id()
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn screen_rect() {
+    let mut ws = MainState::default();
+    let rect = get_screen_rect(&mut ws, &WND_MAIN_WGTS[2]);
+    assert_eq!(Coord { col: 21, row: 3 }, rect.coord);
+    assert_eq!(
+        Size {
+            width: 5,
+            height: 1
+        },
+        rect.size
+    );
+}
+
+#[test]
+fn arrow_keys_move_focus() {
+    let mut ws = MainState::default();
+
+    assert_eq!(id::BTN_B, move_focus(&mut ws, id::BTN_A, Key::Right));
+    assert_eq!(id::BTN_C, move_focus(&mut ws, id::BTN_A, Key::Down));
+    assert_eq!(id::BTN_A, move_focus(&mut ws, id::BTN_B, Key::Left));
+    // nothing in line below, so the nearest one
+    assert_eq!(id::BTN_D, move_focus(&mut ws, id::BTN_B, Key::Down));
+    // the one in line wins over the closer one
+    assert_eq!(id::BTN_D, move_focus(&mut ws, id::BTN_C, Key::Right));
+    assert_eq!(id::BTN_C, move_focus(&mut ws, id::BTN_D, Key::Left));
+    assert_eq!(id::BTN_C, move_focus(&mut ws, id::BTN_E, Key::Up));
+    assert_eq!(id::BTN_E, move_focus(&mut ws, id::BTN_C, Key::Down));
+}
+
+#[test]
+fn no_widget_in_direction() {
+    let mut ws = MainState::default();
+
+    // fall back to the tree order
+    assert_eq!(id::BTN_E, move_focus(&mut ws, id::BTN_A, Key::Up));
+    assert_eq!(id::BTN_B, move_focus(&mut ws, id::BTN_A, Key::Right));
+    assert_eq!(id::BTN_A, move_focus(&mut ws, id::BTN_E, Key::Down));
+    assert_eq!(id::BTN_D, move_focus(&mut ws, id::BTN_E, Key::Right));
+}
+
+#[test]
+fn tab_order_wraps() {
+    let mut ws = MainState::default();
+
+    assert_eq!(id::BTN_A, move_focus(&mut ws, id::BTN_E, Key::Tab));
+    ws.focused_id = id::BTN_A;
+    assert!(process_input(&mut ws, &shift_tab()));
+    assert_eq!(id::BTN_E, ws.focused_id);
+}
+
+#[test]
+fn tab_index_and_tab_stop() {
+    let mut ws = FormState::default();
+
+    // Next and Back go first, Help is skipped
+    let mut order = vec![];
+    ws.focused_id = WIDGET_ID_NONE;
+    for _ in 0..5 {
+        assert!(process_input(&mut ws, &key(Key::Tab)));
+        order.push(ws.focused_id);
+    }
+    assert_eq!(
+        vec![
+            idf::BTN_NEXT,
+            idf::BTN_BACK,
+            idf::BTN_NAME,
+            idf::BTN_STREET,
+            idf::BTN_CITY
+        ],
+        order
+    );
+    assert_eq!(idf::BTN_NEXT, move_focus(&mut ws, idf::BTN_CITY, Key::Tab));
+
+    // Help focused by mouse continues in the tree order
+    assert_eq!(idf::BTN_NEXT, move_focus(&mut ws, idf::BTN_HELP, Key::Tab));
+    ws.focused_id = idf::BTN_HELP;
+    assert!(process_input(&mut ws, &shift_tab()));
+    assert_eq!(idf::BTN_CITY, ws.focused_id);
+}
+
+#[test]
+fn focus_scope() {
+    let mut ws = FormState::default();
+    assert_eq!(idf::BTN_NEXT, move_focus(&mut ws, idf::BTN_CITY, Key::Tab));
+
+    // Tab cycles within the panel
+    ws.address_scope = true;
+    assert_eq!(
+        idf::BTN_STREET,
+        move_focus(&mut ws, idf::BTN_CITY, Key::Tab)
+    );
+    assert_eq!(
+        idf::BTN_CITY,
+        move_focus(&mut ws, idf::BTN_STREET, Key::Tab)
+    );
+    ws.focused_id = idf::BTN_STREET;
+    assert!(process_input(&mut ws, &shift_tab()));
+    assert_eq!(idf::BTN_CITY, ws.focused_id);
+
+    // entered from outside
+    assert_eq!(
+        idf::BTN_STREET,
+        move_focus(&mut ws, idf::BTN_NAME, Key::Tab)
+    );
+}
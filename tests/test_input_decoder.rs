@@ -2,7 +2,7 @@
 
 extern crate rtwins;
 use rtwins::input::*;
-use rtwins::input_decoder::{Decoder, InputQue};
+use rtwins::input_decoder::{Decoder, InputQue, TermProfile, ESC_TIMEOUT_MS, PASTE_MAX_LENGTH};
 use rtwins::utils;
 
 trait EasyInput {
@@ -32,7 +32,7 @@ fn inp_empty() {
     }
     else {
         // assert_matches!(other, InputType::None);
-        assert!(false);
+        panic!();
     }
 }
 
@@ -53,7 +53,7 @@ fn inp_unknown_esc() {
     }
     else {
         // assert_matches!(other, InputType::None);
-        assert!(false);
+        panic!();
     }
 }
 
@@ -223,6 +223,258 @@ fn esc_followed_by_nothing() {
     assert!(matches!(ii.evnt, InputEvent::Key(Key::Esc)));
 }
 
+#[test]
+fn esc_timeout() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+    dec.set_esc_timeout(30);
+
+    // sole ESC is awaited until the timeout elapses
+    inp.push(AnsiCodes::ESC as u8);
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 1000));
+    assert!(matches!(ii.evnt, InputEvent::None));
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 1029));
+    assert!(matches!(ii.evnt, InputEvent::None));
+
+    assert_eq!(1, dec.decode_input_seq_at(&mut inp, &mut ii, 1030));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Esc)));
+    assert_eq!(KEY_MOD_SPECIAL, ii.kmod.mask);
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn esc_timeout_split_sequence() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+    dec.set_esc_timeout(30);
+
+    // sequence split into many parts is kept as long as the next bytes come in time
+    inp.push(AnsiCodes::ESC as u8);
+    for (i, part) in ["[", "1", ";", "5"].iter().enumerate() {
+        let now = 100 + i as u32 * 20;
+        assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, now));
+        assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, now + 10));
+        inp.push_str(part);
+    }
+
+    inp.push_str("H");
+    assert_eq!(6, dec.decode_input_seq_at(&mut inp, &mut ii, 200));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Home)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn esc_timeout_incomplete_sequence() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // ESC followed by '[' only is reported as Esc after the timeout
+    inp.push_str("\x1B[");
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 0));
+    assert_eq!(
+        1,
+        dec.decode_input_seq_at(&mut inp, &mut ii, ESC_TIMEOUT_MS as u32)
+    );
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Esc)));
+    assert_eq!(1, inp.len());
+
+    // incomplete sequence is dropped after the timeout
+    inp.clear();
+    inp.push_str("\x1B[1;5");
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 100));
+    assert_eq!(5, inp.len());
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 200));
+    assert!(matches!(ii.evnt, InputEvent::None));
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn user_key_seq() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // unknown sequence
+    inp.push_str("\x1B[[A");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::None));
+
+    inp.clear();
+    dec.add_key_seq("\x1B[[A", "F1", Key::F1, KEY_MOD_NONE);
+    // overrides the built-in Up key
+    dec.add_key_seq("[A", "S-Up", Key::Up, KEY_MOD_SHIFT);
+    // too long sequence is rejected
+    dec.add_key_seq("[1234567~", "F20", Key::F12, KEY_MOD_NONE);
+
+    inp.push_str("\x1B[[A\x1B[A\x1B[1234567~");
+    assert_eq!(4, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::F1)));
+    assert_eq!(KEY_MOD_SPECIAL, ii.kmod.mask);
+    assert_eq!("F1", ii.name);
+
+    assert_eq!(3, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_SHIFT, ii.kmod.mask);
+
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::None));
+
+    // back to the built-in map
+    inp.clear();
+    dec.clear_key_seqs();
+    inp.push_str("\x1B[A");
+    assert_eq!(3, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+    assert_eq!(KEY_MOD_SPECIAL, ii.kmod.mask);
+}
+
+#[test]
+fn term_profiles() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    dec.set_term_profile(TermProfile::Rxvt);
+    inp.push_str("\x1B[a\x1BOd\x1B[7$\x1B[23^");
+    assert_eq!(3, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_SHIFT, ii.kmod.mask);
+    assert_eq!(3, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Left)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+    assert_eq!(4, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Home)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_SHIFT, ii.kmod.mask);
+    // built-in sequence
+    assert_eq!(5, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::F1)));
+
+    // the same sequence differs between profiles
+    dec.set_term_profile(TermProfile::Xterm);
+    inp.push_str("\x1BOA");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+    assert_eq!(KEY_MOD_SPECIAL, ii.kmod.mask);
+
+    dec.set_term_profile(TermProfile::Putty);
+    inp.push_str("\x1BOA\x1B[[E");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::F5)));
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn user_key_seq_split() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+    dec.set_term_profile(TermProfile::LinuxConsole);
+
+    inp.push_str("\x1B[[");
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 0));
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 10));
+    assert_eq!(3, inp.len());
+
+    inp.push_str("C");
+    assert_eq!(4, dec.decode_input_seq_at(&mut inp, &mut ii, 20));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::F3)));
+}
+
+#[test]
+fn feed_bytes() {
+    let mut dec = Decoder::default();
+
+    // Ctrl+Home split into bytes
+    for b in b"\x1B[1;5".iter() {
+        assert!(dec.feed(*b).is_none());
+    }
+
+    let ii = dec.feed(b'H').unwrap();
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Home)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+
+    // UTF-8 character
+    assert!(dec.feed(0xC5).is_none());
+    let ii = dec.feed(0x82).unwrap();
+    assert!(matches!(ii.evnt, InputEvent::Char(ref cb) if cb.as_str() == "ł"));
+
+    // sole ESC is reported by the next poll
+    assert!(dec.feed(AnsiCodes::ESC as u8).is_none());
+    let ii = dec.poll().unwrap();
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Esc)));
+    assert!(dec.poll().is_none());
+}
+
+#[test]
+fn feed_at_esc_timeout() {
+    let mut dec = Decoder::default();
+    dec.set_esc_timeout(20);
+
+    assert!(dec.feed_at(AnsiCodes::ESC as u8, 100).is_none());
+    assert!(dec.poll_at(110).is_none());
+    assert!(dec.feed_at(b'O', 115).is_none());
+    let ii = dec.feed_at(b'P', 130).unwrap();
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::F1)));
+
+    assert!(dec.feed_at(AnsiCodes::ESC as u8, 200).is_none());
+    assert!(dec.poll_at(219).is_none());
+    let ii = dec.poll_at(220).unwrap();
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Esc)));
+    assert!(dec.poll_at(300).is_none());
+}
+
+#[test]
+fn feed_slice_iter() {
+    let mut dec = Decoder::default();
+
+    let keys: Vec<_> = dec
+        .feed_slice(b"ab\x1B[A\x1B[B\x0D\x0A\x1B[1;")
+        .map(|ii| ii.name)
+        .collect();
+    assert_eq!(vec!["<Char>", "<Char>", "Up", "Down", "Enter"], keys);
+
+    // the rest of sequence comes
+    let keys: Vec<_> = dec.feed_slice(b"2C").map(|ii| ii.name).collect();
+    assert_eq!(vec!["S-Right"], keys);
+}
+
+#[test]
+fn feed_long_paste() {
+    let mut dec = Decoder::default();
+
+    // paste longer than the buffer is reported in parts, no byte is lost
+    let text = "x".repeat(100);
+    let mut pasted = String::new();
+    assert!(dec.feed_slice(b"\x1B[200~").next().is_none());
+    for ii in dec.feed_slice(text.as_bytes()) {
+        if let InputEvent::Paste(ref part) = ii.evnt {
+            pasted.push_str(part);
+        }
+        else {
+            panic!("paste event expected");
+        }
+    }
+
+    let events: Vec<_> = dec.feed_slice(b"\x1B[201~\x1B[B").collect();
+    for ii in events.iter().take(events.len() - 1) {
+        if let InputEvent::Paste(ref part) = ii.evnt {
+            pasted.push_str(part);
+        }
+        else {
+            panic!("paste event expected");
+        }
+    }
+    assert_eq!(text, pasted);
+    assert_eq!(Some("Down"), events.last().map(|ii| ii.name));
+}
+
 #[test]
 fn ctrl_s() {
     let mut dec = Decoder::default();
@@ -469,6 +721,384 @@ fn mouse_click_at_11() {
     assert_eq!(0, ii.kmod.mask);
 }
 
+#[test]
+fn mouse_drag_and_move() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // left button drag with Ctrl
+    inp.push_str("\x1B[MP%&");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    if let InputEvent::Mouse(ref m) = ii.evnt {
+        assert_eq!(MouseEvent::Drag(MouseButton::Left), m.evt);
+        assert_eq!(5, m.col);
+        assert_eq!(6, m.row);
+    }
+    else {
+        panic!("mouse event expected");
+    }
+    assert!(ii.kmod.has_ctrl());
+
+    inp.push_str("\x1B[MB!!");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(
+        ii.evnt,
+        InputEvent::Mouse(MouseInfo {
+            evt: MouseEvent::Drag(MouseButton::Right),
+            ..
+        })
+    ));
+
+    // motion without button
+    inp.push_str("\x1B[MC!!");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(
+        ii.evnt,
+        InputEvent::Mouse(MouseInfo {
+            evt: MouseEvent::Move,
+            ..
+        })
+    ));
+    assert_eq!(0, ii.kmod.mask);
+}
+
+#[test]
+fn mouse_sgr_press_release() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    inp.push_str("\x1B[<0;250;40M\x1B[<2;300;5mx");
+    assert_eq!(12, dec.decode_input_seq(&mut inp, &mut ii));
+    if let InputEvent::Mouse(ref m) = ii.evnt {
+        assert_eq!(MouseEvent::ButtonLeft, m.evt);
+        assert_eq!(Some(MouseButton::Left), m.btn);
+        assert_eq!(250, m.col);
+        assert_eq!(40, m.row);
+    }
+    else {
+        panic!("mouse event expected");
+    }
+
+    assert_eq!(11, dec.decode_input_seq(&mut inp, &mut ii));
+    if let InputEvent::Mouse(ref m) = ii.evnt {
+        assert_eq!(MouseEvent::ButtonReleased, m.evt);
+        assert_eq!(Some(MouseButton::Right), m.btn);
+        assert_eq!(300, m.col);
+        assert_eq!(5, m.row);
+        // beyond the widgets coordinates range
+        assert_eq!(None, m.coord());
+    }
+    else {
+        panic!("mouse event expected");
+    }
+
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::Char(_)));
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn mouse_sgr_wheel_drag_mods() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    inp.push_str("\x1B[<65;10;11M");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(
+        ii.evnt,
+        InputEvent::Mouse(MouseInfo {
+            evt: MouseEvent::WheelDown,
+            btn: None,
+            col: 10,
+            row: 11,
+        })
+    ));
+
+    // Ctrl + left button drag
+    inp.push_str("\x1B[<48;1000;1M");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(
+        ii.evnt,
+        InputEvent::Mouse(MouseInfo {
+            evt: MouseEvent::Drag(MouseButton::Left),
+            col: 1000,
+            ..
+        })
+    ));
+    assert!(ii.kmod.has_ctrl());
+    assert!(!ii.kmod.has_shift());
+}
+
+#[test]
+fn mouse_sgr_incomplete() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    inp.push_str("\x1B[<0;25");
+    assert_eq!(0, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::None));
+    assert_eq!(7, inp.len());
+
+    inp.push_str("0;4M");
+    assert_eq!(11, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(
+        ii.evnt,
+        InputEvent::Mouse(MouseInfo {
+            evt: MouseEvent::ButtonLeft,
+            col: 250,
+            row: 4,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn bracketed_paste() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    inp.push_str("\x1B[200~id:\t42\r\n\x1B[A\x1B[201~+");
+    assert_eq!(23, dec.decode_input_seq(&mut inp, &mut ii));
+    if let InputEvent::Paste(ref text) = ii.evnt {
+        assert_eq!("id:\t42\r\n\x1B[A", text);
+    }
+    else {
+        panic!("paste event expected");
+    }
+
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::Char(_)));
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn bracketed_paste_split() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // wait for the end marker
+    inp.push_str("\x1B[200~Lorem ");
+    assert_eq!(0, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::None));
+    inp.push_str("ipsum\x1B[20");
+    assert_eq!(0, dec.decode_input_seq(&mut inp, &mut ii));
+    inp.push_str("1~");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    if let InputEvent::Paste(ref text) = ii.evnt {
+        assert_eq!("Lorem ipsum", text);
+    }
+    else {
+        panic!("paste event expected");
+    }
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn bracketed_paste_timeout() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // the end marker is lost - the text is reported after the timeout
+    inp.push_str("\x1B[200~Lorem");
+    assert_eq!(0, dec.decode_input_seq_at(&mut inp, &mut ii, 100));
+    assert_eq!(
+        0,
+        dec.decode_input_seq_at(&mut inp, &mut ii, 100 + ESC_TIMEOUT_MS as u32 - 1)
+    );
+    assert_eq!(
+        11,
+        dec.decode_input_seq_at(&mut inp, &mut ii, 100 + ESC_TIMEOUT_MS as u32)
+    );
+    if let InputEvent::Paste(ref text) = ii.evnt {
+        assert_eq!("Lorem", text);
+    }
+    else {
+        panic!("paste event expected");
+    }
+    assert_eq!(0, inp.len());
+
+    // following keys are decoded as usual
+    inp.push_str("\x1B[A");
+    dec.decode_input_seq_at(&mut inp, &mut ii, 500);
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+}
+
+#[test]
+fn bracketed_paste_long() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // the text longer than the limit is reported in parts, without splitting the UTF-8 chars
+    let text = "ąb".repeat(PASTE_MAX_LENGTH);
+    inp.push_str("\x1B[200~");
+    inp.push_str(&text);
+
+    let mut pasted = String::new();
+    while dec.decode_input_seq(&mut inp, &mut ii) > 0 {
+        if let InputEvent::Paste(ref part) = ii.evnt {
+            assert!(part.len() <= PASTE_MAX_LENGTH);
+            pasted.push_str(part);
+        }
+        else {
+            panic!("paste event expected");
+        }
+    }
+    assert!(pasted.len() > PASTE_MAX_LENGTH);
+    assert!(text.starts_with(&pasted));
+
+    // the rest comes with the end marker, followed by a regular key
+    inp.push_str("\x1B[201~\x1B[A");
+    dec.decode_input_seq(&mut inp, &mut ii);
+    if let InputEvent::Paste(ref part) = ii.evnt {
+        pasted.push_str(part);
+        dec.decode_input_seq(&mut inp, &mut ii);
+    }
+    assert_eq!(text, pasted);
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn focus_in_out() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    inp.push_str("\x1B[O\x1B[I\x1BOP");
+    assert_eq!(3, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Focus(false)));
+    assert_eq!(3, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Focus(true)));
+
+    // SS3 sequence is not confused with the focus report
+    dec.decode_input_seq(&mut inp, &mut ii);
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::F1)));
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn csi_u_special_keys() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // Ctrl+Enter, Shift+Tab, Esc
+    inp.push_str("\x1B[13;5u\x1B[9;2u\x1B[27u");
+    assert_eq!(7, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Enter)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+    assert_eq!("C-Enter", ii.name);
+
+    assert_eq!(6, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Tab)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_SHIFT, ii.kmod.mask);
+
+    assert_eq!(5, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Esc)));
+    assert_eq!(KEY_MOD_SPECIAL, ii.kmod.mask);
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn csi_u_chars() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // Ctrl+1, Ctrl+a, Alt+ł, keypad Ctrl+5
+    inp.push_str("\x1B[49;5u\x1B[97;5u\x1B[322;3u\x1B[57404;5u");
+    assert_eq!(7, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Char(ref cb) if cb.as_str() == "1"));
+    assert_eq!(KEY_MOD_CTRL, ii.kmod.mask);
+
+    // compatible with the legacy Ctrl+A
+    assert_eq!(7, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Char(ref cb) if cb.as_str() == "A"));
+    assert_eq!(KEY_MOD_CTRL, ii.kmod.mask);
+    assert_eq!("C-A", ii.name);
+
+    assert_eq!(8, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Char(ref cb) if cb.as_str() == "ł"));
+    assert_eq!(KEY_MOD_ALT, ii.kmod.mask);
+
+    assert_eq!(10, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Char(ref cb) if cb.as_str() == "5"));
+    assert_eq!(KEY_MOD_CTRL, ii.kmod.mask);
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn csi_u_modifiers_fallback() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // Ctrl+Alt+Shift+F5 is not in the legacy map
+    inp.push_str("\x1B[15;8~\x1B[1;5:1A\x1B[1;5H");
+    assert_eq!(7, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::F5)));
+    assert_eq!(
+        KEY_MOD_SPECIAL | KEY_MOD_CTRL | KEY_MOD_ALT | KEY_MOD_SHIFT,
+        ii.kmod.mask
+    );
+    assert_eq!("F5", ii.name);
+
+    // explicit press event type
+    assert_eq!(8, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Up)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+
+    // legacy sequence decoded from the map
+    assert_eq!(6, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Home)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn csi_u_release_and_modifier_keys() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    // Ctrl+Enter released, sole left Shift pressed
+    inp.push_str("\x1B[13;5:3u\x1B[57441;2u");
+    assert_eq!(9, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::None));
+    assert_eq!("KeyRelease", ii.name);
+
+    assert_eq!(10, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::None));
+    assert_eq!(KEY_MOD_NONE, ii.kmod.mask);
+    assert_eq!(0, inp.len());
+}
+
+#[test]
+fn csi_u_incomplete() {
+    let mut dec = Decoder::default();
+    let mut inp = InputQue::new();
+    let mut ii = InputInfo::default();
+
+    inp.push_str("\x1B[57414;");
+    for _ in 0..4 {
+        assert_eq!(0, dec.decode_input_seq(&mut inp, &mut ii));
+    }
+    assert_eq!(8, inp.len());
+
+    inp.push_str("5u");
+    assert_eq!(10, dec.decode_input_seq(&mut inp, &mut ii));
+    assert!(matches!(ii.evnt, InputEvent::Key(Key::Enter)));
+    assert_eq!(KEY_MOD_SPECIAL | KEY_MOD_CTRL, ii.kmod.mask);
+}
+
 #[test]
 fn mouse_wheel_down() {
     let mut dec = Decoder::default();
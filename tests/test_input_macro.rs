@@ -0,0 +1,150 @@
+//! # RTWins input macro tests
+
+extern crate rtwins;
+use rtwins::input::*;
+use rtwins::input_macro::*;
+
+fn key(key: Key, kmod: u8) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Key(key),
+        kmod: KeyMod {
+            mask: kmod | KEY_MOD_SPECIAL,
+        },
+        ..Default::default()
+    }
+}
+
+fn chr(c: char, kmod: u8) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Char(CharBuff::from(c)),
+        kmod: KeyMod { mask: kmod },
+        ..Default::default()
+    }
+}
+
+fn mouse(evt: MouseEvent, btn: Option<MouseButton>, col: u16, row: u16) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Mouse(MouseInfo { evt, btn, col, row }),
+        ..Default::default()
+    }
+}
+
+fn same(a: &InputInfo, b: &InputInfo) -> bool {
+    format!("{:?}", a.evnt) == format!("{:?}", b.evnt) && a.kmod.mask == b.kmod.mask
+}
+
+#[test]
+fn record_and_decode() {
+    let inputs = vec![
+        chr('a', KEY_MOD_NONE),
+        chr('ł', KEY_MOD_NONE),
+        chr('A', KEY_MOD_CTRL),
+        chr('x', KEY_MOD_ALT),
+        key(Key::Up, KEY_MOD_NONE),
+        key(Key::Home, KEY_MOD_CTRL | KEY_MOD_SHIFT),
+        key(Key::F1, KEY_MOD_NONE),
+        key(Key::F12, KEY_MOD_ALT),
+        key(Key::Delete, KEY_MOD_NONE),
+        key(Key::Enter, KEY_MOD_NONE),
+        key(Key::Tab, KEY_MOD_SHIFT),
+        key(Key::Esc, KEY_MOD_NONE),
+        key(Key::Backspace, KEY_MOD_CTRL),
+        mouse(MouseEvent::ButtonLeft, Some(MouseButton::Left), 12, 300),
+        mouse(
+            MouseEvent::Drag(MouseButton::Left),
+            Some(MouseButton::Left),
+            13,
+            300,
+        ),
+        mouse(MouseEvent::ButtonReleased, Some(MouseButton::Left), 13, 301),
+        mouse(MouseEvent::ButtonReleased, None, 1, 1),
+        mouse(MouseEvent::WheelDown, None, 5, 6),
+        InputInfo {
+            evnt: InputEvent::Paste("multi\nline \\ text".into()),
+            ..Default::default()
+        },
+        InputInfo {
+            evnt: InputEvent::Focus(false),
+            ..Default::default()
+        },
+    ];
+
+    let mut rec = MacroRecorder::default();
+    // not recording yet
+    rec.record(&inputs[0], 0);
+
+    rec.start();
+    assert!(rec.is_recording());
+    for (i, ii) in inputs.iter().enumerate() {
+        rec.record(ii, 1000 + i as u32 * 10);
+    }
+    // ignored
+    rec.record(&InputInfo::default(), 5000);
+
+    let mcr = rec.stop();
+    assert!(!rec.is_recording());
+    assert_eq!(inputs.len(), mcr.steps.len());
+    assert_eq!(0, mcr.steps[0].delay_ms);
+    assert_eq!(10, mcr.steps[1].delay_ms);
+
+    let events = mcr.events();
+    assert_eq!(inputs.len(), events.len());
+    for (inp, (_, ev)) in inputs.iter().zip(events.iter()) {
+        assert!(same(inp, ev), "{:?} != {:?}", inp, ev);
+    }
+}
+
+#[test]
+fn text_form() {
+    let mut rec = MacroRecorder::default();
+    rec.start();
+    rec.record(&key(Key::Up, KEY_MOD_CTRL), 100);
+    rec.record(&chr('\\', KEY_MOD_NONE), 220);
+    rec.record(
+        &InputInfo {
+            evnt: InputEvent::Paste("a\tb\r\n".into()),
+            ..Default::default()
+        },
+        250,
+    );
+    let mcr = rec.stop();
+
+    let text = mcr.to_text();
+    assert_eq!(
+        "0 \\e[1;5A\n120 \\\\\n30 \\e[200~a\\tb\\r\\n\\e[201~\n",
+        text
+    );
+    assert_eq!(Some(mcr), InputMacro::from_text(&text));
+
+    // comments, empty lines and escaped control characters
+    let mcr = InputMacro::from_text("# header\n\n5 \\x1B[B\n").unwrap();
+    assert_eq!(1, mcr.steps.len());
+    assert_eq!(5, mcr.steps[0].delay_ms);
+    assert_eq!("\x1B[B", mcr.steps[0].seq);
+
+    // malformed
+    assert!(InputMacro::from_text("abc \\e[A").is_none());
+    assert!(InputMacro::from_text("10").is_none());
+    assert!(InputMacro::from_text("10 \\q").is_none());
+}
+
+#[test]
+fn playback_timing() {
+    let mcr = InputMacro::from_text("0 a\n100 b\n50 \\e[A\n").unwrap();
+    let mut player = MacroPlayer::new(&mcr);
+
+    let ii = player.poll(1000).unwrap();
+    assert!(same(&ii, &chr('a', KEY_MOD_NONE)));
+    assert!(player.poll(1099).is_none());
+
+    // late poll - next delay counted from the planned time
+    let ii = player.poll(1120).unwrap();
+    assert!(same(&ii, &chr('b', KEY_MOD_NONE)));
+    assert!(player.poll(1149).is_none());
+    assert!(!player.is_finished());
+
+    let ii = player.poll(1150).unwrap();
+    assert!(same(&ii, &key(Key::Up, KEY_MOD_NONE)));
+    assert!(player.is_finished());
+    assert!(player.poll(5000).is_none());
+}
@@ -0,0 +1,115 @@
+//! # RTWins TextEdit input mask tests
+
+extern crate rtwins;
+use rtwins::input::*;
+use rtwins::input_mask::*;
+use rtwins::wgt::*;
+
+mod common;
+use common::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn apply_mask() {
+    let mask = InputMask::new("99:99:99");
+    assert_eq!(8, mask.len());
+    assert_eq!("__:__:__", mask.apply(""));
+    assert_eq!("12:34:56", mask.apply("123456"));
+    assert_eq!("12:34:56", mask.apply("12:34:56"));
+    assert_eq!("12:3_:__", mask.apply("12:3"));
+    // placeholder left empty before the literal
+    assert_eq!("9_:30:__", mask.apply("9:30"));
+    // not accepted characters skipped
+    assert_eq!("12:34:__", mask.apply("1a2b34"));
+
+    let mask = InputMask::new("HH-HH-HH-HH-HH-HH");
+    assert_eq!("01-aB-__-__-__-__", mask.apply("01aB"));
+
+    // escaped placeholder character
+    let mask = InputMask::new("\\9-AA");
+    assert_eq!("9-xy", mask.apply("xy"));
+    assert_eq!("9-xy", mask.apply("9-xy"));
+}
+
+#[test]
+fn complete() {
+    let mask = InputMask::new("9999-99-99");
+    assert!(mask.is_complete("2024-01-31"));
+    assert!(!mask.is_complete("2024-01-3_"));
+    assert!(!mask.is_complete("2024/01/31"));
+    assert!(!mask.is_complete("2024-01-31 "));
+}
+
+#[test]
+fn cursor_positions() {
+    let mask = InputMask::new("99:99");
+    assert_eq!(0, mask.next_pos(0));
+    assert_eq!(3, mask.next_pos(2));
+    assert_eq!(5, mask.next_pos(5));
+    assert_eq!(Some(1), mask.prev_pos(3));
+    assert_eq!(None, mask.prev_pos(0));
+    assert_eq!(3, mask.first_empty("12:__"));
+    assert_eq!(5, mask.first_empty("12:34"));
+
+    let mut txt = mask.apply("");
+    assert_eq!(Some(1), mask.put_char(&mut txt, 0, '1'));
+    assert_eq!(Some(3), mask.put_char(&mut txt, 1, '2'));
+    assert_eq!(None, mask.put_char(&mut txt, 3, 'x'));
+    // put at the literal goes to the next placeholder
+    assert_eq!(Some(4), mask.put_char(&mut txt, 2, '3'));
+    assert_eq!("12:3_", txt);
+
+    mask.clear(&mut txt, 0, 5);
+    assert_eq!("__:__", txt);
+}
+
+#[test]
+fn text_edit_with_mask() {
+    let _lock = lock();
+    let mut ws = MainState {
+        time: "9:30".into(),
+        ..MainState::new(id::EDT_TIME)
+    };
+
+    // enter edit mode, cursor at first empty placeholder
+    process_input(&mut ws, &key(Key::Enter));
+    // letter rejected
+    process_input(&mut ws, &chr('x'));
+    process_input(&mut ws, &chr('5'));
+    // cursor already after the literal
+    process_input(&mut ws, &chr(':'));
+    process_input(&mut ws, &chr('4'));
+    // incomplete text is not committed
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("9:30", ws.time);
+    assert_eq!(Some("Incomplete input"), *ws.errors.last().unwrap());
+    process_input(&mut ws, &chr('0'));
+    process_input(&mut ws, &chr('0'));
+    process_input(&mut ws, &chr('0'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("95:40:00", ws.time);
+
+    // fix the hour
+    ws.time = "09:30:45".into();
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &key(Key::Home));
+    process_input(&mut ws, &chr('1'));
+    process_input(&mut ws, &chr('0'));
+    // cursor skipped the literal
+    process_input(&mut ws, &key(Key::Delete));
+    process_input(&mut ws, &key(Key::End));
+    process_input(&mut ws, &key(Key::Backspace));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("09:30:45", ws.time);
+    // placeholders cleared by Delete and Backspace
+    process_input(&mut ws, &key(Key::Left));
+    process_input(&mut ws, &key(Key::Left));
+    process_input(&mut ws, &key(Key::Left));
+    process_input(&mut ws, &chr('2'));
+    process_input(&mut ws, &key(Key::End));
+    process_input(&mut ws, &key(Key::Left));
+    process_input(&mut ws, &chr('9'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("10:20:49", ws.time);
+}
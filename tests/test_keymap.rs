@@ -0,0 +1,328 @@
+//! # RTWins key bindings tests
+
+extern crate rtwins;
+use rtwins::colors::*;
+use rtwins::common::*;
+use rtwins::input::*;
+use rtwins::keymap::*;
+use rtwins::wgt::*;
+
+use std::sync::Mutex;
+
+// ---------------------------------------------------------------------------------------------- //
+
+mod id {
+    use rtwins::wgt::{WId, WIDGET_ID_NONE};
+
+    #[rustfmt::skip]
+    rtwins::generate_ids!(
+        WND_MAIN
+            LBX_ITEMS
+            BTN_OK
+            EDT_NAME
+            CBX_MODE
+    );
+}
+
+#[rustfmt::skip]
+const WINDOW_MAIN: Widget = Widget {
+    id: id::WND_MAIN,
+    link: Link::cdeflt(),
+    coord: Coord { col: 1, row: 1 },
+    size: Size { width: 40, height: 12 },
+    prop: prop::Window {
+        title: "Main",
+        fg_color: ColorFg::White,
+        bg_color: ColorBg::Blue,
+        is_popup: false,
+    }.into(),
+    children: &[
+        Widget {
+            id: id::LBX_ITEMS,
+            coord: Coord { col: 2, row: 2 },
+            size: Size { width: 20, height: 6 },
+            prop: prop::ListBox {
+                fg_color: ColorFg::White,
+                bg_color: ColorBg::Black,
+                no_frame: false,
+            }.into(),
+            ..Widget::cdeflt()
+        },
+        Widget {
+            id: id::BTN_OK,
+            coord: Coord { col: 2, row: 9 },
+            prop: prop::Button {
+                text: "OK",
+                fg_color: ColorFg::White,
+                bg_color: ColorBg::Green,
+                style: ButtonStyle::Simple,
+            }.into(),
+            ..Widget::cdeflt()
+        },
+        Widget {
+            id: id::EDT_NAME,
+            coord: Coord { col: 10, row: 9 },
+            size: Size { width: 20, height: 1 },
+            prop: prop::TextEdit {
+                fg_color: ColorFg::White,
+                bg_color: ColorBg::Black,
+                psw_mask: false,
+                input_mask: "",
+            }.into(),
+            ..Widget::cdeflt()
+        },
+        Widget {
+            id: id::CBX_MODE,
+            coord: Coord { col: 2, row: 10 },
+            size: Size { width: 10, height: 1 },
+            prop: prop::ComboBox {
+                fg_color: ColorFg::White,
+                bg_color: ColorBg::Black,
+                drop_down_size: 3,
+            }.into(),
+            ..Widget::cdeflt()
+        },
+    ]
+};
+
+const WND_MAIN_WGTS: [Widget; transform::tree_wgt_count(&WINDOW_MAIN)] =
+    transform::tree_to_array(&WINDOW_MAIN);
+
+#[derive(Default)]
+struct MainState {
+    focused_id: WId,
+    lbx_sel_idx: i16,
+    name: String,
+    cbx_drop_down: bool,
+}
+
+impl WindowState for MainState {
+    fn on_list_box_select(&mut self, _wgt: &Widget, new_sel_idx: i16) {
+        self.lbx_sel_idx = new_sel_idx;
+    }
+
+    fn get_list_box_state(&mut self, _wgt: &Widget, out: &mut rstate::LbxState) {
+        out.items_cnt = 5;
+        out.sel_idx = self.lbx_sel_idx;
+    }
+
+    fn on_combo_box_drop(&mut self, _wgt: &Widget, drop_state: bool) {
+        self.cbx_drop_down = drop_state;
+    }
+
+    fn get_combo_box_state(&mut self, _wgt: &Widget, out: &mut rstate::CbbxState) {
+        out.items_cnt = 3;
+        out.drop_down = self.cbx_drop_down;
+    }
+
+    fn on_text_edit_change(&mut self, _wgt: &Widget, txt: &mut String) {
+        self.name = txt.clone();
+    }
+
+    fn get_text_edit_text(&mut self, _wgt: &Widget, out: &mut String, _edit_mode: bool) {
+        out.push_str(&self.name);
+    }
+
+    fn get_focused_id(&mut self) -> WId {
+        self.focused_id
+    }
+
+    fn set_focused_id(&mut self, wid: WId) {
+        self.focused_id = wid;
+    }
+
+    fn get_widgets(&self) -> &'static [Widget] {
+        &WND_MAIN_WGTS
+    }
+
+    fn get_window_coord(&mut self) -> Coord {
+        WND_MAIN_WGTS[0].coord
+    }
+}
+
+/// Tests changing the global `KEYMAP`
+static KEYMAP_LOCK: Mutex<()> = Mutex::new(());
+
+fn key(key: Key, kmod: u8) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Key(key),
+        kmod: KeyMod {
+            mask: kmod | KEY_MOD_SPECIAL,
+        },
+        ..Default::default()
+    }
+}
+
+fn chr(c: char, kmod: u8) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Char(CharBuff::from(c)),
+        kmod: KeyMod { mask: kmod },
+        ..Default::default()
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn default_bindings() {
+    let km = Keymap::default();
+
+    assert_eq!(
+        Some(Action::FocusNext),
+        km.action(&key(Key::Tab, KEY_MOD_NONE))
+    );
+    assert_eq!(
+        Some(Action::FocusPrev),
+        km.action(&key(Key::Tab, KEY_MOD_SHIFT))
+    );
+    assert_eq!(
+        Some(Action::Cancel),
+        km.action(&key(Key::Esc, KEY_MOD_NONE))
+    );
+    assert_eq!(Some(Action::Toggle), km.action(&chr(' ', KEY_MOD_NONE)));
+    assert_eq!(
+        Some(Action::NextPage),
+        km.action(&key(Key::PgDown, KEY_MOD_CTRL))
+    );
+    assert_eq!(
+        Some(Action::DeleteToStart),
+        km.action(&key(Key::Backspace, KEY_MOD_CTRL))
+    );
+    assert_eq!(Some(Action::Copy), km.action(&chr('C', KEY_MOD_CTRL)));
+    assert_eq!(
+        Some(Action::Paste),
+        km.action(&key(Key::Insert, KEY_MOD_SHIFT))
+    );
+    assert_eq!(Some(Action::Find), km.action(&chr('/', KEY_MOD_NONE)));
+    assert_eq!(
+        Some(Action::FindPrev),
+        km.action(&key(Key::F3, KEY_MOD_SHIFT))
+    );
+    // modifiers must match exactly
+    assert_eq!(None, km.action(&key(Key::Enter, KEY_MOD_ALT)));
+    assert_eq!(None, km.action(&chr('j', KEY_MOD_NONE)));
+    // not a key event
+    assert_eq!(None, km.action(&InputInfo::default()));
+
+    assert_eq!(None, Keymap::empty().action(&key(Key::Tab, KEY_MOD_NONE)));
+}
+
+#[test]
+fn bind_unbind() {
+    let mut km = Keymap::default();
+
+    // rebinding replaces the previous action
+    km.bind(KeyCode::Key(Key::Down), KEY_MOD_NONE, Action::FocusNext);
+    assert_eq!(
+        Some(Action::FocusNext),
+        km.action(&key(Key::Down, KEY_MOD_NONE))
+    );
+
+    km.bind(KeyCode::Char('j'), KEY_MOD_NONE, Action::LineDown);
+    assert_eq!(Some(Action::LineDown), km.action(&chr('j', KEY_MOD_NONE)));
+    assert_eq!(None, km.action(&chr('j', KEY_MOD_CTRL)));
+
+    km.unbind(KeyCode::Char('j'), KEY_MOD_NONE);
+    assert_eq!(None, km.action(&chr('j', KEY_MOD_NONE)));
+
+    km.unbind_action(Action::FocusNext);
+    assert_eq!(None, km.action(&key(Key::Tab, KEY_MOD_NONE)));
+    assert_eq!(None, km.action(&key(Key::Down, KEY_MOD_NONE)));
+    assert_eq!(
+        Some(Action::FocusPrev),
+        km.action(&key(Key::Tab, KEY_MOD_SHIFT))
+    );
+}
+
+#[test]
+fn custom_bindings_drive_widgets() {
+    let _lock = KEYMAP_LOCK.lock().unwrap();
+    let mut ws = MainState {
+        focused_id: id::LBX_ITEMS,
+        ..Default::default()
+    };
+
+    // default map: `j` is not handled by the list box
+    process_input(&mut ws, &chr('j', KEY_MOD_NONE));
+    assert_eq!(0, ws.lbx_sel_idx);
+    process_input(&mut ws, &key(Key::Down, KEY_MOD_NONE));
+    assert_eq!(1, ws.lbx_sel_idx);
+
+    {
+        let mut km = KEYMAP.try_lock().unwrap();
+        km.bind(KeyCode::Char('j'), KEY_MOD_NONE, Action::LineDown);
+        km.bind(KeyCode::Char('k'), KEY_MOD_NONE, Action::LineUp);
+        km.bind(KeyCode::Key(Key::Down), KEY_MOD_NONE, Action::FocusNext);
+    }
+
+    process_input(&mut ws, &chr('j', KEY_MOD_NONE));
+    process_input(&mut ws, &chr('j', KEY_MOD_NONE));
+    assert_eq!(3, ws.lbx_sel_idx);
+    process_input(&mut ws, &chr('k', KEY_MOD_NONE));
+    assert_eq!(2, ws.lbx_sel_idx);
+
+    // Down moves the focus instead of the selection
+    process_input(&mut ws, &key(Key::Down, KEY_MOD_NONE));
+    assert_eq!(2, ws.lbx_sel_idx);
+    assert_eq!(id::BTN_OK, ws.focused_id);
+
+    *KEYMAP.try_lock().unwrap() = Keymap::default();
+}
+
+#[test]
+fn char_bound_edit_actions() {
+    let _lock = KEYMAP_LOCK.lock().unwrap();
+    let mut ws = MainState {
+        focused_id: id::EDT_NAME,
+        name: "abc".into(),
+        ..Default::default()
+    };
+
+    {
+        let mut km = KEYMAP.try_lock().unwrap();
+        km.bind(KeyCode::Char('H'), KEY_MOD_CTRL, Action::DeleteBack);
+        km.bind(KeyCode::Char('B'), KEY_MOD_CTRL, Action::CursorLeft);
+    }
+
+    // actions bound to characters are applied in the edit mode
+    process_input(&mut ws, &key(Key::Enter, KEY_MOD_NONE));
+    process_input(&mut ws, &chr('H', KEY_MOD_CTRL));
+    process_input(&mut ws, &chr('B', KEY_MOD_CTRL));
+    process_input(&mut ws, &chr('x', KEY_MOD_NONE));
+    // other characters bound to actions are typed
+    process_input(&mut ws, &chr('n', KEY_MOD_NONE));
+    process_input(&mut ws, &key(Key::Enter, KEY_MOD_NONE));
+    assert_eq!("axnb", ws.name);
+
+    *KEYMAP.try_lock().unwrap() = Keymap::default();
+}
+
+#[test]
+fn combo_box_drop_down_chars() {
+    let _lock = KEYMAP_LOCK.lock().unwrap();
+    let mut ws = MainState {
+        focused_id: id::CBX_MODE,
+        ..Default::default()
+    };
+
+    process_input(&mut ws, &chr(' ', KEY_MOD_NONE));
+    assert!(ws.cbx_drop_down);
+
+    // characters bound to the actions not used by the combo box
+    process_input(&mut ws, &chr('n', KEY_MOD_NONE));
+    process_input(&mut ws, &chr('N', KEY_MOD_NONE));
+    process_input(&mut ws, &chr('/', KEY_MOD_NONE));
+    assert!(ws.cbx_drop_down);
+    assert_eq!(id::CBX_MODE, ws.focused_id);
+
+    // the focus change closes the drop-down
+    KEYMAP
+        .try_lock()
+        .unwrap()
+        .bind(KeyCode::Char('j'), KEY_MOD_NONE, Action::FocusNext);
+    process_input(&mut ws, &chr('j', KEY_MOD_NONE));
+    assert!(!ws.cbx_drop_down);
+    assert_ne!(id::CBX_MODE, ws.focused_id);
+
+    *KEYMAP.try_lock().unwrap() = Keymap::default();
+}
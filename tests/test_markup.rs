@@ -0,0 +1,199 @@
+//! # RTWins rich text markup tests
+
+extern crate rtwins;
+use rtwins::colors::*;
+use rtwins::esc;
+use rtwins::input::*;
+use rtwins::markup::{self, Span, Style};
+use rtwins::string_ext::StrExt;
+use rtwins::wgt::*;
+
+mod common;
+use common::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn markup_parse() {
+    assert!(markup::parse("").is_empty());
+
+    let bold = Style {
+        bold: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        vec![
+            Span {
+                text: "a ",
+                style: Style::default()
+            },
+            Span {
+                text: "bold",
+                style: bold
+            },
+            Span {
+                text: "red",
+                style: Style {
+                    fg: Some(ColorFg::Red),
+                    ..bold
+                }
+            },
+            Span {
+                text: " b",
+                style: Style::default()
+            },
+        ],
+        markup::parse("a [b]bold[fg=red]red[/][/b] b")
+    );
+
+    // closing tag restores the style from before it was opened
+    let spans = markup::parse("[u][i]x[/u]y");
+    assert_eq!(2, spans.len());
+    assert!(spans[0].style.underline && spans[0].style.italics);
+    assert_eq!(Style::default(), spans[1].style);
+}
+
+#[test]
+fn markup_literals() {
+    // escaped bracket, unknown, unmatched and malformed tags are kept
+    assert_eq!("[b] x", markup::strip("[[b] x"));
+    assert_eq!("[x]y[/b]", markup::strip("[x]y[/b]"));
+    assert_eq!("[fg=pink]a", markup::strip("[fg=pink]a"));
+    assert_eq!("[b=1]a[b", markup::strip("[b=1]a[b"));
+    assert_eq!("a [i] b", markup::strip("a [[i] [u]b"));
+    assert_eq!(5, markup::displayed_width("[b]ab[/b][fg=blue]c😁[/]"));
+}
+
+#[test]
+fn markup_to_esc() {
+    assert_eq!(
+        "plain",
+        markup::to_esc("plain", ColorFg::White, ColorBg::Blue)
+    );
+    assert_eq!(
+        format!("{}B{}", esc::BOLD, esc::NORMAL),
+        markup::to_esc("[b]B[/b]", ColorFg::White, ColorBg::Blue)
+    );
+
+    // widget colors restored
+    assert_eq!(
+        format!(
+            "{}{}x{}{}",
+            ColorFg::Red.encode(),
+            ColorBg::YellowIntense.encode(),
+            ColorFg::White.encode(),
+            ColorBg::Blue.encode()
+        ),
+        markup::to_esc(
+            "[fg=red][bg=yellow_intense]x",
+            ColorFg::White,
+            ColorBg::Blue
+        )
+    );
+
+    let txt = markup::to_esc("[u]a[s]b[/s][/u] [r]c", ColorFg::White, ColorBg::Blue);
+    assert_eq!(4, txt.as_str().displayed_width());
+    assert_eq!("ab c", txt.strip_esc());
+}
+
+#[test]
+fn markup_word_wrap() {
+    let lines = markup::word_wrap(
+        6,
+        "[b]one two[/b] three\nfour",
+        ColorFg::White,
+        ColorBg::Blue,
+    );
+    let lines = lines.borrow();
+
+    // each line has own style
+    assert_eq!(
+        vec![
+            format!("{}one {}", esc::BOLD, esc::NORMAL),
+            format!("{}two{} ", esc::BOLD, esc::NORMAL),
+            "three".to_string(),
+            "four".to_string(),
+        ],
+        *lines
+    );
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+fn notes_state() -> MainState {
+    MainState::with_lines(id::TBX_NOTES, &["[fg=red]note[/] ok"])
+}
+
+#[test]
+fn markup_label() {
+    let _lock = lock();
+    let out = capture_output();
+    let mut ws = notes_state();
+
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    {
+        let out = out.lock().unwrap();
+        assert!(out.contains(&format!(
+            "{}Hi{} {}ther…",
+            esc::BOLD,
+            esc::NORMAL,
+            esc::UNDERLINE_ON
+        )));
+        // attributes of the truncated text restored
+        assert!(out.contains(&format!(
+            "ther…{}{}{}{}",
+            esc::BOLD,
+            esc::NORMAL,
+            esc::UNDERLINE_ON,
+            esc::UNDERLINE_OFF
+        )));
+        assert!(out.contains("[ok]"));
+        assert!(!out.contains("[b]"));
+    }
+
+    // bold not applied to the faint text
+    out.lock().unwrap().clear();
+    ws.label_disabled = true;
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    {
+        let out = out.lock().unwrap();
+        assert!(out.contains(&format!("Hi {}ther…", esc::UNDERLINE_ON)));
+        assert!(!out.contains(&format!("{}Hi", esc::BOLD)));
+    }
+}
+
+#[test]
+fn markup_text_box_inherited_colors() {
+    let _lock = lock();
+    let out = capture_output();
+    let mut ws = notes_state();
+
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    let out = out.lock().unwrap();
+    // the window colors are restored after the color tag
+    assert!(out.contains(&format!("note{} ok", ColorFg::White.encode())));
+}
+
+#[test]
+fn markup_text_box_search_highlight() {
+    let _lock = lock();
+    let out = capture_output();
+    let mut ws = notes_state();
+
+    for c in "/no".chars() {
+        process_input(&mut ws, &chr(c));
+    }
+
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    // the markup color is restored after the match
+    assert!(out.lock().unwrap().contains(&format!(
+        "no{}{}{}te",
+        ColorFg::White.encode(),
+        ColorBg::Blue.encode(),
+        ColorFg::Red.encode()
+    )));
+
+    // close the prompt and clear the search
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &key(Key::Esc));
+}
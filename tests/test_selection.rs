@@ -0,0 +1,152 @@
+//! # RTWins text selection tests
+
+extern crate rtwins;
+use rtwins::esc;
+use rtwins::input::*;
+use rtwins::wgt::*;
+
+mod common;
+use common::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+fn mouse(evt: MouseEvent, col: u16, row: u16) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Mouse(MouseInfo {
+            evt,
+            btn: Some(MouseButton::Left),
+            col,
+            row,
+        }),
+        ..Default::default()
+    }
+}
+
+fn click(ws: &mut MainState, col: u16, row: u16) {
+    process_input(ws, &mouse(MouseEvent::ButtonLeft, col, row));
+    process_input(ws, &mouse(MouseEvent::ButtonReleased, col, row));
+}
+
+fn drag(ws: &mut MainState, col: u16, row: u16, to_col: u16, to_row: u16) {
+    process_input(ws, &mouse(MouseEvent::ButtonLeft, col, row));
+    process_input(
+        ws,
+        &mouse(MouseEvent::Drag(MouseButton::Left), to_col, to_row),
+    );
+    process_input(ws, &mouse(MouseEvent::ButtonReleased, to_col, to_row));
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn text_edit_selection() {
+    let _lock = lock();
+    let mut ws = MainState {
+        name: "hello big world".into(),
+        ..MainState::new(id::EDT_NAME)
+    };
+
+    // select the last word and replace it
+    process_input(&mut ws, &key(Key::Enter));
+    for _ in 0..5 {
+        process_input(&mut ws, &key_mod(Key::Left, KEY_MOD_SHIFT));
+    }
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("world", clipboard_get());
+    process_input(&mut ws, &chr('X'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("hello big X", ws.name);
+
+    // selection to the end deleted
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &key(Key::Home));
+    process_input(&mut ws, &key(Key::Right));
+    process_input(&mut ws, &key_mod(Key::End, KEY_MOD_SHIFT));
+    process_input(&mut ws, &key(Key::Delete));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("h", ws.name);
+
+    // double-click selects the word, entering the edit mode;
+    // the text starts at column 3
+    ws.name = "hello big world".into();
+    ws.focused_id = id::TBX_LOG;
+    click(&mut ws, 11, 3);
+    assert_eq!(id::EDT_NAME, ws.focused_id);
+    click(&mut ws, 11, 3);
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("big", clipboard_get());
+
+    // mouse drag selects the text
+    drag(&mut ws, 3, 3, 8, 3);
+    process_input(&mut ws, &ctrl('X'));
+    assert_eq!("hello", clipboard_get());
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!(" big world", ws.name);
+}
+
+#[test]
+fn text_box_selection() {
+    let _lock = lock();
+    let out = capture_output();
+    let mut ws = MainState::new(id::TBX_LOG);
+
+    {
+        let mut lines = ws.lines.borrow_mut();
+        for i in 0..10 {
+            lines.push(format!("line {i}"));
+        }
+        lines[1] = format!("{}line 1{}", esc::BOLD, esc::NORMAL);
+    }
+
+    // 3 lines visible; starts at the first visible line
+    process_input(&mut ws, &key_mod(Key::Down, KEY_MOD_SHIFT));
+    for _ in 0..3 {
+        process_input(&mut ws, &key_mod(Key::Down, KEY_MOD_SHIFT));
+    }
+    assert_eq!(1, ws.top_line);
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("line 0\nline 1\nline 2\nline 3", clipboard_get());
+
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    assert!(out
+        .lock()
+        .unwrap()
+        .contains(&format!("{}line 3", esc::INVERSE_ON)));
+
+    // Esc clears the selection, then everything is copied
+    process_input(&mut ws, &key(Key::Esc));
+    assert_eq!(id::TBX_LOG, ws.focused_id);
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!(10, clipboard_get().lines().count());
+
+    // from the first visible line to the end
+    process_input(&mut ws, &key_mod(Key::End, KEY_MOD_SHIFT));
+    assert_eq!(7, ws.top_line);
+    process_input(&mut ws, &ctrl('C'));
+    assert!(clipboard_get().starts_with("line 1\nline 2"));
+    assert!(clipboard_get().ends_with("line 9"));
+
+    // box content starts at row 6; double-click selects the line
+    click(&mut ws, 5, 7);
+    click(&mut ws, 5, 7);
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("line 8", clipboard_get());
+
+    // dragged below the box, scrolling is limited
+    drag(&mut ws, 5, 6, 5, 12);
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("line 7\nline 8\nline 9", clipboard_get());
+
+    // dragged above the box scrolls up
+    ws.top_line = 3;
+    drag(&mut ws, 5, 7, 5, 2);
+    assert_eq!(2, ws.top_line);
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("line 2\nline 3\nline 4", clipboard_get());
+
+    // selection cleared when the focus leaves
+    process_input(&mut ws, &key(Key::Tab));
+    ws.focused_id = id::TBX_LOG;
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!(10, clipboard_get().lines().count());
+}
@@ -49,6 +49,29 @@ fn displayed_width() {
     assert_eq!(9, "Multi\nLine".displayed_width());
 }
 
+#[test]
+fn strip_esc() {
+    assert_eq!("", "".strip_esc());
+    assert_eq!("Title", "Title".strip_esc());
+    assert_eq!(
+        "Title ąę",
+        format!("{}Title{} ąę{}", esc::BOLD, esc::NORMAL, esc::FG_CRIMSON).strip_esc()
+    );
+}
+
+#[test]
+fn skip_displayed_cols() {
+    assert_eq!("", "".skip_displayed_cols(3));
+    assert_eq!("tle", "Title".skip_displayed_cols(2));
+    assert_eq!("", "Title".skip_displayed_cols(10));
+    assert_eq!(
+        format!("{}{}le{}", esc::BOLD, esc::NORMAL, esc::FG_CRIMSON),
+        format!("{}Tit{}le{}", esc::BOLD, esc::NORMAL, esc::FG_CRIMSON).skip_displayed_cols(3)
+    );
+    // wide character cut in half
+    assert_eq!(" b", "a界b".skip_displayed_cols(2));
+}
+
 #[test]
 fn str_stream() {
     let mut s = String::from("Hello");
@@ -159,7 +182,7 @@ fn set_displayed_width_with_esc() {
         s.push_str(esc::BOLD);
         s.push_str("Item");
         s.push_str(esc::NORMAL);
-        s.push_str("#");
+        s.push('#');
 
         let mut s_exppected = s.clone();
         s_exppected.push_str("  ");
@@ -192,7 +215,7 @@ fn set_displayed_width_with_esc() {
         s.push_str(esc::NORMAL);
         s.set_displayed_width(1);
 
-        s_exppected.push_str("…");
+        s_exppected.push('…');
         assert_eq!(s_exppected, s);
     }
 }
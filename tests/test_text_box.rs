@@ -0,0 +1,199 @@
+//! # RTWins TextBox word-wrap, horizontal scrolling and search tests
+
+extern crate rtwins;
+use rtwins::colors::*;
+use rtwins::input::*;
+use rtwins::utils::StringListRc;
+use rtwins::wgt::*;
+
+mod common;
+use common::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn text_box_word_wrap() {
+    let _lock = lock();
+    let mut ws = MainState::with_lines(id::TBX_WRAP, &["alpha beta gamma", "", "delta"]);
+
+    // wrapped into 4 lines, 2 visible
+    process_input(&mut ws, &key(Key::PgDown));
+    assert_eq!(2, ws.top_line);
+
+    // wrapped parts copied as one line
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("alpha beta gamma\n\ndelta", clipboard_get());
+
+    ws.top_line = 0;
+    process_input(&mut ws, &key_mod(Key::Down, KEY_MOD_SHIFT));
+    process_input(&mut ws, &key_mod(Key::Down, KEY_MOD_SHIFT));
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("alpha beta gamma", clipboard_get());
+    process_input(&mut ws, &key(Key::Esc));
+
+    // no horizontal scrolling
+    process_input(&mut ws, &key(Key::Right));
+    assert_eq!(0, ws.left_col);
+}
+
+#[test]
+fn text_box_word_wrap_changed_lines() {
+    let _lock = lock();
+    let mut ws = MainState::with_lines(id::TBX_WRAP, &["alpha", "beta"]);
+
+    process_input(&mut ws, &key(Key::PgDown));
+    assert_eq!(0, ws.top_line);
+
+    // lines changed in place are wrapped again; the word wider than the box is broken
+    *ws.lines.borrow_mut() = vec!["abcdefghijklmnopqrstuvwxyz".into(), "end".into()];
+    ws.lines_generation += 1;
+    process_input(&mut ws, &key(Key::PgDown));
+    assert_eq!(2, ws.top_line);
+
+    // the lines count changed
+    ws.lines.borrow_mut().push("more".into());
+    process_input(&mut ws, &key(Key::PgDown));
+    assert_eq!(3, ws.top_line);
+
+    // the new list
+    ws.lines = StringListRc::default();
+    ws.lines.borrow_mut().push("one".into());
+    ws.top_line = 0;
+    process_input(&mut ws, &key(Key::PgDown));
+    assert_eq!(0, ws.top_line);
+}
+
+#[test]
+fn text_box_scroll_horizontal() {
+    let _lock = lock();
+    let out = capture_output();
+    let mut ws = MainState::with_lines(id::TBX_SCROLL, &["0123456789abcdefghij", "short"]);
+
+    // 10 columns visible
+    for _ in 0..3 {
+        process_input(&mut ws, &key(Key::Right));
+    }
+    assert_eq!(3, ws.left_col);
+    for _ in 0..20 {
+        process_input(&mut ws, &key(Key::Right));
+    }
+    assert_eq!(10, ws.left_col);
+    assert_eq!(id::TBX_SCROLL, ws.focused_id);
+
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    {
+        let out = out.lock().unwrap();
+        assert!(out.contains("abcdefghij"));
+        assert!(!out.contains("0123"));
+        // horizontal scroll bar
+        assert!(out.contains("▒▒▒▒▒▒▒▒▒◘"));
+    }
+
+    process_input(&mut ws, &key(Key::Left));
+    assert_eq!(9, ws.left_col);
+
+    // copied text is not scrolled
+    process_input(&mut ws, &ctrl('C'));
+    assert_eq!("0123456789abcdefghij\nshort", clipboard_get());
+
+    // lines fitting the widget are not scrolled
+    ws.lines.borrow_mut()[0] = "fits".into();
+    ws.left_col = 0;
+    process_input(&mut ws, &key(Key::Right));
+    assert_eq!(0, ws.left_col);
+}
+
+#[test]
+fn text_box_search() {
+    let _lock = lock();
+    let out = capture_output();
+    let mut ws = MainState::new(id::TBX_SCROLL);
+    {
+        let mut lines = ws.lines.borrow_mut();
+        for i in 0..10 {
+            lines.push(format!("line {i}"));
+        }
+        lines[3] = "ERR 42".into();
+        lines[7] = "ok, ERR 42".into();
+        lines[8] = "err 42".into();
+    }
+
+    // n is not used until something is searched
+    process_input(&mut ws, &chr('n'));
+    assert_eq!(0, ws.top_line);
+
+    // found while typing; 2 lines visible
+    process_input(&mut ws, &chr('/'));
+    for c in "ERR".chars() {
+        process_input(&mut ws, &chr(c));
+    }
+    assert_eq!(3, ws.top_line);
+
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    assert!(out.lock().unwrap().contains("/ERR"));
+    process_input(&mut ws, &key(Key::Enter));
+
+    // letters case ignored, wraps around
+    process_input(&mut ws, &chr('n'));
+    assert_eq!(7, ws.top_line);
+    process_input(&mut ws, &key(Key::F3));
+    assert_eq!(7, ws.top_line);
+    process_input(&mut ws, &chr('n'));
+    assert_eq!(3, ws.top_line);
+    process_input(&mut ws, &chr('N'));
+    assert_eq!(8, ws.top_line);
+
+    // matches highlighted
+    out.lock().unwrap().clear();
+    rtwins::TERM.try_lock().unwrap().draw_wnd(&mut ws);
+    {
+        let highlight = format!("{}{}", ColorFg::Black.encode(), ColorBg::Yellow.encode());
+        let out = out.lock().unwrap();
+        assert!(out.contains(&format!("{highlight}err{}", ColorFg::White.encode())));
+        assert!(!out.contains("/ERR"));
+    }
+
+    // Esc clears the search
+    process_input(&mut ws, &key(Key::Esc));
+    process_input(&mut ws, &chr('n'));
+    assert_eq!(8, ws.top_line);
+    assert_eq!(id::TBX_SCROLL, ws.focused_id);
+
+    // typed n/N are searched; Esc in the prompt cancels
+    process_input(&mut ws, &ctrl('F'));
+    process_input(&mut ws, &chr('N'));
+    process_input(&mut ws, &chr('e'));
+    assert_eq!(8, ws.top_line);
+    process_input(&mut ws, &key(Key::Esc));
+    process_input(&mut ws, &chr('N'));
+    assert_eq!(8, ws.top_line);
+
+    // found while typing, after wrapping around; the only match stays
+    process_input(&mut ws, &chr('/'));
+    process_input(&mut ws, &chr('1'));
+    assert_eq!(1, ws.top_line);
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &chr('n'));
+    assert_eq!(1, ws.top_line);
+}
+
+#[test]
+fn text_box_search_long_pattern() {
+    let _lock = lock();
+    let mut ws = MainState::with_lines(id::TBX_SCROLL, &["line"]);
+
+    // the prompt cursor stays within the widget
+    process_input(&mut ws, &chr('/'));
+    for _ in 0..254 {
+        process_input(&mut ws, &chr('x'));
+    }
+    let out = capture_output();
+    {
+        let mut term_guard = rtwins::TERM.try_lock().unwrap();
+        set_cursor_at(&mut term_guard, &mut ws, wgt(id::TBX_SCROLL));
+        term_guard.flush_buff();
+    }
+    process_input(&mut ws, &key(Key::Esc));
+    // bottom row, after the prompt limited to the widget width
+    assert_eq!("\x1B[10;41H", *out.lock().unwrap());
+}
@@ -0,0 +1,120 @@
+//! # RTWins TextEdit undo/redo tests
+
+extern crate rtwins;
+use rtwins::input::*;
+use rtwins::wgt::*;
+
+mod common;
+use common::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+fn type_text(ws: &mut MainState, txt: &str) {
+    for c in txt.chars() {
+        process_input(ws, &chr(c));
+    }
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn undo_redo() {
+    let _lock = lock();
+    let mut ws = MainState {
+        name: "abc".into(),
+        ..MainState::new(id::EDT_NAME)
+    };
+
+    process_input(&mut ws, &key(Key::Enter));
+    // typing is a single step, broken by the cursor move
+    type_text(&mut ws, "def");
+    process_input(&mut ws, &key(Key::Home));
+    type_text(&mut ws, "12");
+    process_input(&mut ws, &key(Key::End));
+    process_input(&mut ws, &key(Key::Backspace));
+    process_input(&mut ws, &key(Key::Backspace));
+
+    process_input(&mut ws, &ctrl('Z'));
+    process_input(&mut ws, &ctrl('Z'));
+    // cursor restored at the end
+    process_input(&mut ws, &chr('_'));
+    process_input(&mut ws, &ctrl('Z'));
+    process_input(&mut ws, &ctrl('Z'));
+    // cursor restored before "12"
+    process_input(&mut ws, &chr('_'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("_abcdef", ws.name);
+
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &ctrl('Z'));
+    process_input(&mut ws, &key(Key::Enter));
+    // history not available from the previous edit
+    assert_eq!("_abcdef", ws.name);
+
+    process_input(&mut ws, &key(Key::Enter));
+    type_text(&mut ws, "xy");
+    process_input(&mut ws, &ctrl('Z'));
+    process_input(&mut ws, &ctrl('Y'));
+    process_input(&mut ws, &ctrl('Y'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("_abcdefxy", ws.name);
+
+    // new change clears the redo
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &key(Key::Backspace));
+    process_input(&mut ws, &ctrl('Z'));
+    process_input(&mut ws, &chr('!'));
+    process_input(&mut ws, &ctrl('Y'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("_abcdefxy!", ws.name);
+}
+
+#[test]
+fn undo_selection_and_paste() {
+    let _lock = lock();
+    let mut ws = MainState {
+        name: "hello".into(),
+        ..MainState::new(id::EDT_NAME)
+    };
+
+    // replacing the selection is a separate step
+    process_input(&mut ws, &key(Key::Enter));
+    type_text(&mut ws, " w");
+    process_input(&mut ws, &ctrl('A'));
+    type_text(&mut ws, "bye");
+    process_input(&mut ws, &ctrl('Z'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("hello w", ws.name);
+
+    // paste starting the edit mode
+    ws.name = "id:".into();
+    process_input(
+        &mut ws,
+        &InputInfo {
+            evnt: InputEvent::Paste("42".into()),
+            ..Default::default()
+        },
+    );
+    process_input(&mut ws, &ctrl('Z'));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("id:", ws.name);
+}
+
+#[test]
+fn undo_history_limit() {
+    let _lock = lock();
+    let mut ws = MainState::new(id::EDT_NAME);
+
+    process_input(&mut ws, &key(Key::Enter));
+    for _ in 0..40 {
+        // each character a separate step
+        process_input(&mut ws, &chr('a'));
+        process_input(&mut ws, &key(Key::End));
+    }
+
+    for _ in 0..40 {
+        process_input(&mut ws, &ctrl('Z'));
+    }
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("a".repeat(8), ws.name);
+}
@@ -19,17 +19,18 @@ fn word_wrap() {
     let lines = linesrc.borrow();
 
     assert_eq!(
-        lines.get(0).unwrap(),
+        lines.first().unwrap(),
         String::new().append(esc::BOLD).append("🔶 Lorem ")
     );
     assert_eq!(lines.get(1).unwrap(), "ipsum ");
     assert_eq!(lines.get(2).unwrap(), "dolor sit");
     assert_eq!(lines.get(3).unwrap(), " amet, ");
-    assert_eq!(lines.get(4).unwrap(), "consectetur ");
-    assert_eq!(lines.get(5).unwrap(), "adipiscing ");
-    assert_eq!(lines.get(6).unwrap(), "elit. ");
+    assert_eq!(lines.get(4).unwrap(), "consectetu");
+    assert_eq!(lines.get(5).unwrap(), "r ");
+    assert_eq!(lines.get(6).unwrap(), "adipiscing ");
+    assert_eq!(lines.get(7).unwrap(), "elit. ");
     assert_eq!(
-        lines.get(7).unwrap(),
+        lines.get(8).unwrap(),
         String::new().append(esc::NORMAL).append("▄")
     );
 }
@@ -192,3 +193,45 @@ fn num_edit_limited() {
         assert_eq!("1", txt);
     }
 }
+
+#[test]
+fn word_wrap_trailing_words() {
+    let linesrc = utils::word_wrap(20, "Hello big world");
+    assert_eq!(*linesrc.borrow(), vec!["Hello big world"]);
+
+    let linesrc = utils::word_wrap(8, "Hello big world");
+    assert_eq!(*linesrc.borrow(), vec!["Hello ", "big ", "world"]);
+
+    let linesrc = utils::word_wrap(8, "Hello\n\nworld\n");
+    assert_eq!(*linesrc.borrow(), vec!["Hello", "", "world"]);
+}
+
+#[test]
+fn word_wrap_long_words() {
+    let linesrc = utils::word_wrap(4, "abcdefghij kl");
+    assert_eq!(*linesrc.borrow(), vec!["abcd", "efgh", "ij ", "kl"]);
+
+    // wide characters are not split
+    let linesrc = utils::word_wrap(3, "🔶🔶🔶");
+    assert_eq!(*linesrc.borrow(), vec!["🔶", "🔶", "🔶"]);
+
+    // ESC sequences are kept whole
+    let mut txt = String::new();
+    txt.append(esc::BOLD).append("abcdef");
+    let linesrc = utils::word_wrap(3, &txt);
+    let lines = linesrc.borrow();
+    assert_eq!(2, lines.len());
+    assert_eq!(&lines[0], String::new().append(esc::BOLD).append("abc"));
+    assert_eq!(lines[1], "def");
+}
+
+#[test]
+fn base64_encode() {
+    assert_eq!("", utils::base64_encode(b""));
+    assert_eq!("Zg==", utils::base64_encode(b"f"));
+    assert_eq!("Zm8=", utils::base64_encode(b"fo"));
+    assert_eq!("Zm9v", utils::base64_encode(b"foo"));
+    assert_eq!("Zm9vYmFy", utils::base64_encode(b"foobar"));
+    assert_eq!("xIXEmQ==", utils::base64_encode("ąę".as_bytes()));
+    assert_eq!("+/8=", utils::base64_encode(&[0xFB, 0xFF]));
+}
@@ -0,0 +1,150 @@
+//! # RTWins TextEdit validators tests
+
+extern crate rtwins;
+use rtwins::input::*;
+use rtwins::validator::*;
+use rtwins::wgt::*;
+
+mod common;
+use common::*;
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn numbers() {
+    let v = Validator::Int { min: -5, max: 100 };
+    assert!(v.validate("-5").is_ok());
+    assert!(v.validate("100").is_ok());
+    assert_eq!(Err("Value out of range"), v.validate("101"));
+    assert!(v.validate("").is_err());
+    assert!(v.validate("1a").is_err());
+    assert!(v.allows_input("-1"));
+    assert!(!v.allows_input("1.5"));
+
+    let v = Validator::Float { min: 0.0, max: 1.0 };
+    assert!(v.validate("0.25").is_ok());
+    assert!(v.validate("1e-3").is_ok());
+    assert!(v.validate("1.5").is_err());
+    assert!(v.validate("inf").is_err());
+    assert!(v.validate("NaN").is_err());
+}
+
+#[test]
+fn text_limits() {
+    assert!(Validator::MaxLen(3).validate("abc").is_ok());
+    assert!(Validator::MaxLen(3).validate("abcd").is_err());
+    assert!(!Validator::MaxLen(3).allows_input("ąęść"));
+
+    assert!(Validator::CharSet("abc").validate("cab").is_ok());
+    assert!(Validator::CharSet("abc").validate("cad").is_err());
+
+    const NAME: Validator = Validator::All(&[Validator::MaxLen(8), Validator::CharSet("xyz")]);
+    assert!(NAME.validate("xyz").is_ok());
+    assert!(NAME.validate("xyzxyzxyz").is_err());
+    assert!(NAME.validate("xyza").is_err());
+}
+
+#[test]
+fn addresses() {
+    assert!(Validator::Ipv4.validate("192.168.0.1").is_ok());
+    assert!(Validator::Ipv4.validate("255.255.255.255").is_ok());
+    assert!(Validator::Ipv4.validate("256.1.1.1").is_err());
+    assert!(Validator::Ipv4.validate("1.1.1").is_err());
+    assert!(Validator::Ipv4.validate("1..1.1").is_err());
+    assert!(Validator::Ipv4.validate("+1.1.1.1").is_err());
+
+    assert!(Validator::Mac.validate("01:23:45:67:89:aB").is_ok());
+    assert!(Validator::Mac.validate("01-23-45-67-89-AB").is_ok());
+    assert!(Validator::Mac.validate("01-23:45-67-89-AB").is_err());
+    assert!(Validator::Mac.validate("01:23:45:67:89").is_err());
+    assert!(Validator::Mac.validate("01:23:45:67:89:AG").is_err());
+
+    assert!(Validator::Hex.validate("0x1F").is_ok());
+    assert!(Validator::Hex.validate("dead").is_ok());
+    assert!(Validator::Hex.validate("0x").is_err());
+    assert!(Validator::Hex.validate("12g").is_err());
+}
+
+#[test]
+fn patterns() {
+    assert!(pattern_match("abc", "abc"));
+    assert!(!pattern_match("abc", "abcd"));
+    assert!(pattern_match("a.c", "axc"));
+    assert!(pattern_match("[A-Z][a-z]*", "Hello"));
+    assert!(!pattern_match("[A-Z][a-z]*", "hello"));
+    assert!(pattern_match("\\d+-\\d\\d", "123-45"));
+    assert!(!pattern_match("\\d+-\\d\\d", "-45"));
+    assert!(pattern_match("colou?r", "color"));
+    assert!(pattern_match("colou?r", "colour"));
+    assert!(pattern_match("[^0-9]*", "abc"));
+    assert!(!pattern_match("[^0-9]*", "ab1"));
+    assert!(pattern_match("[a-]+", "a-a"));
+    assert!(pattern_match("\\w+\\.txt", "file_1.txt"));
+    assert!(!pattern_match("\\w+\\.txt", "file_1xtxt"));
+    // backtracking
+    assert!(pattern_match("a*ab", "aaab"));
+    // malformed
+    assert!(!pattern_match("[abc", "a"));
+    assert!(!pattern_match("*a", "a"));
+}
+
+#[test]
+fn text_edit_validation() {
+    let _lock = lock();
+    let mut ws = MainState::new(id::EDT_PORT);
+
+    // enter edit mode
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &chr('7'));
+    // rejected
+    process_input(&mut ws, &chr('x'));
+    process_input(&mut ws, &chr('0'));
+    process_input(&mut ws, &chr('0'));
+    process_input(&mut ws, &chr('0'));
+    process_input(&mut ws, &chr('0'));
+    assert_eq!(
+        vec![None, None, None, None, Some("Value out of range")],
+        ws.errors
+    );
+
+    // refused to commit
+    process_input(&mut ws, &key(Key::Enter));
+    assert!(ws.port.is_empty());
+    assert_eq!(Some("Value out of range"), *ws.errors.last().unwrap());
+
+    process_input(&mut ws, &key(Key::Backspace));
+    assert_eq!(None, *ws.errors.last().unwrap());
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("7000", ws.port);
+}
+
+#[test]
+fn text_edit_paste_validation() {
+    let _lock = lock();
+    let mut ws = MainState {
+        port: "80".into(),
+        ..MainState::new(id::EDT_PORT)
+    };
+
+    // paste entering the edit mode is rejected
+    process_input(&mut ws, &paste("8x"));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("80", ws.port);
+    // the original text was committed
+    assert_eq!(vec![None], ws.errors);
+
+    // paste in the edit mode is rejected
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &paste("y1"));
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("80", ws.port);
+
+    // allowed paste is validated
+    process_input(&mut ws, &key(Key::Enter));
+    process_input(&mut ws, &paste("000"));
+    assert_eq!(Some("Value out of range"), *ws.errors.last().unwrap());
+    process_input(&mut ws, &key(Key::Backspace));
+    assert_eq!(None, *ws.errors.last().unwrap());
+    process_input(&mut ws, &key(Key::Enter));
+    assert_eq!("8000", ws.port);
+}
@@ -1,5 +1,11 @@
 //! # RTWins Widget tests
 
+#![allow(
+    clippy::empty_line_after_doc_comments,
+    clippy::needless_borrow,
+    clippy::unnecessary_mut_passed
+)]
+
 extern crate rtwins;
 use rtwins::colors::*;
 use rtwins::common::*;
@@ -61,6 +67,7 @@ const PAGE_VER_CHILDREN: &[Widget] = &[
                     title: "FwVer: 1.1",
                     fg_color: ColorFg::YellowIntense,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -72,6 +79,7 @@ const PAGE_VER_CHILDREN: &[Widget] = &[
                     title: "",
                     fg_color: ColorFg::Blue,
                     bg_color: ColorBg::Inherit,
+                    markup: false,
                 }.into(),
                 ..Widget::cdeflt()
             },
@@ -0,0 +1,738 @@
+//! # RTWins Window Manager tests
+
+extern crate rtwins;
+use rtwins::colors::*;
+use rtwins::common::*;
+use rtwins::dialog::{id, DialogResult, DialogState};
+use rtwins::input::*;
+use rtwins::wgt::*;
+use rtwins::wnd_manager::*;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+// ---------------------------------------------------------------------------------------------- //
+
+mod idm {
+    use rtwins::wgt::{WId, WIDGET_ID_NONE};
+
+    #[rustfmt::skip]
+    rtwins::generate_ids!(
+        WND_MAIN
+            BTN_OK
+    );
+}
+
+mod idt {
+    use rtwins::wgt::{WId, WIDGET_ID_NONE};
+
+    #[rustfmt::skip]
+    rtwins::generate_ids!(
+        WND_TOOL
+            BTN_TOOL
+    );
+}
+
+#[rustfmt::skip]
+const WINDOW_MAIN: Widget = Widget {
+    id: idm::WND_MAIN,
+    link: Link::cdeflt(),
+    coord: Coord { col: 1, row: 1 },
+    size: Size { width: 60, height: 20 },
+    prop: prop::Window {
+        title: "Main",
+        fg_color: ColorFg::White,
+        bg_color: ColorBg::Blue,
+        is_popup: false,
+    }.into(),
+    children: &[
+        Widget {
+            id: idm::BTN_OK,
+            coord: Coord { col: 2, row: 2 },
+            prop: prop::Button {
+                text: "OK",
+                fg_color: ColorFg::White,
+                bg_color: ColorBg::Green,
+                style: ButtonStyle::Simple,
+            }.into(),
+            ..Widget::cdeflt()
+        },
+    ]
+};
+
+const WND_MAIN_WGTS: [Widget; transform::tree_wgt_count(&WINDOW_MAIN)] =
+    transform::tree_to_array(&WINDOW_MAIN);
+
+#[derive(Default)]
+struct MainState {
+    focused_id: WId,
+    results: Vec<(WId, DialogResult)>,
+    term_focus: Option<bool>,
+}
+
+impl WindowState for MainState {
+    fn on_window_unhandled_input_evt(&mut self, _wgt: &Widget, ii: &InputInfo) -> bool {
+        if let InputEvent::Focus(focused) = ii.evnt {
+            self.term_focus = Some(focused);
+            return true;
+        }
+        false
+    }
+
+    fn on_modal_result(&mut self, wnd_id: WId, result: &dyn std::any::Any) {
+        if let Some(result) = result.downcast_ref::<DialogResult>() {
+            self.results.push((wnd_id, result.clone()));
+        }
+    }
+
+    fn get_focused_id(&mut self) -> WId {
+        self.focused_id
+    }
+
+    fn set_focused_id(&mut self, wid: WId) {
+        self.focused_id = wid;
+    }
+
+    fn get_widgets(&self) -> &'static [Widget] {
+        &WND_MAIN_WGTS
+    }
+
+    fn get_window_coord(&mut self) -> Coord {
+        WND_MAIN_WGTS[0].coord
+    }
+}
+
+#[rustfmt::skip]
+const WINDOW_TOOL: Widget = Widget {
+    id: idt::WND_TOOL,
+    link: Link::cdeflt(),
+    coord: Coord { col: 0, row: 0 },
+    size: Size { width: 20, height: 6 },
+    prop: prop::Window {
+        title: "Tool",
+        fg_color: ColorFg::White,
+        bg_color: ColorBg::Green,
+        is_popup: false,
+    }.into(),
+    children: &[
+        Widget {
+            id: idt::BTN_TOOL,
+            coord: Coord { col: 2, row: 2 },
+            prop: prop::Button {
+                text: "Tool",
+                fg_color: ColorFg::White,
+                bg_color: ColorBg::Blue,
+                style: ButtonStyle::Simple,
+            }.into(),
+            ..Widget::cdeflt()
+        },
+    ]
+};
+
+const WND_TOOL_WGTS: [Widget; transform::tree_wgt_count(&WINDOW_TOOL)] =
+    transform::tree_to_array(&WINDOW_TOOL);
+
+#[derive(Default)]
+struct ToolState {
+    coord: Coord,
+    size: Size,
+    focused_id: WId,
+    clicks: u16,
+}
+
+impl WindowState for ToolState {
+    fn on_button_click(&mut self, _wgt: &Widget, _ii: &InputInfo) {
+        self.clicks += 1;
+    }
+
+    fn get_focused_id(&mut self) -> WId {
+        self.focused_id
+    }
+
+    fn set_focused_id(&mut self, wid: WId) {
+        self.focused_id = wid;
+    }
+
+    fn get_widgets(&self) -> &'static [Widget] {
+        &WND_TOOL_WGTS
+    }
+
+    fn is_movable(&self) -> bool {
+        true
+    }
+
+    fn is_resizable(&self) -> bool {
+        true
+    }
+
+    fn get_window_coord(&mut self) -> Coord {
+        self.coord
+    }
+
+    fn get_window_size(&mut self) -> Size {
+        self.size
+    }
+
+    fn set_window_coord(&mut self, coord: Coord) {
+        self.coord = coord;
+    }
+
+    fn set_window_size(&mut self, size: Size) {
+        self.size = size;
+    }
+}
+
+#[derive(Default)]
+struct TestWndMngr {
+    visible: Vec<WId>,
+    main: MainState,
+    dlg1: DialogState,
+    dlg2: DialogState,
+    tool1: ToolState,
+    tool2: ToolState,
+}
+
+impl TestWndMngr {
+    const MAIN: WId = 0;
+    const DLG1: WId = 1;
+    const DLG2: WId = 2;
+    const TOOL1: WId = 3;
+    const TOOL2: WId = 4;
+}
+
+impl WindowManager for TestWndMngr {
+    fn get_ref(&self, wnd_id: WId) -> Option<&dyn WindowState> {
+        match wnd_id {
+            Self::MAIN => Some(&self.main),
+            Self::DLG1 => Some(&self.dlg1),
+            Self::DLG2 => Some(&self.dlg2),
+            Self::TOOL1 => Some(&self.tool1),
+            Self::TOOL2 => Some(&self.tool2),
+            _ => None,
+        }
+    }
+
+    fn get_mut(&mut self, wnd_id: WId) -> Option<&mut dyn WindowState> {
+        match wnd_id {
+            Self::MAIN => Some(&mut self.main),
+            Self::DLG1 => Some(&mut self.dlg1),
+            Self::DLG2 => Some(&mut self.dlg2),
+            Self::TOOL1 => Some(&mut self.tool1),
+            Self::TOOL2 => Some(&mut self.tool2),
+            _ => None,
+        }
+    }
+
+    fn get_visible(&self) -> &[WId] {
+        &self.visible[..]
+    }
+
+    fn get_visible_mut(&mut self) -> &mut Vec<WId> {
+        &mut self.visible
+    }
+}
+
+/// Window manager tests share the global widgets state, so they can't run in parallel
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Collects the terminal output, with the timestamp set by the test
+#[derive(Default)]
+struct PalClock {
+    out: Arc<Mutex<String>>,
+    now: Arc<AtomicU32>,
+}
+
+impl rtwins::pal::Pal for PalClock {
+    fn write_char_n(&mut self, c: char, repeat: i16) {
+        self.out
+            .lock()
+            .unwrap()
+            .push_str(&c.to_string().repeat(repeat as usize));
+    }
+
+    fn write_str_n(&mut self, s: &str, repeat: i16) {
+        self.out
+            .lock()
+            .unwrap()
+            .push_str(&s.repeat(repeat as usize));
+    }
+
+    fn get_timestamp_ms(&self) -> u32 {
+        self.now.load(Ordering::Relaxed)
+    }
+}
+
+/// Top-right corner of the 80 columns screen
+const ANCHOR: Coord = Coord { col: 80, row: 1 };
+
+fn mouse_evt(evt: MouseEvent, col: u8, row: u8) -> InputInfo {
+    InputInfo {
+        evnt: InputEvent::Mouse(MouseInfo {
+            evt,
+            col: col as u16,
+            row: row as u16,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn mouse_click(col: u8, row: u8) -> InputInfo {
+    mouse_evt(MouseEvent::ButtonLeft, col, row)
+}
+
+// ---------------------------------------------------------------------------------------------- //
+
+#[test]
+fn toasts_push_and_expire() {
+    let mut tq = ToastQueue::default();
+    assert!(tq.is_empty());
+
+    tq.push("first", Severity::Info, 1000, 100);
+    tq.push("second", Severity::Error, 3000, 200);
+    assert_eq!(2, tq.len());
+
+    assert!(tq.remove_expired(1099, ANCHOR).is_empty());
+    assert_eq!(2, tq.len());
+
+    assert!(!tq.remove_expired(1100, ANCHOR).is_empty());
+    assert_eq!(1, tq.len());
+    assert_eq!("second", tq.as_slice()[0].text);
+    assert_eq!(Severity::Error, tq.as_slice()[0].severity);
+
+    assert!(!tq.remove_expired(3200, ANCHOR).is_empty());
+    assert!(tq.is_empty());
+}
+
+#[test]
+fn toasts_limit() {
+    let mut tq = ToastQueue::default();
+
+    for i in 0..ToastQueue::MAX_TOASTS + 2 {
+        tq.push(&i.to_string(), Severity::Warning, 1000, 0);
+    }
+
+    // the oldest ones are dropped
+    assert_eq!(ToastQueue::MAX_TOASTS, tq.len());
+    assert_eq!("2", tq.as_slice()[0].text);
+}
+
+#[test]
+fn toasts_timer_overflow() {
+    let mut tq = ToastQueue::default();
+    tq.push("wrap", Severity::Success, 100, u32::MAX - 10);
+
+    assert!(tq.remove_expired(u32::MAX, ANCHOR).is_empty());
+    assert!(tq.remove_expired(88, ANCHOR).is_empty());
+    assert!(!tq.remove_expired(89, ANCHOR).is_empty());
+}
+
+#[test]
+fn toasts_untimed() {
+    let mut tq = ToastQueue::default();
+    tq.push_untimed("later", Severity::Info, 100);
+    assert!(!tq.as_slice()[0].is_expired(u32::MAX));
+
+    // timer started at the first check
+    assert!(tq.remove_expired(500, ANCHOR).is_empty());
+    assert_eq!(Some(500), tq.as_slice()[0].shown_at);
+    assert!(!tq.remove_expired(600, ANCHOR).is_empty());
+}
+
+#[test]
+fn toasts_notify() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    TOASTS.try_lock().unwrap().clear();
+
+    // top-right corner of the screen
+    rtwins::TERM.try_lock().unwrap().screen_size = Size::new(120, 40);
+    assert_eq!(Some(Coord::new(120, 1)), wm.get_toasts_anchor());
+    rtwins::TERM.try_lock().unwrap().screen_size = Size::new(0, 0);
+    assert_eq!(Some(Coord::new(1, 1)), wm.get_toasts_anchor());
+
+    // not lost when the terminal is busy
+    {
+        let _term_guard = rtwins::TERM.try_lock().unwrap();
+        wm.notify("busy", Severity::Warning, 1000);
+    }
+    assert_eq!(1, TOASTS.try_lock().unwrap().len());
+    assert_eq!(None, TOASTS.try_lock().unwrap().as_slice()[0].shown_at);
+
+    wm.toasts_tick();
+    assert_eq!(1, TOASTS.try_lock().unwrap().len());
+    assert!(TOASTS.try_lock().unwrap().as_slice()[0].shown_at.is_some());
+
+    TOASTS.try_lock().unwrap().clear();
+    rtwins::TERM.try_lock().unwrap().screen_size = Size::new(80, 24);
+}
+
+#[test]
+fn toasts_layout_removed() {
+    let mut tq = ToastQueue::default();
+    tq.push("older one", Severity::Info, 1000, 0);
+    tq.push("new", Severity::Info, 100, 0);
+
+    // the newest on top
+    assert_eq!(
+        vec![Rect::new(68, 4, 13, 3), Rect::new(74, 1, 7, 3)],
+        get_toasts_layout(ANCHOR, tq.as_slice())
+    );
+
+    // the older one moved up
+    assert_eq!(
+        vec![Rect::new(74, 1, 7, 3), Rect::new(68, 4, 13, 3)],
+        tq.remove_expired(100, ANCHOR)
+    );
+    assert_eq!(
+        vec![Rect::new(68, 1, 13, 3)],
+        get_toasts_layout(ANCHOR, tq.as_slice())
+    );
+}
+
+#[test]
+fn toasts_tick_clears_area() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    TOASTS.try_lock().unwrap().clear();
+    let pal = PalClock::default();
+    let out = pal.out.clone();
+    let now = pal.now.clone();
+    now.store(1000, Ordering::Relaxed);
+    {
+        let mut term_guard = rtwins::TERM.try_lock().unwrap();
+        term_guard.pal = Box::new(pal);
+        term_guard.screen_size = Size::new(80, 24);
+    }
+
+    wm.show(TestWndMngr::MAIN);
+    wm.notify("bye", Severity::Info, 100);
+    assert!(out.lock().unwrap().contains("bye"));
+
+    now.store(1099, Ordering::Relaxed);
+    out.lock().unwrap().clear();
+    wm.toasts_tick();
+    assert!(out.lock().unwrap().is_empty());
+
+    // the toast outside the main window is cleared
+    now.store(1100, Ordering::Relaxed);
+    wm.toasts_tick();
+    assert!(TOASTS.try_lock().unwrap().is_empty());
+    {
+        let out = out.lock().unwrap();
+        for row in 1..=3 {
+            assert!(out.contains(&format!("\x1B[{row};74H       ")));
+        }
+        assert!(!out.contains("bye"));
+    }
+
+    wm.hide(TestWndMngr::MAIN);
+    rtwins::TERM.try_lock().unwrap().pal = Box::new(PalClock::default());
+}
+
+#[test]
+fn modal_stack() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.dlg1.center_on(Rect::new(1, 1, 60, 20));
+    wm.dlg2.center_on(Rect::new(1, 1, 60, 20));
+    wm.show(TestWndMngr::MAIN);
+    wm.main.focused_id = idm::BTN_OK;
+
+    // first modal
+    wm.dlg1.confirm("Question", "Are you sure?");
+    wm.show_modal(TestWndMngr::DLG1);
+    assert!(wm.is_top(TestWndMngr::DLG1));
+    assert!(wm.is_modal(TestWndMngr::DLG1));
+    assert!(!wm.is_modal(TestWndMngr::MAIN));
+
+    // clicks outside of the modal are swallowed
+    wm.main.focused_id = WIDGET_ID_NONE;
+    assert!(wm.process_input(&mouse_click(1, 1)));
+    assert!(wm.is_top(TestWndMngr::DLG1));
+
+    // nested modal
+    wm.dlg2.prompt_text("Text", "Name:", "Bob");
+    wm.show_modal(TestWndMngr::DLG2);
+    assert_eq!(&[0, 1, 2], wm.get_visible());
+
+    // result delivered to the opener, which is the first modal
+    wm.close_modal(
+        TestWndMngr::DLG2,
+        Box::new(DialogResult::Text("Bob".into())),
+    );
+    assert_eq!(&[0, 1], wm.get_visible());
+    assert!(wm.main.results.is_empty());
+    assert_eq!(id::BTN_NO, wm.dlg1.get_focused_id());
+
+    // the dialog closes itself on the next input
+    wm.dlg1.close(DialogResult::Yes);
+    wm.process_input(&InputInfo::default());
+    assert_eq!(&[0], wm.get_visible());
+    assert!(!wm.is_modal(TestWndMngr::DLG1));
+    assert_eq!(
+        vec![(TestWndMngr::DLG1, DialogResult::Yes)],
+        wm.main.results
+    );
+    // focus restored
+    assert_eq!(idm::BTN_OK, wm.main.focused_id);
+}
+
+#[test]
+fn mouse_routing_z_order() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.tool1.coord = Coord::new(5, 3);
+    wm.tool2.coord = Coord::new(15, 5);
+    wm.show(TestWndMngr::MAIN);
+    wm.show(TestWndMngr::TOOL1);
+    wm.show(TestWndMngr::TOOL2);
+
+    // hit-test from the top-most window
+    assert_eq!(Some(TestWndMngr::TOOL2), wm.find_window_at(16, 6));
+    assert_eq!(Some(TestWndMngr::TOOL1), wm.find_window_at(6, 4));
+    assert_eq!(Some(TestWndMngr::MAIN), wm.find_window_at(50, 20));
+    assert_eq!(None, wm.find_window_at(100, 100));
+
+    // wheel goes to the window under the cursor, without raising it
+    wm.process_input(&mouse_evt(MouseEvent::WheelDown, 7, 5));
+    assert_eq!(&[0, 3, 4], wm.get_visible());
+
+    // click on the button of the lower window raises the window
+    wm.process_input(&mouse_click(8, 5));
+    assert_eq!(&[0, 4, 3], wm.get_visible());
+    assert_eq!(idt::BTN_TOOL, wm.tool1.focused_id);
+
+    // the release is given to the window that received the press
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 8, 5));
+    assert_eq!(1, wm.tool1.clicks);
+    assert_eq!(0, wm.tool2.clicks);
+
+    // tool2 button is now partially covered by tool1
+    wm.process_input(&mouse_click(20, 7));
+    assert!(wm.is_top(TestWndMngr::TOOL1));
+    wm.process_input(&mouse_click(30, 7));
+    assert!(wm.is_top(TestWndMngr::TOOL2));
+    assert_eq!(&[0, 3, 4], wm.get_visible());
+}
+
+#[test]
+fn window_move_and_resize() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.tool1.coord = Coord::new(5, 3);
+    wm.show(TestWndMngr::MAIN);
+    wm.show(TestWndMngr::TOOL1);
+    assert_eq!(
+        Some(Rect::new(5, 3, 20, 6)),
+        wm.get_window_rect(TestWndMngr::TOOL1)
+    );
+
+    // drag the title bar
+    wm.process_input(&mouse_click(10, 3));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 20, 8));
+    assert_eq!(Coord::new(15, 8), wm.tool1.coord);
+
+    // drag the bottom-right corner
+    wm.process_input(&mouse_click(34, 13));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 38, 15));
+    assert_eq!(Size::new(24, 8), wm.tool1.size);
+    assert_eq!(Some(TestWndMngr::TOOL1), wm.find_window_at(38, 15));
+
+    // limits
+    wm.process_input(&mouse_click(38, 15));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 0, 0));
+    assert_eq!(Size::new(10, 3), wm.tool1.size);
+    wm.process_input(&mouse_click(20, 8));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 0, 0));
+    assert_eq!(Coord::new(1, 1), wm.tool1.coord);
+
+    // click inside the window does not move it
+    wm.process_input(&mouse_click(3, 2));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 8, 2));
+    assert_eq!(Coord::new(1, 1), wm.tool1.coord);
+
+    // the main window is not movable
+    wm.process_input(&mouse_click(30, 1));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 35, 5));
+    assert_eq!(
+        Some(Coord::new(1, 1)),
+        wm.get_window_rect(TestWndMngr::MAIN).map(|r| r.coord)
+    );
+}
+
+#[test]
+fn window_drag_to_screen_edge() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.tool1.coord = Coord::new(5, 3);
+    wm.show(TestWndMngr::MAIN);
+    wm.show(TestWndMngr::TOOL1);
+
+    // the window must fit the u8 coordinates
+    wm.process_input(&mouse_click(10, 3));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 255, 255));
+    assert_eq!(Coord::new(236, 250), wm.tool1.coord);
+    assert_eq!(Some(TestWndMngr::TOOL1), wm.find_window_at(255, 255));
+
+    // the bottom-right corner at the edge
+    wm.process_input(&mouse_click(255, 255));
+    wm.process_input(&mouse_evt(MouseEvent::Drag(MouseButton::Left), 255, 255));
+    assert_eq!(
+        Some(Rect::new(236, 250, 20, 6)),
+        wm.get_window_rect(TestWndMngr::TOOL1)
+    );
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 250, 254));
+    assert_eq!(Size::new(15, 5), wm.tool1.size);
+}
+
+#[test]
+fn mouse_beyond_coord_range() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.tool1.coord = Coord::new(236, 3);
+    wm.show(TestWndMngr::TOOL1);
+    wm.show(TestWndMngr::MAIN);
+
+    // not folded onto the column 255
+    let ii = InputInfo {
+        evnt: InputEvent::Mouse(MouseInfo {
+            evt: MouseEvent::ButtonLeft,
+            col: 300,
+            row: 3,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert!(!wm.process_input(&ii));
+    assert!(wm.is_top(TestWndMngr::MAIN));
+
+    wm.process_input(&mouse_click(255, 3));
+    assert!(wm.is_top(TestWndMngr::TOOL1));
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 255, 3));
+}
+
+#[test]
+fn window_live_drag() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.tool1.coord = Coord::new(5, 3);
+    wm.show(TestWndMngr::MAIN);
+    wm.show(TestWndMngr::TOOL1);
+
+    wm.process_input(&mouse_click(10, 3));
+    wm.process_input(&mouse_evt(MouseEvent::Drag(MouseButton::Left), 12, 4));
+    assert_eq!(Coord::new(7, 4), wm.tool1.coord);
+    wm.process_input(&mouse_evt(MouseEvent::Drag(MouseButton::Left), 14, 6));
+    assert_eq!(Coord::new(9, 6), wm.tool1.coord);
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 14, 6));
+    assert_eq!(Coord::new(9, 6), wm.tool1.coord);
+
+    // drag after release does nothing
+    wm.process_input(&mouse_evt(MouseEvent::Drag(MouseButton::Left), 20, 10));
+    assert_eq!(Coord::new(9, 6), wm.tool1.coord);
+}
+
+#[test]
+fn list_box_drag() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    let items = (0..10).map(|i| i.to_string()).collect();
+    wm.dlg1.center_on(Rect::new(1, 1, 60, 20));
+    wm.dlg1.select_item("Pick", "", items, 0);
+    wm.show(TestWndMngr::MAIN);
+    wm.show_modal(TestWndMngr::DLG1);
+
+    let lbx = find_by_id(wm.dlg1.get_widgets(), id::LBX_ITEMS).unwrap();
+    let lbx_coord = get_screen_coord(&mut wm.dlg1, lbx);
+    let sel_idx = |wm: &mut TestWndMngr| {
+        let mut lbs = Default::default();
+        wm.dlg1.get_list_box_state(lbx, &mut lbs);
+        lbs.sel_idx
+    };
+
+    wm.process_input(&mouse_click(lbx_coord.col + 2, lbx_coord.row + 1));
+    assert_eq!(0, sel_idx(&mut wm));
+
+    // drag over the items, even outside of the modal window
+    let drag = MouseEvent::Drag(MouseButton::Left);
+    wm.process_input(&mouse_evt(drag, lbx_coord.col + 2, lbx_coord.row + 3));
+    assert_eq!(2, sel_idx(&mut wm));
+    wm.process_input(&mouse_evt(drag, 1, lbx_coord.row + lbx.size.height + 5));
+    assert_eq!(3, sel_idx(&mut wm));
+    wm.process_input(&mouse_evt(drag, 1, 1));
+    assert_eq!(2, sel_idx(&mut wm));
+
+    wm.process_input(&mouse_evt(MouseEvent::ButtonReleased, 1, 1));
+    wm.process_input(&mouse_evt(drag, lbx_coord.col + 2, lbx_coord.row + 1));
+    assert_eq!(2, sel_idx(&mut wm));
+    wm.close_modal(TestWndMngr::DLG1, Box::new(DialogResult::Cancel));
+}
+
+#[test]
+fn text_edit_paste() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.dlg1.prompt_text("Text", "Name:", "id");
+    wm.show(TestWndMngr::MAIN);
+    wm.show_modal(TestWndMngr::DLG1);
+
+    let paste = |text: &str| InputInfo {
+        evnt: InputEvent::Paste(text.into()),
+        ..Default::default()
+    };
+
+    // starts editing; control characters do not leak as the keys
+    assert!(wm.process_input(&paste(":\t12\n34\r\n")));
+    assert!(wm.is_visible(TestWndMngr::DLG1));
+    assert!(wm.process_input(&paste("\x1B56")));
+
+    let enter = InputInfo {
+        evnt: InputEvent::Key(Key::Enter),
+        kmod: KeyMod {
+            mask: KEY_MOD_SPECIAL,
+        },
+        ..Default::default()
+    };
+    wm.process_input(&enter);
+    assert!(!wm.is_visible(TestWndMngr::DLG1));
+    assert_eq!(
+        vec![(TestWndMngr::DLG1, DialogResult::Text("id: 12 3456".into()))],
+        wm.main.results
+    );
+}
+
+#[test]
+fn modal_without_opener() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.dlg1.center_on(Rect::new(1, 1, 60, 20));
+
+    // modal, even if there is no window to return the result to
+    wm.dlg1.confirm("Question", "Are you sure?");
+    wm.show_modal(TestWndMngr::DLG1);
+    assert!(wm.is_top(TestWndMngr::DLG1));
+    assert!(wm.is_modal(TestWndMngr::DLG1));
+
+    wm.close_modal(TestWndMngr::DLG1, Box::new(DialogResult::Yes));
+    assert!(!wm.is_visible(TestWndMngr::DLG1));
+    assert!(!wm.is_modal(TestWndMngr::DLG1));
+    assert!(wm.main.results.is_empty());
+}
+
+#[test]
+fn terminal_focus() {
+    let _serial = SERIAL.lock().unwrap_or_else(|err| err.into_inner());
+    let mut wm = TestWndMngr::default();
+    wm.show(TestWndMngr::MAIN);
+
+    let focus = |focused| InputInfo {
+        evnt: InputEvent::Focus(focused),
+        ..Default::default()
+    };
+
+    assert!(wm.process_input(&focus(false)));
+    assert_eq!(Some(false), wm.main.term_focus);
+    assert!(wm.process_input(&focus(true)));
+    assert_eq!(Some(true), wm.main.term_focus);
+}